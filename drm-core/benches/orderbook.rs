@@ -0,0 +1,117 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use drm_core::{BookSide, L2Book, PriceLevel};
+
+const TICK: f64 = 0.001;
+
+fn make_levels(depth: usize, start: f64, step: f64) -> Vec<PriceLevel> {
+    (0..depth)
+        .map(|i| PriceLevel::new(start + step * i as f64, 100.0 + i as f64))
+        .collect()
+}
+
+/// Deterministic stream of `(side, price, size)` updates spread over the book.
+fn make_deltas(depth: usize, count: usize) -> Vec<(BookSide, f64, f64)> {
+    (0..count)
+        .map(|i| {
+            let level = (i * 7919) % depth;
+            let size = if i % 5 == 0 { 0.0 } else { (i % 300) as f64 };
+            if i % 2 == 0 {
+                (BookSide::Bid, 0.499 - TICK * level as f64, size)
+            } else {
+                (BookSide::Ask, 0.501 + TICK * level as f64, size)
+            }
+        })
+        .collect()
+}
+
+/// Baseline: what the websocket clients did before `L2Book` - patch the level
+/// in a `Vec` and re-sort the whole side on every update.
+fn apply_vec(bids: &mut Vec<PriceLevel>, asks: &mut Vec<PriceLevel>, delta: (BookSide, f64, f64)) {
+    let (side, price, size) = delta;
+    let levels = match side {
+        BookSide::Bid => &mut *bids,
+        BookSide::Ask => &mut *asks,
+    };
+
+    match levels
+        .iter()
+        .position(|l| (l.price - price).abs() < TICK / 2.0)
+    {
+        Some(idx) if size <= 0.0 => {
+            levels.remove(idx);
+        }
+        Some(idx) => levels[idx].size = size,
+        None if size > 0.0 => levels.push(PriceLevel::new(price, size)),
+        None => {}
+    }
+
+    bids.sort_by(|a, b| {
+        b.price
+            .partial_cmp(&a.price)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    asks.sort_by(|a, b| {
+        a.price
+            .partial_cmp(&b.price)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+fn bench_apply_delta(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply_delta");
+
+    for depth in [50usize, 200, 1000] {
+        let bids = make_levels(depth, 0.499 - TICK * (depth - 1) as f64, TICK);
+        let asks = make_levels(depth, 0.501, TICK);
+        let deltas = make_deltas(depth, 1000);
+
+        group.bench_with_input(BenchmarkId::new("vec_resort", depth), &depth, |b, _| {
+            b.iter(|| {
+                let mut bids = bids.clone();
+                let mut asks = asks.clone();
+                for delta in &deltas {
+                    apply_vec(&mut bids, &mut asks, *delta);
+                }
+                black_box((bids.first().copied(), asks.first().copied()))
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("l2_book", depth), &depth, |b, _| {
+            let mut base = L2Book::with_tick_size("bench", TICK);
+            base.apply_snapshot(bids.iter().copied(), asks.iter().copied());
+            b.iter(|| {
+                let mut book = base.clone();
+                for &(side, price, size) in &deltas {
+                    book.apply_delta(side, price, size);
+                }
+                black_box((book.best_bid_level(), book.best_ask_level()))
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_snapshot(c: &mut Criterion) {
+    let mut group = c.benchmark_group("to_orderbook");
+
+    for depth in [50usize, 200, 1000] {
+        let mut book = L2Book::with_tick_size("bench", TICK);
+        book.apply_snapshot(
+            make_levels(depth, 0.499 - TICK * (depth - 1) as f64, TICK),
+            make_levels(depth, 0.501, TICK),
+        );
+
+        group.bench_with_input(BenchmarkId::new("full", depth), &book, |b, book| {
+            b.iter(|| black_box(book.to_orderbook()))
+        });
+        group.bench_with_input(BenchmarkId::new("top_10", depth), &book, |b, book| {
+            b.iter(|| black_box(book.to_orderbook_depth(10)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_apply_delta, bench_snapshot);
+criterion_main!(benches);
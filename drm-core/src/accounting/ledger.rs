@@ -0,0 +1,506 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+
+use crate::error::DrmError;
+use crate::models::{Order, OrderSide, Position};
+
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostBasisMethod {
+    #[default]
+    Fifo,
+    AverageCost,
+}
+
+/// One execution against a market outcome.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub timestamp: DateTime<Utc>,
+    pub market_id: String,
+    pub outcome: String,
+    pub side: OrderSide,
+    pub price: f64,
+    pub size: f64,
+    #[serde(default)]
+    pub fee: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<String>,
+}
+
+impl Fill {
+    /// Builds a fill from an order update, e.g. the `fill_size` reported by
+    /// [`OrderTracker`](crate::strategy::OrderTracker) callbacks. Assumes the
+    /// fill happened at the order's limit price.
+    pub fn from_order(order: &Order, size: f64, fee: f64) -> Self {
+        Self {
+            timestamp: order.updated_at.unwrap_or_else(Utc::now),
+            market_id: order.market_id.clone(),
+            outcome: order.outcome.clone(),
+            side: order.side,
+            price: order.price,
+            size,
+            fee,
+            order_id: Some(order.id.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lot {
+    pub size: f64,
+    pub price: f64,
+    pub opened_at: DateTime<Utc>,
+}
+
+/// Holdings and running PnL for one `(market_id, outcome)`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionLedger {
+    pub market_id: String,
+    pub outcome: String,
+    pub lots: VecDeque<Lot>,
+    pub realized_pnl: f64,
+    pub fees: f64,
+    /// Cash received when the market resolved.
+    pub settlement_payout: f64,
+    pub settled: bool,
+    /// Last mark used for unrealized PnL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mark_price: Option<f64>,
+}
+
+impl PositionLedger {
+    fn new(market_id: &str, outcome: &str) -> Self {
+        Self {
+            market_id: market_id.to_string(),
+            outcome: outcome.to_string(),
+            lots: VecDeque::new(),
+            realized_pnl: 0.0,
+            fees: 0.0,
+            settlement_payout: 0.0,
+            settled: false,
+            mark_price: None,
+        }
+    }
+
+    pub fn size(&self) -> f64 {
+        self.lots.iter().map(|l| l.size).sum()
+    }
+
+    pub fn cost_basis(&self) -> f64 {
+        self.lots.iter().map(|l| l.size * l.price).sum()
+    }
+
+    pub fn average_price(&self) -> f64 {
+        let size = self.size();
+        if size <= EPSILON {
+            return 0.0;
+        }
+        self.cost_basis() / size
+    }
+
+    /// Unrealized PnL at the last mark; zero when never marked.
+    pub fn unrealized_pnl(&self) -> f64 {
+        match self.mark_price {
+            Some(mark) => self.size() * mark - self.cost_basis(),
+            None => 0.0,
+        }
+    }
+
+    fn buy(&mut self, method: CostBasisMethod, size: f64, price: f64, at: DateTime<Utc>) {
+        match (method, self.lots.front_mut()) {
+            (CostBasisMethod::AverageCost, Some(lot)) => {
+                let total = lot.size + size;
+                lot.price = (lot.size * lot.price + size * price) / total;
+                lot.size = total;
+            }
+            _ => self.lots.push_back(Lot {
+                size,
+                price,
+                opened_at: at,
+            }),
+        }
+    }
+
+    /// Closes `size` shares oldest lot first and returns the realized PnL.
+    fn close(&mut self, size: f64, price: f64) -> f64 {
+        let mut remaining = size;
+        let mut realized = 0.0;
+
+        while remaining > EPSILON {
+            let Some(lot) = self.lots.front_mut() else {
+                break;
+            };
+            let closed = remaining.min(lot.size);
+            realized += closed * (price - lot.price);
+            lot.size -= closed;
+            remaining -= closed;
+            if lot.size <= EPSILON {
+                self.lots.pop_front();
+            }
+        }
+
+        self.realized_pnl += realized;
+        realized
+    }
+
+    fn to_position(&self) -> Position {
+        Position {
+            market_id: self.market_id.clone(),
+            outcome: self.outcome.clone(),
+            size: self.size(),
+            average_price: self.average_price(),
+            current_price: self.mark_price.unwrap_or_else(|| self.average_price()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionPnl {
+    pub market_id: String,
+    pub outcome: String,
+    pub size: f64,
+    pub average_price: f64,
+    pub mark_price: Option<f64>,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub fees: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PnlSnapshot {
+    pub timestamp: DateTime<Utc>,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub fees: f64,
+    pub settlement_payouts: f64,
+    /// Realized plus unrealized, net of fees.
+    pub total_pnl: f64,
+    pub positions: Vec<PositionPnl>,
+}
+
+/// Turns fills into realized and unrealized PnL using FIFO or average-cost
+/// basis per market outcome. Fees are tracked separately from the cost
+/// basis and deducted in [`PnlSnapshot::total_pnl`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PnlLedger {
+    pub method: CostBasisMethod,
+    /// Keyed by `market_id:outcome`.
+    positions: BTreeMap<String, PositionLedger>,
+    history: Vec<PnlSnapshot>,
+}
+
+fn key(market_id: &str, outcome: &str) -> String {
+    format!("{market_id}:{outcome}")
+}
+
+impl PnlLedger {
+    pub fn new(method: CostBasisMethod) -> Self {
+        Self {
+            method,
+            ..Default::default()
+        }
+    }
+
+    pub fn position(&self, market_id: &str, outcome: &str) -> Option<&PositionLedger> {
+        self.positions.get(&key(market_id, outcome))
+    }
+
+    pub fn position_ledgers(&self) -> impl Iterator<Item = &PositionLedger> {
+        self.positions.values()
+    }
+
+    /// Open holdings with their real average price, in the shape returned by
+    /// `Exchange::fetch_positions`.
+    pub fn positions(&self) -> Vec<Position> {
+        self.positions
+            .values()
+            .filter(|p| p.size() > EPSILON)
+            .map(PositionLedger::to_position)
+            .collect()
+    }
+
+    /// Applies a fill and returns the PnL it realized. Sells larger than the
+    /// current holding are rejected without changing the ledger.
+    pub fn record_fill(&mut self, fill: &Fill) -> Result<f64, DrmError> {
+        if fill.size <= 0.0 || !(0.0..=1.0).contains(&fill.price) {
+            return Err(DrmError::InvalidInput(format!(
+                "invalid fill: {} @ {}",
+                fill.size, fill.price
+            )));
+        }
+
+        let position_key = key(&fill.market_id, &fill.outcome);
+        let existing = self.positions.get(&position_key);
+        if existing.is_some_and(|ledger| ledger.settled) {
+            return Err(DrmError::InvalidInput(format!(
+                "{}:{} is already settled",
+                fill.market_id, fill.outcome
+            )));
+        }
+        if fill.side == OrderSide::Sell {
+            let held = existing.map(PositionLedger::size).unwrap_or(0.0);
+            if fill.size > held + EPSILON {
+                return Err(DrmError::InvalidInput(format!(
+                    "sell of {} exceeds holding of {held} in {}:{}",
+                    fill.size, fill.market_id, fill.outcome
+                )));
+            }
+        }
+
+        let method = self.method;
+        let ledger = self
+            .positions
+            .entry(position_key)
+            .or_insert_with(|| PositionLedger::new(&fill.market_id, &fill.outcome));
+        let realized = match fill.side {
+            OrderSide::Buy => {
+                ledger.buy(method, fill.size, fill.price, fill.timestamp);
+                0.0
+            }
+            OrderSide::Sell => ledger.close(fill.size, fill.price),
+        };
+        ledger.fees += fill.fee;
+
+        Ok(realized)
+    }
+
+    pub fn mark(&mut self, market_id: &str, outcome: &str, price: f64) {
+        if let Some(ledger) = self.positions.get_mut(&key(market_id, outcome)) {
+            ledger.mark_price = Some(price);
+        }
+    }
+
+    /// Resolves an outcome at `payout` per share (1.0 for the winner, 0.0
+    /// otherwise), realizing the remaining holding. Returns the cash paid out.
+    pub fn settle(&mut self, market_id: &str, outcome: &str, payout: f64) -> f64 {
+        let Some(ledger) = self.positions.get_mut(&key(market_id, outcome)) else {
+            return 0.0;
+        };
+        if ledger.settled {
+            return 0.0;
+        }
+
+        let size = ledger.size();
+        ledger.close(size, payout);
+        ledger.settlement_payout += size * payout;
+        ledger.mark_price = Some(payout);
+        ledger.settled = true;
+        size * payout
+    }
+
+    pub fn realized_pnl(&self) -> f64 {
+        self.positions.values().map(|p| p.realized_pnl).sum()
+    }
+
+    pub fn unrealized_pnl(&self) -> f64 {
+        self.positions.values().map(|p| p.unrealized_pnl()).sum()
+    }
+
+    pub fn fees(&self) -> f64 {
+        self.positions.values().map(|p| p.fees).sum()
+    }
+
+    pub fn snapshot(&self, timestamp: DateTime<Utc>) -> PnlSnapshot {
+        let realized_pnl = self.realized_pnl();
+        let unrealized_pnl = self.unrealized_pnl();
+        let fees = self.fees();
+
+        PnlSnapshot {
+            timestamp,
+            realized_pnl,
+            unrealized_pnl,
+            fees,
+            settlement_payouts: self.positions.values().map(|p| p.settlement_payout).sum(),
+            total_pnl: realized_pnl + unrealized_pnl - fees,
+            positions: self
+                .positions
+                .values()
+                .map(|p| PositionPnl {
+                    market_id: p.market_id.clone(),
+                    outcome: p.outcome.clone(),
+                    size: p.size(),
+                    average_price: p.average_price(),
+                    mark_price: p.mark_price,
+                    realized_pnl: p.realized_pnl,
+                    unrealized_pnl: p.unrealized_pnl(),
+                    fees: p.fees,
+                })
+                .collect(),
+        }
+    }
+
+    /// Appends a snapshot to the time series.
+    pub fn record_snapshot(&mut self, timestamp: DateTime<Utc>) -> &PnlSnapshot {
+        let snapshot = self.snapshot(timestamp);
+        self.history.push(snapshot);
+        self.history.last().unwrap()
+    }
+
+    pub fn history(&self) -> &[PnlSnapshot] {
+        &self.history
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// The snapshot time series as CSV, one row per snapshot.
+    pub fn history_csv(&self) -> String {
+        let mut csv = String::from(
+            "timestamp,realized_pnl,unrealized_pnl,fees,settlement_payouts,total_pnl\n",
+        );
+        for s in &self.history {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                s.timestamp.to_rfc3339(),
+                s.realized_pnl,
+                s.unrealized_pnl,
+                s.fees,
+                s.settlement_payouts,
+                s.total_pnl
+            );
+        }
+        csv
+    }
+
+    /// Per-position PnL of the current state as CSV.
+    pub fn positions_csv(&self) -> String {
+        let mut csv = String::from(
+            "market_id,outcome,size,average_price,mark_price,realized_pnl,unrealized_pnl,fees\n",
+        );
+        for p in self.snapshot(Utc::now()).positions {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{}",
+                csv_field(&p.market_id),
+                csv_field(&p.outcome),
+                p.size,
+                p.average_price,
+                p.mark_price.map(|m| m.to_string()).unwrap_or_default(),
+                p.realized_pnl,
+                p.unrealized_pnl,
+                p.fees
+            );
+        }
+        csv
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(side: OrderSide, price: f64, size: f64) -> Fill {
+        Fill {
+            timestamp: Utc::now(),
+            market_id: "market-1".to_string(),
+            outcome: "Yes".to_string(),
+            side,
+            price,
+            size,
+            fee: 0.1,
+            order_id: None,
+        }
+    }
+
+    fn approx(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_fifo_and_average_cost_realize_differently() {
+        // given
+        let fills = [
+            fill(OrderSide::Buy, 0.40, 10.0),
+            fill(OrderSide::Buy, 0.60, 10.0),
+            fill(OrderSide::Sell, 0.70, 10.0),
+        ];
+        let mut fifo = PnlLedger::new(CostBasisMethod::Fifo);
+        let mut average = PnlLedger::new(CostBasisMethod::AverageCost);
+
+        // when
+        for f in &fills {
+            fifo.record_fill(f).unwrap();
+            average.record_fill(f).unwrap();
+        }
+        fifo.mark("market-1", "Yes", 0.65);
+        average.mark("market-1", "Yes", 0.65);
+
+        // then
+        assert!(approx(fifo.realized_pnl(), 3.0));
+        assert!(approx(fifo.unrealized_pnl(), 0.5));
+        assert!(approx(average.realized_pnl(), 2.0));
+        assert!(approx(average.unrealized_pnl(), 1.5));
+        assert!(approx(fifo.fees(), 0.3));
+        assert!(approx(fifo.snapshot(Utc::now()).total_pnl, 3.2));
+        assert!(approx(fifo.positions()[0].average_price, 0.60));
+        assert!(approx(average.positions()[0].average_price, 0.50));
+        assert!(fifo.record_fill(&fill(OrderSide::Sell, 0.5, 11.0)).is_err());
+        assert!(approx(
+            fifo.position("market-1", "Yes").unwrap().size(),
+            10.0
+        ));
+    }
+
+    #[test]
+    fn test_settlement_realizes_payout_and_exports() {
+        // given
+        let mut ledger = PnlLedger::default();
+        ledger
+            .record_fill(&fill(OrderSide::Buy, 0.30, 20.0))
+            .unwrap();
+        ledger.record_snapshot(Utc::now());
+
+        // when
+        let payout = ledger.settle("market-1", "Yes", 1.0);
+        ledger.record_snapshot(Utc::now());
+
+        // then
+        assert!(approx(payout, 20.0));
+        let last = &ledger.history()[1];
+        assert!(approx(last.realized_pnl, 14.0));
+        assert!(approx(last.settlement_payouts, 20.0));
+        assert!(approx(last.unrealized_pnl, 0.0));
+        assert!(ledger.positions().is_empty());
+        assert!(ledger.record_fill(&fill(OrderSide::Buy, 0.5, 1.0)).is_err());
+
+        let csv = ledger.history_csv();
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.starts_with("timestamp,realized_pnl,"));
+        let restored = PnlLedger::from_json(&ledger.to_json().unwrap()).unwrap();
+        assert_eq!(restored.history().len(), 2);
+        assert!(approx(restored.realized_pnl(), 14.0));
+    }
+
+    #[test]
+    fn test_rejected_sell_leaves_no_position() {
+        // given
+        let mut ledger = PnlLedger::default();
+
+        // when
+        let result = ledger.record_fill(&fill(OrderSide::Sell, 0.50, 5.0));
+
+        // then
+        assert!(result.is_err());
+        assert!(ledger.position("market-1", "Yes").is_none());
+        assert_eq!(ledger.position_ledgers().count(), 0);
+        assert!(approx(ledger.fees(), 0.0));
+    }
+}
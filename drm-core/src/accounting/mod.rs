@@ -0,0 +1,5 @@
+mod ledger;
+mod portfolio;
+
+pub use ledger::*;
+pub use portfolio::*;
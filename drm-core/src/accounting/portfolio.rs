@@ -0,0 +1,415 @@
+use chrono::{DateTime, Utc};
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::DrmError;
+use crate::exchange::Exchange;
+use crate::models::{Market, Nav, Position, PositionBreakdown};
+
+/// Conversion rates from venue balance currencies to the reporting currency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrencyRates {
+    pub base: String,
+    pub rates: HashMap<String, f64>,
+}
+
+impl Default for CurrencyRates {
+    fn default() -> Self {
+        Self {
+            base: "USD".to_string(),
+            rates: HashMap::from([
+                ("USD".to_string(), 1.0),
+                ("USDC".to_string(), 1.0),
+                ("USDC.E".to_string(), 1.0),
+                ("USDT".to_string(), 1.0),
+            ]),
+        }
+    }
+}
+
+impl CurrencyRates {
+    pub fn with_rate(mut self, currency: impl Into<String>, rate: f64) -> Self {
+        self.rates.insert(currency.into().to_uppercase(), rate);
+        self
+    }
+
+    /// Converts a balance to the base currency. Breakdown entries such as
+    /// `USDC_AVAILABLE` are subsets of the total and return `Some(0.0)` so they
+    /// are not counted twice; unknown currencies return `None`.
+    pub fn convert(&self, currency: &str, amount: f64) -> Option<f64> {
+        let currency = currency.to_uppercase();
+        if currency.ends_with("_AVAILABLE") || currency.ends_with("_LOCKED") {
+            return Some(0.0);
+        }
+        self.rates.get(&currency).map(|rate| amount * rate)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketExposure {
+    pub exchange: String,
+    pub market_id: String,
+    pub event: String,
+    pub cost_basis: f64,
+    pub value: f64,
+    pub positions: Vec<PositionBreakdown>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExchangeNav {
+    pub exchange: String,
+    pub cash: f64,
+    pub positions_value: f64,
+    pub nav: f64,
+    /// Raw balances as reported by the venue.
+    pub balances: HashMap<String, f64>,
+    pub markets: Vec<MarketExposure>,
+    /// Set when the venue could not be queried; it then contributes nothing
+    /// to the totals.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Value held in one event, summed over all its markets and venues.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventExposure {
+    pub event: String,
+    pub cost_basis: f64,
+    pub value: f64,
+    pub exchanges: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioNav {
+    pub timestamp: DateTime<Utc>,
+    pub currency: String,
+    pub nav: Nav,
+    pub exchanges: Vec<ExchangeNav>,
+    pub events: Vec<EventExposure>,
+    /// Balance currencies with no configured rate, left out of cash.
+    pub unpriced_currencies: Vec<String>,
+}
+
+impl PortfolioNav {
+    pub fn exchange(&self, name: &str) -> Option<&ExchangeNav> {
+        self.exchanges.iter().find(|e| e.exchange == name)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Groups markets into events using the identifiers venues keep in the raw
+/// market metadata; markets without one form their own event.
+pub fn event_key(market: &Market) -> String {
+    let metadata = &market.metadata;
+    [
+        "event_ticker",
+        "eventSlug",
+        "event_slug",
+        "eventId",
+        "event_id",
+    ]
+    .iter()
+    .find_map(|key| match metadata.get(*key) {
+        Some(serde_json::Value::String(s)) if !s.is_empty() => Some(s.clone()),
+        Some(serde_json::Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    })
+    .or_else(|| {
+        metadata
+            .get("events")
+            .and_then(|e| e.as_array())
+            .and_then(|e| e.first())
+            .and_then(|e| e.get("slug"))
+            .and_then(|s| s.as_str())
+            .map(String::from)
+    })
+    .unwrap_or_else(|| market.id.clone())
+}
+
+/// Gathers balances and positions from several venues concurrently and
+/// consolidates them into one [`Nav`]. Adapters report position prices per
+/// share in dollars; only balances need currency conversion.
+pub struct PortfolioAggregator {
+    exchanges: Vec<(String, Box<dyn Exchange>)>,
+    rates: CurrencyRates,
+    resolve_events: bool,
+}
+
+impl Default for PortfolioAggregator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PortfolioAggregator {
+    pub fn new() -> Self {
+        Self {
+            exchanges: Vec::new(),
+            rates: CurrencyRates::default(),
+            resolve_events: true,
+        }
+    }
+
+    /// Adds a venue under its [`Exchange::id`].
+    pub fn with_exchange(self, exchange: Box<dyn Exchange>) -> Self {
+        let name = exchange.id().to_string();
+        self.with_named_exchange(name, exchange)
+    }
+
+    /// Adds a venue under a custom name, e.g. for two accounts on one venue.
+    pub fn with_named_exchange(
+        mut self,
+        name: impl Into<String>,
+        exchange: Box<dyn Exchange>,
+    ) -> Self {
+        self.exchanges.push((name.into(), exchange));
+        self
+    }
+
+    pub fn with_rates(mut self, rates: CurrencyRates) -> Self {
+        self.rates = rates;
+        self
+    }
+
+    /// Looks up each held market to group exposure by event. Disable to save
+    /// one request per market; every market is then its own event.
+    pub fn with_resolve_events(mut self, resolve: bool) -> Self {
+        self.resolve_events = resolve;
+        self
+    }
+
+    pub async fn calculate_nav(&self) -> Result<PortfolioNav, DrmError> {
+        if self.exchanges.is_empty() {
+            return Err(DrmError::InvalidInput(
+                "portfolio has no exchanges".to_string(),
+            ));
+        }
+
+        let results = join_all(
+            self.exchanges
+                .iter()
+                .map(|(name, exchange)| self.collect_exchange(name, exchange.as_ref())),
+        )
+        .await;
+
+        let mut exchanges = Vec::with_capacity(results.len());
+        let mut unpriced = Vec::new();
+        for (result, (name, _)) in results.into_iter().zip(&self.exchanges) {
+            match result {
+                Ok((nav, missing)) => {
+                    exchanges.push(nav);
+                    unpriced.extend(missing);
+                }
+                Err(err) => exchanges.push(ExchangeNav {
+                    exchange: name.clone(),
+                    cash: 0.0,
+                    positions_value: 0.0,
+                    nav: 0.0,
+                    balances: HashMap::new(),
+                    markets: Vec::new(),
+                    error: Some(err.to_string()),
+                }),
+            }
+        }
+        if exchanges.iter().all(|e| e.error.is_some()) {
+            return Err(DrmError::Other(format!(
+                "no exchange could be queried: {}",
+                exchanges[0].error.as_deref().unwrap_or_default()
+            )));
+        }
+        unpriced.sort();
+        unpriced.dedup();
+
+        let mut events: BTreeMap<String, EventExposure> = BTreeMap::new();
+        let mut breakdown = Vec::new();
+        for exchange in &exchanges {
+            for market in &exchange.markets {
+                let event = events
+                    .entry(market.event.clone())
+                    .or_insert_with(|| EventExposure {
+                        event: market.event.clone(),
+                        cost_basis: 0.0,
+                        value: 0.0,
+                        exchanges: Vec::new(),
+                    });
+                event.cost_basis += market.cost_basis;
+                event.value += market.value;
+                if !event.exchanges.contains(&exchange.exchange) {
+                    event.exchanges.push(exchange.exchange.clone());
+                }
+                breakdown.extend(market.positions.iter().cloned());
+            }
+        }
+        let mut events: Vec<EventExposure> = events.into_values().collect();
+        events.sort_by(|a, b| b.value.total_cmp(&a.value));
+
+        let cash: f64 = exchanges.iter().map(|e| e.cash).sum();
+        let positions_value: f64 = exchanges.iter().map(|e| e.positions_value).sum();
+
+        Ok(PortfolioNav {
+            timestamp: Utc::now(),
+            currency: self.rates.base.clone(),
+            nav: Nav {
+                nav: cash + positions_value,
+                cash,
+                positions_value,
+                positions: breakdown,
+            },
+            exchanges,
+            events,
+            unpriced_currencies: unpriced,
+        })
+    }
+
+    async fn collect_exchange(
+        &self,
+        name: &str,
+        exchange: &dyn Exchange,
+    ) -> Result<(ExchangeNav, Vec<String>), DrmError> {
+        let (balances, positions) =
+            tokio::try_join!(exchange.fetch_balance(), exchange.fetch_positions(None))?;
+
+        let mut cash = 0.0;
+        let mut unpriced = Vec::new();
+        for (currency, amount) in &balances {
+            match self.rates.convert(currency, *amount) {
+                Some(value) => cash += value,
+                None => unpriced.push(currency.clone()),
+            }
+        }
+
+        let mut by_market: BTreeMap<String, Vec<Position>> = BTreeMap::new();
+        for position in positions.into_iter().filter(|p| p.size != 0.0) {
+            by_market
+                .entry(position.market_id.clone())
+                .or_default()
+                .push(position);
+        }
+
+        let events: Vec<String> = if self.resolve_events {
+            join_all(by_market.keys().map(|id| async move {
+                exchange
+                    .fetch_market(id)
+                    .await
+                    .map(|m| event_key(&m))
+                    .unwrap_or_else(|_| id.clone())
+            }))
+            .await
+        } else {
+            by_market.keys().cloned().collect()
+        };
+
+        let markets: Vec<MarketExposure> = by_market
+            .into_iter()
+            .zip(events)
+            .map(|((market_id, positions), event)| MarketExposure {
+                exchange: name.to_string(),
+                event,
+                cost_basis: positions.iter().map(|p| p.cost_basis()).sum(),
+                value: positions.iter().map(|p| p.current_value()).sum(),
+                positions: positions
+                    .iter()
+                    .map(|p| PositionBreakdown {
+                        outcome: format!("{name}:{market_id}:{}", p.outcome),
+                        size: p.size,
+                        current_price: p.current_price,
+                        value: p.current_value(),
+                    })
+                    .collect(),
+                market_id,
+            })
+            .collect();
+        let positions_value: f64 = markets.iter().map(|m| m.value).sum();
+
+        Ok((
+            ExchangeNav {
+                exchange: name.to_string(),
+                cash,
+                positions_value,
+                nav: cash + positions_value,
+                balances,
+                markets,
+                error: None,
+            },
+            unpriced,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{make_market, MockExchange};
+
+    fn position(market_id: &str, outcome: &str, size: f64, price: f64) -> Position {
+        Position {
+            market_id: market_id.to_string(),
+            outcome: outcome.to_string(),
+            size,
+            average_price: 0.4,
+            current_price: price,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_consolidates_nav_across_exchanges_and_events() {
+        // given
+        let mut fed_pm = make_market("pm-fed");
+        fed_pm.metadata = serde_json::json!({ "events": [{ "slug": "fed-december" }] });
+        let polymarket = MockExchange::with_market(fed_pm);
+        polymarket
+            .positions
+            .lock()
+            .unwrap()
+            .push(position("pm-fed", "Yes", 100.0, 0.6));
+
+        let mut fed_kalshi = make_market("KXFED-25DEC");
+        fed_kalshi.metadata = serde_json::json!({ "event_ticker": "fed-december" });
+        let kalshi = MockExchange::with_market(fed_kalshi);
+        kalshi.markets.lock().unwrap().push(make_market("RAIN"));
+        {
+            let mut balance = kalshi.balance.lock().unwrap();
+            balance.clear();
+            balance.insert("USD".to_string(), 250.0);
+            balance.insert("EUR".to_string(), 10.0);
+        }
+        kalshi.positions.lock().unwrap().extend([
+            position("KXFED-25DEC", "No", 50.0, 0.3),
+            position("RAIN", "Yes", 10.0, 0.5),
+        ]);
+
+        let limitless = MockExchange::with_market(make_market("lm-1"));
+        limitless
+            .balance
+            .lock()
+            .unwrap()
+            .insert("USDC_AVAILABLE".to_string(), 800.0);
+
+        let aggregator = PortfolioAggregator::new()
+            .with_named_exchange("polymarket", Box::new(polymarket))
+            .with_named_exchange("kalshi", Box::new(kalshi))
+            .with_named_exchange("limitless", Box::new(limitless));
+
+        // when
+        let nav = aggregator.calculate_nav().await.unwrap();
+
+        // then
+        assert_eq!(nav.nav.cash, 1000.0 + 250.0 + 1000.0);
+        assert_eq!(nav.nav.positions_value, 60.0 + 15.0 + 5.0);
+        assert_eq!(nav.nav.nav, 2330.0);
+        assert_eq!(nav.unpriced_currencies, vec!["EUR".to_string()]);
+
+        let kalshi = nav.exchange("kalshi").unwrap();
+        assert_eq!(kalshi.nav, 270.0);
+        assert_eq!(kalshi.markets.len(), 2);
+
+        assert_eq!(nav.events[0].event, "fed-december");
+        assert_eq!(nav.events[0].value, 75.0);
+        assert_eq!(nav.events[0].exchanges, vec!["polymarket", "kalshi"]);
+        assert_eq!(nav.events[1].event, "RAIN");
+    }
+}
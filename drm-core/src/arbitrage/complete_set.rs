@@ -0,0 +1,602 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration, Instant};
+
+use crate::backtest::FeeModel;
+use crate::error::DrmError;
+use crate::exchange::{CompleteSetConverter, Exchange};
+use crate::models::{Order, OrderSide, Orderbook, PriceLevel};
+
+use super::scanner::ArbLeg;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompleteSetSide {
+    /// Yes ask + No ask below $1: buy both, merge or hold to resolution.
+    Buy,
+    /// Yes bid + No bid above $1: sell both out of held or split sets.
+    Sell,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteSetOpportunity {
+    pub exchange: String,
+    pub market_id: String,
+    pub yes_book: String,
+    pub no_book: String,
+    pub side: CompleteSetSide,
+    /// Sets executable at a profit.
+    pub size: f64,
+    /// Worst levels needed for the full size; use as limit prices.
+    pub yes_limit: f64,
+    pub no_limit: f64,
+    pub yes_average: f64,
+    pub no_average: f64,
+    pub fees: f64,
+    /// Guaranteed profit after fees.
+    pub edge: f64,
+    pub edge_per_share: f64,
+    pub observed_at: DateTime<Utc>,
+}
+
+struct PairWalk {
+    size: f64,
+    yes_notional: f64,
+    no_notional: f64,
+    fees: f64,
+    yes_limit: f64,
+    no_limit: f64,
+}
+
+/// Walks both ladders best-first while a pair still earns `min_edge` after
+/// fees. `unit_edge(yes, no)` is the per-set profit at those prices.
+fn walk_pairs(
+    yes: &[PriceLevel],
+    no: &[PriceLevel],
+    fee_model: FeeModel,
+    min_edge: f64,
+    unit_edge: impl Fn(f64, f64) -> f64,
+) -> Option<PairWalk> {
+    let (mut i, mut j) = (0, 0);
+    let (mut yes_left, mut no_left) = (yes.first()?.size, no.first()?.size);
+    let mut walk = PairWalk {
+        size: 0.0,
+        yes_notional: 0.0,
+        no_notional: 0.0,
+        fees: 0.0,
+        yes_limit: 0.0,
+        no_limit: 0.0,
+    };
+
+    while i < yes.len() && j < no.len() {
+        let (py, pn) = (yes[i].price, no[j].price);
+        let fees = fee_model.fee(py, 1.0, false) + fee_model.fee(pn, 1.0, false);
+        let edge = unit_edge(py, pn) - fees;
+        if edge <= 1e-9 || edge < min_edge - 1e-9 {
+            break;
+        }
+
+        let take = yes_left.min(no_left);
+        walk.size += take;
+        walk.yes_notional += take * py;
+        walk.no_notional += take * pn;
+        walk.fees += fee_model.fee(py, take, false) + fee_model.fee(pn, take, false);
+        walk.yes_limit = py;
+        walk.no_limit = pn;
+
+        yes_left -= take;
+        no_left -= take;
+        if yes_left <= 1e-9 {
+            i += 1;
+            yes_left = yes.get(i).map(|l| l.size).unwrap_or(0.0);
+        }
+        if no_left <= 1e-9 {
+            j += 1;
+            no_left = no.get(j).map(|l| l.size).unwrap_or(0.0);
+        }
+    }
+
+    (walk.size > 0.0).then_some(walk)
+}
+
+/// Checks one venue's Yes and No books for a complete-set edge, preferring
+/// the buy side when both exist. `leg.no_book` must be set.
+pub fn find_complete_set(
+    leg: &ArbLeg,
+    yes_book: &Orderbook,
+    no_book: &Orderbook,
+    min_edge: f64,
+) -> Option<CompleteSetOpportunity> {
+    let no_book_id = leg.no_book.clone()?;
+    let buy = walk_pairs(
+        &yes_book.asks,
+        &no_book.asks,
+        leg.fee_model,
+        min_edge,
+        |y, n| 1.0 - y - n,
+    )
+    .map(|w| (CompleteSetSide::Buy, w));
+    let (side, walk) = buy.or_else(|| {
+        walk_pairs(
+            &yes_book.bids,
+            &no_book.bids,
+            leg.fee_model,
+            min_edge,
+            |y, n| y + n - 1.0,
+        )
+        .map(|w| (CompleteSetSide::Sell, w))
+    })?;
+
+    let gross = match side {
+        CompleteSetSide::Buy => walk.size - walk.yes_notional - walk.no_notional,
+        CompleteSetSide::Sell => walk.yes_notional + walk.no_notional - walk.size,
+    };
+    let edge = gross - walk.fees;
+
+    Some(CompleteSetOpportunity {
+        exchange: leg.exchange.clone(),
+        market_id: leg.market_id.clone(),
+        yes_book: leg.yes_book.clone(),
+        no_book: no_book_id,
+        side,
+        size: walk.size,
+        yes_limit: walk.yes_limit,
+        no_limit: walk.no_limit,
+        yes_average: walk.yes_notional / walk.size,
+        no_average: walk.no_notional / walk.size,
+        fees: walk.fees,
+        edge,
+        edge_per_share: edge / walk.size,
+        observed_at: Utc::now(),
+    })
+}
+
+/// What to do with the excess of the better-filled leg.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LegRiskPolicy {
+    /// Cancel the unfilled remainders and keep the excess as inventory.
+    Cancel,
+    /// Also trade the excess back out, accepting up to `max_slippage`
+    /// per share versus its entry price.
+    Unwind { max_slippage: f64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct CompleteSetConfig {
+    pub yes_outcome: String,
+    pub no_outcome: String,
+    /// How long both legs may rest before remainders are cancelled.
+    pub leg_timeout_ms: u64,
+    pub poll_interval_ms: u64,
+    pub leg_risk: LegRiskPolicy,
+    /// Merge bought sets into collateral when a converter is attached.
+    pub merge_after_buy: bool,
+    /// Split collateral into the sets to sell when a converter is attached;
+    /// otherwise sells come out of held inventory.
+    pub split_before_sell: bool,
+}
+
+impl Default for CompleteSetConfig {
+    fn default() -> Self {
+        Self {
+            yes_outcome: "Yes".to_string(),
+            no_outcome: "No".to_string(),
+            leg_timeout_ms: 5000,
+            poll_interval_ms: 250,
+            leg_risk: LegRiskPolicy::Unwind { max_slippage: 0.05 },
+            merge_after_buy: true,
+            split_before_sell: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CompleteSetExecution {
+    pub yes_order: Order,
+    pub no_order: Order,
+    /// Sets completed on both legs.
+    pub matched: f64,
+    /// Excess shares traded back out under [`LegRiskPolicy::Unwind`],
+    /// cancelled if still open after the leg timeout.
+    pub unwind_order: Option<Order>,
+    /// Excess shares left in inventory on the better-filled leg, net of
+    /// any unwind fills.
+    pub unhedged: f64,
+    /// Why the unwind order could not be placed; the excess then stays in
+    /// `unhedged`.
+    pub unwind_error: Option<String>,
+    pub split_tx: Option<String>,
+    pub merge_tx: Option<String>,
+    /// Why merging the matched sets failed; the shares stay in inventory.
+    pub merge_error: Option<String>,
+}
+
+/// Executes a [`CompleteSetOpportunity`] by placing both legs at once,
+/// cancelling remainders after the leg timeout and handling any fill
+/// imbalance per [`LegRiskPolicy`].
+pub struct CompleteSetExecutor<E: Exchange + ?Sized> {
+    exchange: Arc<E>,
+    converter: Option<Arc<dyn CompleteSetConverter>>,
+    config: CompleteSetConfig,
+}
+
+impl<E: Exchange + ?Sized + 'static> CompleteSetExecutor<E> {
+    pub fn new(exchange: Arc<E>, config: CompleteSetConfig) -> Self {
+        Self {
+            exchange,
+            converter: None,
+            config,
+        }
+    }
+
+    pub fn with_converter(mut self, converter: Arc<dyn CompleteSetConverter>) -> Self {
+        self.converter = Some(converter);
+        self
+    }
+
+    async fn place(
+        &self,
+        opportunity: &CompleteSetOpportunity,
+        outcome: &str,
+        token_id: &str,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+    ) -> Result<Order, DrmError> {
+        self.exchange
+            .create_order(
+                &opportunity.market_id,
+                outcome,
+                side,
+                price,
+                size,
+                HashMap::from([("token_id".to_string(), token_id.to_string())]),
+            )
+            .await
+    }
+
+    async fn refresh(&self, order: &Order) -> Order {
+        self.exchange
+            .fetch_order(&order.id, Some(&order.market_id))
+            .await
+            .unwrap_or_else(|_| order.clone())
+    }
+
+    /// Polls `orders` until all fill or the leg timeout passes, then
+    /// cancels whatever is still open.
+    async fn fill_or_cancel(&self, orders: &mut [Order]) {
+        let deadline = Instant::now() + Duration::from_millis(self.config.leg_timeout_ms);
+        while !orders.iter().all(Order::is_filled) && Instant::now() < deadline {
+            sleep(Duration::from_millis(self.config.poll_interval_ms.max(1))).await;
+            for order in orders.iter_mut() {
+                *order = self.refresh(order).await;
+            }
+        }
+
+        for order in orders.iter_mut() {
+            if !order.is_filled() {
+                if let Ok(cancelled) = self
+                    .exchange
+                    .cancel_order(&order.id, Some(&order.market_id))
+                    .await
+                {
+                    *order = cancelled;
+                }
+            }
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        opportunity: &CompleteSetOpportunity,
+    ) -> Result<CompleteSetExecution, DrmError> {
+        let side = match opportunity.side {
+            CompleteSetSide::Buy => OrderSide::Buy,
+            CompleteSetSide::Sell => OrderSide::Sell,
+        };
+        let size = opportunity.size;
+
+        let mut split_tx = None;
+        if opportunity.side == CompleteSetSide::Sell && self.config.split_before_sell {
+            if let Some(converter) = &self.converter {
+                split_tx = Some(converter.split(&opportunity.market_id, size).await?);
+            }
+        }
+
+        let (yes, no) = tokio::join!(
+            self.place(
+                opportunity,
+                &self.config.yes_outcome,
+                &opportunity.yes_book,
+                side,
+                opportunity.yes_limit,
+                size,
+            ),
+            self.place(
+                opportunity,
+                &self.config.no_outcome,
+                &opportunity.no_book,
+                side,
+                opportunity.no_limit,
+                size,
+            )
+        );
+        let mut legs = match (yes, no) {
+            (Ok(yes), Ok(no)) => [yes, no],
+            (Ok(placed), Err(err)) | (Err(err), Ok(placed)) => {
+                let _ = self
+                    .exchange
+                    .cancel_order(&placed.id, Some(&placed.market_id))
+                    .await;
+                return Err(err);
+            }
+            (Err(err), Err(_)) => return Err(err),
+        };
+
+        self.fill_or_cancel(&mut legs).await;
+        let [yes, no] = legs;
+
+        let matched = yes.filled.min(no.filled);
+        let excess = (yes.filled - no.filled).abs();
+        let mut unwind_order = None;
+        let mut unwind_error = None;
+        let mut unhedged = excess;
+
+        if let (LegRiskPolicy::Unwind { max_slippage }, true) =
+            (self.config.leg_risk, excess > 1e-9)
+        {
+            let (heavy, token_id) = if yes.filled > no.filled {
+                (&yes, &opportunity.yes_book)
+            } else {
+                (&no, &opportunity.no_book)
+            };
+            let (unwind_side, price) = match side {
+                OrderSide::Buy => (OrderSide::Sell, (heavy.price - max_slippage).max(0.0)),
+                OrderSide::Sell => (OrderSide::Buy, (heavy.price + max_slippage).min(1.0)),
+            };
+            match self
+                .place(
+                    opportunity,
+                    &heavy.outcome.clone(),
+                    token_id,
+                    unwind_side,
+                    price,
+                    excess,
+                )
+                .await
+            {
+                Ok(order) => {
+                    let mut unwind = [order];
+                    self.fill_or_cancel(&mut unwind).await;
+                    let [order] = unwind;
+                    unhedged = (excess - order.filled).max(0.0);
+                    unwind_order = Some(order);
+                }
+                Err(err) => unwind_error = Some(err.to_string()),
+            }
+        }
+
+        let mut merge_tx = None;
+        let mut merge_error = None;
+        if opportunity.side == CompleteSetSide::Buy && self.config.merge_after_buy && matched > 0.0
+        {
+            if let Some(converter) = &self.converter {
+                match converter.merge(&opportunity.market_id, matched).await {
+                    Ok(tx) => merge_tx = Some(tx),
+                    Err(err) => merge_error = Some(err.to_string()),
+                }
+            }
+        }
+
+        Ok(CompleteSetExecution {
+            yes_order: yes,
+            no_order: no,
+            matched,
+            unwind_order,
+            unhedged,
+            unwind_error,
+            split_tx,
+            merge_tx,
+            merge_error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OrderStatus;
+    use crate::testing::{make_market, MockExchange};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    fn book(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Orderbook {
+        Orderbook {
+            bids: bids.iter().map(|&(p, s)| PriceLevel::new(p, s)).collect(),
+            asks: asks.iter().map(|&(p, s)| PriceLevel::new(p, s)).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn leg() -> ArbLeg {
+        ArbLeg::new("polymarket", "market-1", "yes-token").with_no_book("no-token")
+    }
+
+    #[derive(Default)]
+    struct RecordingConverter {
+        calls: Mutex<Vec<(String, f64)>>,
+    }
+
+    #[async_trait]
+    impl CompleteSetConverter for RecordingConverter {
+        async fn split(&self, _market_id: &str, amount: f64) -> Result<String, DrmError> {
+            self.calls.lock().unwrap().push(("split".into(), amount));
+            Ok("0xsplit".into())
+        }
+
+        async fn merge(&self, _market_id: &str, amount: f64) -> Result<String, DrmError> {
+            self.calls.lock().unwrap().push(("merge".into(), amount));
+            Ok("0xmerge".into())
+        }
+    }
+
+    struct FailingConverter;
+
+    #[async_trait]
+    impl CompleteSetConverter for FailingConverter {
+        async fn split(&self, _market_id: &str, _amount: f64) -> Result<String, DrmError> {
+            Err(DrmError::Other("rpc down".into()))
+        }
+
+        async fn merge(&self, _market_id: &str, _amount: f64) -> Result<String, DrmError> {
+            Err(DrmError::Other("rpc down".into()))
+        }
+    }
+
+    #[test]
+    fn test_finds_buy_and_sell_edges_after_fees() {
+        // given
+        let yes = book(&[(0.40, 50.0)], &[(0.45, 10.0), (0.47, 20.0)]);
+        let no = book(&[(0.50, 50.0)], &[(0.50, 15.0), (0.56, 20.0)]);
+        let rich_yes = book(&[(0.55, 8.0)], &[(0.60, 10.0)]);
+        let rich_no = book(&[(0.50, 20.0)], &[(0.52, 10.0)]);
+        let fair = book(&[(0.49, 10.0)], &[(0.51, 10.0)]);
+
+        // when
+        let buy = find_complete_set(&leg(), &yes, &no, 0.0).unwrap();
+        let sell = find_complete_set(&leg(), &rich_yes, &rich_no, 0.0).unwrap();
+        let with_fees = find_complete_set(
+            &leg().with_fee_model(FeeModel::PriceCurve { rate: 0.05 }),
+            &yes,
+            &no,
+            0.0,
+        )
+        .unwrap();
+
+        // then
+        assert_eq!(buy.side, CompleteSetSide::Buy);
+        assert_eq!(buy.size, 15.0);
+        assert_eq!((buy.yes_limit, buy.no_limit), (0.47, 0.50));
+        assert!((buy.edge - (10.0 * 0.05 + 5.0 * 0.03)).abs() < 1e-9);
+        assert_eq!(sell.side, CompleteSetSide::Sell);
+        assert_eq!(sell.size, 8.0);
+        assert!((sell.edge - 0.4).abs() < 1e-9);
+        assert_eq!(with_fees.size, 10.0);
+        assert!(find_complete_set(&leg(), &fair, &fair, 0.0).is_none());
+        assert!(find_complete_set(&ArbLeg::new("kalshi", "FED", "FED"), &yes, &no, 0.0).is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_partial_second_leg_is_unwound_and_matched_sets_merged() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let converter = Arc::new(RecordingConverter::default());
+        let executor = CompleteSetExecutor::new(exchange.clone(), CompleteSetConfig::default())
+            .with_converter(converter.clone());
+        let yes = book(&[], &[(0.45, 10.0)]);
+        let no = book(&[], &[(0.50, 10.0)]);
+        let opportunity = find_complete_set(&leg(), &yes, &no, 0.0).unwrap();
+
+        // when
+        let fills = async {
+            sleep(Duration::from_millis(300)).await;
+            let mut orders = exchange.orders.lock().unwrap();
+            orders[0].filled = 10.0;
+            orders[0].status = OrderStatus::Filled;
+            orders[1].filled = 6.0;
+            orders[1].status = OrderStatus::PartiallyFilled;
+        };
+        let (execution, _) = tokio::join!(executor.execute(&opportunity), fills);
+        let execution = execution.unwrap();
+
+        // then
+        assert_eq!(execution.matched, 6.0);
+        assert_eq!(execution.no_order.status, OrderStatus::Cancelled);
+        let unwind = execution.unwind_order.unwrap();
+        assert_eq!(
+            (unwind.outcome.as_str(), unwind.side, unwind.size),
+            ("Yes", OrderSide::Sell, 4.0)
+        );
+        assert!((unwind.price - 0.40).abs() < 1e-9);
+        assert_eq!(unwind.status, OrderStatus::Cancelled);
+        assert_eq!(execution.unhedged, 4.0);
+        assert_eq!(execution.merge_tx.as_deref(), Some("0xmerge"));
+        assert_eq!(
+            *converter.calls.lock().unwrap(),
+            vec![("merge".to_string(), 6.0)]
+        );
+        assert_eq!(
+            *exchange.cancelled.lock().unwrap(),
+            vec!["order-1".to_string(), "order-2".to_string()]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_unwind_fills_reduce_unhedged() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let executor = CompleteSetExecutor::new(exchange.clone(), CompleteSetConfig::default());
+        let yes = book(&[], &[(0.45, 10.0)]);
+        let no = book(&[], &[(0.50, 10.0)]);
+        let opportunity = find_complete_set(&leg(), &yes, &no, 0.0).unwrap();
+
+        // when
+        let fills = async {
+            sleep(Duration::from_millis(300)).await;
+            {
+                let mut orders = exchange.orders.lock().unwrap();
+                orders[0].filled = 10.0;
+                orders[0].status = OrderStatus::Filled;
+                orders[1].filled = 6.0;
+                orders[1].status = OrderStatus::PartiallyFilled;
+            }
+            sleep(Duration::from_millis(5500)).await;
+            let mut orders = exchange.orders.lock().unwrap();
+            orders[2].filled = 3.0;
+            orders[2].status = OrderStatus::PartiallyFilled;
+        };
+        let (execution, _) = tokio::join!(executor.execute(&opportunity), fills);
+        let execution = execution.unwrap();
+
+        // then
+        let unwind = execution.unwind_order.unwrap();
+        assert_eq!(unwind.filled, 3.0);
+        assert_eq!(unwind.status, OrderStatus::Cancelled);
+        assert_eq!(execution.unhedged, 1.0);
+        assert_eq!(
+            *exchange.cancelled.lock().unwrap(),
+            vec!["order-1".to_string(), "order-2".to_string()]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_merge_failure_keeps_fills() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let executor = CompleteSetExecutor::new(exchange.clone(), CompleteSetConfig::default())
+            .with_converter(Arc::new(FailingConverter));
+        let yes = book(&[], &[(0.45, 10.0)]);
+        let no = book(&[], &[(0.50, 10.0)]);
+        let opportunity = find_complete_set(&leg(), &yes, &no, 0.0).unwrap();
+
+        // when
+        let fills = async {
+            sleep(Duration::from_millis(300)).await;
+            for order in exchange.orders.lock().unwrap().iter_mut() {
+                order.filled = order.size;
+                order.status = OrderStatus::Filled;
+            }
+        };
+        let (execution, _) = tokio::join!(executor.execute(&opportunity), fills);
+        let execution = execution.unwrap();
+
+        // then
+        assert_eq!(execution.matched, 10.0);
+        assert_eq!(execution.yes_order.status, OrderStatus::Filled);
+        assert!(execution.merge_tx.is_none());
+        assert!(execution.merge_error.unwrap().contains("rpc down"));
+        assert!(execution.unwind_error.is_none());
+    }
+}
@@ -0,0 +1,5 @@
+mod complete_set;
+mod scanner;
+
+pub use complete_set::*;
+pub use scanner::*;
@@ -0,0 +1,644 @@
+use chrono::{DateTime, Duration, Utc};
+use futures::future::{join_all, select_all};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::backtest::FeeModel;
+use crate::error::DrmError;
+use crate::exchange::OrderbookSource;
+use crate::models::{Orderbook, OrderbookManager, PriceLevel};
+
+/// One venue's listing of a linked binary event.
+#[derive(Debug, Clone)]
+pub struct ArbLeg {
+    /// Exchange id, also used to pick the [`OrderbookSource`] when polling.
+    pub exchange: String,
+    pub market_id: String,
+    /// Book id of the Yes outcome (token id, ticker or slug).
+    pub yes_book: String,
+    /// Book id of the No outcome. `None` for venues that publish a single
+    /// Yes book (Kalshi); No prices then come from its complement.
+    pub no_book: Option<String>,
+    pub tick_size: f64,
+    pub fee_model: FeeModel,
+}
+
+impl ArbLeg {
+    pub fn new(
+        exchange: impl Into<String>,
+        market_id: impl Into<String>,
+        yes_book: impl Into<String>,
+    ) -> Self {
+        Self {
+            exchange: exchange.into(),
+            market_id: market_id.into(),
+            yes_book: yes_book.into(),
+            no_book: None,
+            tick_size: 0.01,
+            fee_model: FeeModel::None,
+        }
+    }
+
+    pub fn with_no_book(mut self, no_book: impl Into<String>) -> Self {
+        self.no_book = Some(no_book.into());
+        self
+    }
+
+    pub fn with_tick_size(mut self, tick_size: f64) -> Self {
+        self.tick_size = tick_size;
+        self
+    }
+
+    pub fn with_fee_model(mut self, fee_model: FeeModel) -> Self {
+        self.fee_model = fee_model;
+        self
+    }
+
+    fn book_ids(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.yes_book.as_str()).chain(self.no_book.as_deref())
+    }
+}
+
+/// The same binary event listed on several exchanges.
+#[derive(Debug, Clone)]
+pub struct LinkedMarket {
+    pub id: String,
+    pub legs: Vec<ArbLeg>,
+}
+
+impl LinkedMarket {
+    pub fn new(id: impl Into<String>, legs: Vec<ArbLeg>) -> Self {
+        Self {
+            id: id.into(),
+            legs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArbSide {
+    pub exchange: String,
+    pub market_id: String,
+    pub book_id: String,
+    pub outcome: String,
+    /// Worst level needed for the full size; use as the limit price.
+    pub limit_price: f64,
+    pub average_price: f64,
+    pub fees: f64,
+}
+
+/// Buying Yes on one venue and No on another for less than the $1 the pair
+/// pays out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArbOpportunity {
+    pub linked_id: String,
+    pub buy_yes: ArbSide,
+    pub buy_no: ArbSide,
+    /// Shares of each side executable at a profit.
+    pub size: f64,
+    /// Total outlay for both sides including fees.
+    pub cost: f64,
+    /// `size - cost`: guaranteed profit at resolution.
+    pub edge: f64,
+    pub edge_per_share: f64,
+    pub first_seen: DateTime<Utc>,
+    pub observed_at: DateTime<Utc>,
+}
+
+impl ArbOpportunity {
+    pub fn key(&self) -> String {
+        edge_key(
+            &self.linked_id,
+            &self.buy_yes.exchange,
+            &self.buy_yes.market_id,
+            &self.buy_no.exchange,
+            &self.buy_no.market_id,
+        )
+    }
+
+    /// How long this edge has been continuously observed.
+    pub fn persisted(&self) -> Duration {
+        self.observed_at - self.first_seen
+    }
+}
+
+/// An edge that was observed and has since disappeared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosedEdge {
+    pub key: String,
+    pub linked_id: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub best_edge: f64,
+}
+
+impl ClosedEdge {
+    pub fn duration(&self) -> Duration {
+        self.last_seen - self.first_seen
+    }
+}
+
+struct ActiveEdge {
+    linked_id: String,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+    best_edge: f64,
+}
+
+fn edge_key(linked: &str, yes_ex: &str, yes_market: &str, no_ex: &str, no_market: &str) -> String {
+    format!("{linked}|{yes_ex}:{yes_market}|{no_ex}:{no_market}")
+}
+
+fn round_up_to_tick(price: f64, tick: f64) -> f64 {
+    if tick <= 0.0 {
+        return price;
+    }
+    ((price / tick) - 1e-9).ceil() * tick
+}
+
+struct Fill {
+    size: f64,
+    yes_notional: f64,
+    no_notional: f64,
+    yes_fees: f64,
+    no_fees: f64,
+    yes_limit: f64,
+    no_limit: f64,
+}
+
+/// Walks both ask ladders best-first while a Yes + No pair still costs less
+/// than `1 - min_edge` after fees.
+fn walk_ladders(
+    yes: &[PriceLevel],
+    no: &[PriceLevel],
+    yes_fee: FeeModel,
+    no_fee: FeeModel,
+    min_edge: f64,
+) -> Option<Fill> {
+    let (mut i, mut j) = (0, 0);
+    let (mut yes_left, mut no_left) = (yes.first()?.size, no.first()?.size);
+    let mut fill = Fill {
+        size: 0.0,
+        yes_notional: 0.0,
+        no_notional: 0.0,
+        yes_fees: 0.0,
+        no_fees: 0.0,
+        yes_limit: 0.0,
+        no_limit: 0.0,
+    };
+
+    while i < yes.len() && j < no.len() {
+        let (py, pn) = (yes[i].price, no[j].price);
+        let unit = py + yes_fee.fee(py, 1.0, false) + pn + no_fee.fee(pn, 1.0, false);
+        let edge = 1.0 - unit;
+        if edge <= 1e-9 || edge < min_edge - 1e-9 {
+            break;
+        }
+
+        let take = yes_left.min(no_left);
+        fill.size += take;
+        fill.yes_notional += take * py;
+        fill.no_notional += take * pn;
+        fill.yes_fees += yes_fee.fee(py, take, false);
+        fill.no_fees += no_fee.fee(pn, take, false);
+        fill.yes_limit = py;
+        fill.no_limit = pn;
+
+        yes_left -= take;
+        no_left -= take;
+        if yes_left <= 1e-9 {
+            i += 1;
+            yes_left = yes.get(i).map(|l| l.size).unwrap_or(0.0);
+        }
+        if no_left <= 1e-9 {
+            j += 1;
+            no_left = no.get(j).map(|l| l.size).unwrap_or(0.0);
+        }
+    }
+
+    (fill.size > 0.0).then_some(fill)
+}
+
+/// Finds Yes/No pairs across linked venues that cost less than $1.
+///
+/// Books are read from an [`OrderbookManager`] keyed by each leg's book id,
+/// so websocket clients sharing the manager stream into the scanner
+/// directly. [`ArbScanner::poll`] fills it from REST instead.
+pub struct ArbScanner {
+    linked: Vec<LinkedMarket>,
+    orderbooks: OrderbookManager,
+    min_edge_per_share: f64,
+    max_book_age: Option<Duration>,
+    active: HashMap<String, ActiveEdge>,
+    closed: Vec<ClosedEdge>,
+}
+
+impl ArbScanner {
+    pub fn new(linked: Vec<LinkedMarket>, orderbooks: OrderbookManager) -> Self {
+        Self {
+            linked,
+            orderbooks,
+            min_edge_per_share: 0.0,
+            max_book_age: None,
+            active: HashMap::new(),
+            closed: Vec::new(),
+        }
+    }
+
+    /// Ignores pairs whose per-share profit after fees is below `min_edge`.
+    pub fn with_min_edge(mut self, min_edge: f64) -> Self {
+        self.min_edge_per_share = min_edge;
+        self
+    }
+
+    /// Treats books with a timestamp older than `max_age` as missing.
+    pub fn with_max_book_age(mut self, max_age: Duration) -> Self {
+        self.max_book_age = Some(max_age);
+        self
+    }
+
+    pub fn orderbook_manager(&self) -> &OrderbookManager {
+        &self.orderbooks
+    }
+
+    pub fn linked_markets(&self) -> &[LinkedMarket] {
+        &self.linked
+    }
+
+    pub fn add_linked_market(&mut self, linked: LinkedMarket) {
+        self.linked.push(linked);
+    }
+
+    /// Edges that opened and closed since the scanner was created.
+    pub fn closed_edges(&self) -> &[ClosedEdge] {
+        &self.closed
+    }
+
+    fn book_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .linked
+            .iter()
+            .flat_map(|l| l.legs.iter())
+            .flat_map(|leg| leg.book_ids().map(String::from))
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    fn fresh_book(&self, book_id: &str, now: DateTime<Utc>) -> Option<Arc<Orderbook>> {
+        let book = self.orderbooks.get(book_id)?;
+        match (self.max_book_age, book.timestamp) {
+            (Some(max_age), Some(ts)) if now - ts > max_age => None,
+            _ => Some(book),
+        }
+    }
+
+    fn yes_asks(&self, leg: &ArbLeg, now: DateTime<Utc>) -> Vec<PriceLevel> {
+        self.fresh_book(&leg.yes_book, now)
+            .map(|b| b.asks.clone())
+            .unwrap_or_default()
+    }
+
+    fn no_asks(&self, leg: &ArbLeg, now: DateTime<Utc>) -> Vec<PriceLevel> {
+        match &leg.no_book {
+            Some(no_book) => self
+                .fresh_book(no_book, now)
+                .map(|b| b.asks.clone())
+                .unwrap_or_default(),
+            // A Yes bid at p is a No offer at 1 - p; round against us.
+            None => self
+                .fresh_book(&leg.yes_book, now)
+                .map(|b| {
+                    b.bids
+                        .iter()
+                        .map(|l| {
+                            PriceLevel::new(round_up_to_tick(1.0 - l.price, leg.tick_size), l.size)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Evaluates every linked market against the current books. Edges are
+    /// sorted by total edge, largest first.
+    pub fn scan(&mut self) -> Vec<ArbOpportunity> {
+        self.scan_at(Utc::now())
+    }
+
+    /// [`scan`](Self::scan) with an explicit clock, for replay and tests.
+    pub fn scan_at(&mut self, now: DateTime<Utc>) -> Vec<ArbOpportunity> {
+        let mut found = Vec::new();
+
+        for linked in &self.linked {
+            for yes_leg in &linked.legs {
+                let yes_asks = self.yes_asks(yes_leg, now);
+                if yes_asks.is_empty() {
+                    continue;
+                }
+
+                for no_leg in &linked.legs {
+                    if std::ptr::eq(yes_leg, no_leg) {
+                        continue;
+                    }
+                    let no_asks = self.no_asks(no_leg, now);
+                    let fill = match walk_ladders(
+                        &yes_asks,
+                        &no_asks,
+                        yes_leg.fee_model,
+                        no_leg.fee_model,
+                        self.min_edge_per_share,
+                    ) {
+                        Some(fill) => fill,
+                        None => continue,
+                    };
+
+                    let cost = fill.yes_notional + fill.no_notional + fill.yes_fees + fill.no_fees;
+                    let edge = fill.size - cost;
+                    found.push(ArbOpportunity {
+                        linked_id: linked.id.clone(),
+                        buy_yes: ArbSide {
+                            exchange: yes_leg.exchange.clone(),
+                            market_id: yes_leg.market_id.clone(),
+                            book_id: yes_leg.yes_book.clone(),
+                            outcome: "Yes".to_string(),
+                            limit_price: fill.yes_limit,
+                            average_price: fill.yes_notional / fill.size,
+                            fees: fill.yes_fees,
+                        },
+                        buy_no: ArbSide {
+                            exchange: no_leg.exchange.clone(),
+                            market_id: no_leg.market_id.clone(),
+                            book_id: no_leg
+                                .no_book
+                                .clone()
+                                .unwrap_or_else(|| no_leg.yes_book.clone()),
+                            outcome: "No".to_string(),
+                            limit_price: fill.no_limit,
+                            average_price: fill.no_notional / fill.size,
+                            fees: fill.no_fees,
+                        },
+                        size: fill.size,
+                        cost,
+                        edge,
+                        edge_per_share: edge / fill.size,
+                        first_seen: now,
+                        observed_at: now,
+                    });
+                }
+            }
+        }
+
+        self.track_persistence(&mut found, now);
+        found.sort_by(|a, b| b.edge.total_cmp(&a.edge));
+        found
+    }
+
+    fn track_persistence(&mut self, found: &mut [ArbOpportunity], now: DateTime<Utc>) {
+        let mut still_open = HashMap::new();
+
+        for opportunity in found.iter_mut() {
+            let key = opportunity.key();
+            let mut active = self.active.remove(&key).unwrap_or(ActiveEdge {
+                linked_id: opportunity.linked_id.clone(),
+                first_seen: now,
+                last_seen: now,
+                best_edge: 0.0,
+            });
+            active.last_seen = now;
+            active.best_edge = active.best_edge.max(opportunity.edge);
+            opportunity.first_seen = active.first_seen;
+            still_open.insert(key, active);
+        }
+
+        for (key, gone) in self.active.drain() {
+            self.closed.push(ClosedEdge {
+                key,
+                linked_id: gone.linked_id,
+                first_seen: gone.first_seen,
+                last_seen: gone.last_seen,
+                best_edge: gone.best_edge,
+            });
+        }
+        self.active = still_open;
+    }
+
+    /// Fetches every leg's books from the source registered for its
+    /// exchange, publishes them to the manager and scans. Legs without a
+    /// source, or whose fetch fails, keep their previous book.
+    pub async fn poll(
+        &mut self,
+        sources: &HashMap<String, Arc<dyn OrderbookSource>>,
+    ) -> Vec<ArbOpportunity> {
+        let requests: Vec<(String, Arc<dyn OrderbookSource>)> = self
+            .linked
+            .iter()
+            .flat_map(|l| l.legs.iter())
+            .filter_map(|leg| sources.get(&leg.exchange).map(|s| (leg, s)))
+            .flat_map(|(leg, source)| {
+                leg.book_ids()
+                    .map(move |id| (id.to_string(), source.clone()))
+            })
+            .collect();
+
+        let results =
+            join_all(requests.iter().map(|(book_id, source)| async move {
+                (book_id, source.fetch_book(book_id).await)
+            }))
+            .await;
+
+        for (book_id, result) in results {
+            match result {
+                Ok(mut book) => {
+                    book.timestamp.get_or_insert_with(Utc::now);
+                    self.orderbooks.update(book_id.clone(), book);
+                }
+                Err(err) => tracing::warn!("failed to fetch book {book_id}: {err}"),
+            }
+        }
+
+        self.scan()
+    }
+
+    /// Rescans whenever any leg's book changes in the manager and passes
+    /// the result to `on_scan`. Returns when `on_scan` returns `false` or
+    /// the books are removed from the manager.
+    pub async fn watch<F>(&mut self, mut on_scan: F) -> Result<(), DrmError>
+    where
+        F: FnMut(&[ArbOpportunity]) -> bool,
+    {
+        let mut receivers: Vec<_> = self
+            .book_ids()
+            .iter()
+            .map(|id| self.orderbooks.watch(id))
+            .collect();
+        if receivers.is_empty() {
+            return Err(DrmError::InvalidInput("no linked markets to watch".into()));
+        }
+
+        loop {
+            let (changed, _, _) =
+                select_all(receivers.iter_mut().map(|rx| Box::pin(rx.changed()))).await;
+            if changed.is_err() {
+                return Ok(());
+            }
+
+            let opportunities = self.scan();
+            if !on_scan(&opportunities) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+
+    fn make_book(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Orderbook {
+        Orderbook {
+            bids: bids.iter().map(|&(p, s)| PriceLevel::new(p, s)).collect(),
+            asks: asks.iter().map(|&(p, s)| PriceLevel::new(p, s)).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn make_scanner(fee_model: FeeModel) -> ArbScanner {
+        let linked = LinkedMarket::new(
+            "fed-cut",
+            vec![
+                ArbLeg::new("polymarket", "pm-1", "pm-yes")
+                    .with_no_book("pm-no")
+                    .with_fee_model(fee_model),
+                ArbLeg::new("kalshi", "FED-CUT", "FED-CUT").with_fee_model(fee_model),
+            ],
+        );
+        ArbScanner::new(vec![linked], OrderbookManager::new())
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(secs, 0).unwrap()
+    }
+
+    #[test]
+    fn test_finds_complement_edge_after_fees() {
+        // given
+        let mut scanner = make_scanner(FeeModel::Proportional {
+            maker: 0.0,
+            taker: 0.01,
+        });
+        let books = scanner.orderbook_manager().clone();
+        books.update("pm-yes", make_book(&[(0.38, 100.0)], &[(0.40, 100.0)]));
+        books.update("pm-no", make_book(&[(0.58, 100.0)], &[(0.62, 100.0)]));
+        books.update("FED-CUT", make_book(&[(0.55, 50.0)], &[(0.57, 50.0)]));
+
+        // when
+        let found = scanner.scan_at(at(0));
+
+        // then
+        assert_eq!(found.len(), 1);
+        let arb = &found[0];
+        assert_eq!(arb.buy_yes.exchange, "polymarket");
+        assert_eq!(arb.buy_no.exchange, "kalshi");
+        assert!((arb.buy_no.limit_price - 0.45).abs() < 1e-9);
+        assert!((arb.size - 50.0).abs() < 1e-9);
+        let expected_cost = 50.0 * 0.85 * 1.01;
+        assert!((arb.cost - expected_cost).abs() < 1e-9);
+        assert!((arb.edge - (50.0 - expected_cost)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_walks_levels_until_unprofitable() {
+        // given
+        let mut scanner = make_scanner(FeeModel::None).with_min_edge(0.03);
+        let books = scanner.orderbook_manager().clone();
+        books.update(
+            "pm-yes",
+            make_book(&[], &[(0.40, 10.0), (0.45, 10.0), (0.50, 10.0)]),
+        );
+        books.update("FED-CUT", make_book(&[(0.55, 15.0), (0.52, 100.0)], &[]));
+
+        // when
+        let found = scanner.scan_at(at(0));
+
+        // then
+        assert_eq!(found.len(), 1);
+        assert!((found[0].size - 20.0).abs() < 1e-9);
+        assert!((found[0].buy_yes.limit_price - 0.45).abs() < 1e-9);
+        assert!((found[0].buy_no.limit_price - 0.48).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tracks_edge_persistence() {
+        // given
+        let mut scanner = make_scanner(FeeModel::None);
+        let books = scanner.orderbook_manager().clone();
+        books.update("pm-yes", make_book(&[], &[(0.40, 10.0)]));
+        books.update("FED-CUT", make_book(&[(0.55, 10.0)], &[]));
+        scanner.scan_at(at(0));
+
+        // when
+        let later = scanner.scan_at(at(5));
+        books.update("FED-CUT", make_book(&[(0.30, 10.0)], &[]));
+        let gone = scanner.scan_at(at(8));
+
+        // then
+        assert_eq!(later[0].persisted(), Duration::seconds(5));
+        assert!(gone.is_empty());
+        let closed = scanner.closed_edges();
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].duration(), Duration::seconds(5));
+        assert!((closed[0].best_edge - 1.5).abs() < 1e-9);
+    }
+
+    struct StaticSource(HashMap<String, Orderbook>);
+
+    #[async_trait]
+    impl OrderbookSource for StaticSource {
+        async fn fetch_book(&self, book_id: &str) -> Result<Orderbook, DrmError> {
+            self.0
+                .get(book_id)
+                .cloned()
+                .ok_or_else(|| DrmError::InvalidInput(book_id.to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_fetches_books_from_sources() {
+        // given
+        let mut scanner = make_scanner(FeeModel::None);
+        let polymarket = StaticSource(HashMap::from([
+            ("pm-yes".to_string(), make_book(&[], &[(0.40, 10.0)])),
+            ("pm-no".to_string(), make_book(&[], &[(0.70, 10.0)])),
+        ]));
+        let kalshi = StaticSource(HashMap::from([(
+            "FED-CUT".to_string(),
+            make_book(&[(0.55, 10.0)], &[(0.25, 10.0)]),
+        )]));
+        let sources: HashMap<String, Arc<dyn OrderbookSource>> = HashMap::from([
+            (
+                "polymarket".to_string(),
+                Arc::new(polymarket) as Arc<dyn OrderbookSource>,
+            ),
+            (
+                "kalshi".to_string(),
+                Arc::new(kalshi) as Arc<dyn OrderbookSource>,
+            ),
+        ]);
+
+        // when
+        let found = scanner.poll(&sources).await;
+
+        // then
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].buy_yes.exchange, "polymarket");
+        assert!((found[0].edge - 1.5).abs() < 1e-9);
+        assert_eq!(found[1].buy_yes.exchange, "kalshi");
+        assert!((found[1].edge - 0.5).abs() < 1e-9);
+        assert!(scanner.orderbook_manager().get("pm-no").is_some());
+    }
+}
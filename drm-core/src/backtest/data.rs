@@ -0,0 +1,180 @@
+use chrono::{DateTime, Utc};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::error::DrmError;
+use crate::models::{Orderbook, PriceLevel, PricePoint};
+
+/// One book observation replayed by the backtest.
+#[derive(Debug, Clone)]
+pub struct MarketEvent {
+    pub timestamp: DateTime<Utc>,
+    pub token_id: String,
+    pub orderbook: Orderbook,
+}
+
+/// Time-ordered book observations for one or more tokens.
+#[derive(Debug, Clone, Default)]
+pub struct BacktestData {
+    events: Vec<MarketEvent>,
+}
+
+impl BacktestData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses recorded books as-is. Books without a timestamp are dropped.
+    pub fn from_orderbooks(orderbooks: impl IntoIterator<Item = Orderbook>) -> Self {
+        let mut data = Self::new();
+        for orderbook in orderbooks {
+            data.push_orderbook(orderbook);
+        }
+        data
+    }
+
+    /// Synthesizes a one-level book per point at `price ± half_spread` with
+    /// `size` on each side. Useful when only `fetch_price_history` output is
+    /// available.
+    pub fn from_price_history(
+        market_id: &str,
+        token_id: &str,
+        points: &[PricePoint],
+        half_spread: f64,
+        size: f64,
+    ) -> Self {
+        let mut data = Self::new();
+        for point in points {
+            let bid = (point.price - half_spread).max(0.001);
+            let ask = (point.price + half_spread).min(0.999);
+            data.push_orderbook(Orderbook {
+                market_id: market_id.to_string(),
+                asset_id: token_id.to_string(),
+                bids: vec![PriceLevel::new(bid, size)],
+                asks: vec![PriceLevel::new(ask, size)],
+                last_update_id: None,
+                timestamp: Some(point.timestamp),
+            });
+        }
+        data
+    }
+
+    /// Reads books recorded one JSON object per line.
+    pub fn load_jsonl(path: impl AsRef<Path>) -> Result<Self, DrmError> {
+        let file = File::open(path.as_ref())
+            .map_err(|e| DrmError::InvalidInput(format!("cannot open recording: {e}")))?;
+
+        let mut data = Self::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| DrmError::Other(e.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            data.push_orderbook(serde_json::from_str(&line)?);
+        }
+        Ok(data)
+    }
+
+    pub fn write_jsonl(&self, path: impl AsRef<Path>) -> Result<(), DrmError> {
+        let file = File::create(path.as_ref())
+            .map_err(|e| DrmError::InvalidInput(format!("cannot create recording: {e}")))?;
+        let mut writer = BufWriter::new(file);
+
+        for event in &self.events {
+            serde_json::to_writer(&mut writer, &event.orderbook)?;
+            writer
+                .write_all(b"\n")
+                .map_err(|e| DrmError::Other(e.to_string()))?;
+        }
+        writer.flush().map_err(|e| DrmError::Other(e.to_string()))
+    }
+
+    pub fn push_orderbook(&mut self, orderbook: Orderbook) {
+        let timestamp = match orderbook.timestamp {
+            Some(t) => t,
+            None => return,
+        };
+
+        let event = MarketEvent {
+            timestamp,
+            token_id: orderbook.asset_id.clone(),
+            orderbook,
+        };
+        let idx = self.events.partition_point(|e| e.timestamp <= timestamp);
+        self.events.insert(idx, event);
+    }
+
+    /// Interleaves another dataset by timestamp.
+    pub fn merge(mut self, other: BacktestData) -> Self {
+        for event in other.events {
+            self.push_orderbook(event.orderbook);
+        }
+        self
+    }
+
+    pub fn events(&self) -> &[MarketEvent] {
+        &self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn start(&self) -> Option<DateTime<Utc>> {
+        self.events.first().map(|e| e.timestamp)
+    }
+
+    pub fn end(&self) -> Option<DateTime<Utc>> {
+        self.events.last().map(|e| e.timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn point(secs: i64, price: f64) -> PricePoint {
+        PricePoint {
+            timestamp: Utc.timestamp_opt(secs, 0).unwrap(),
+            price,
+            raw: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_price_history_round_trips_through_jsonl() {
+        // given
+        let yes = BacktestData::from_price_history(
+            "market",
+            "yes",
+            &[point(20, 0.5), point(0, 0.4)],
+            0.01,
+            100.0,
+        );
+        let no = BacktestData::from_price_history("market", "no", &[point(10, 0.6)], 0.01, 100.0);
+        let path = std::env::temp_dir().join(format!("drm-backtest-{}.jsonl", std::process::id()));
+
+        // when
+        yes.merge(no).write_jsonl(&path).unwrap();
+        let loaded = BacktestData::load_jsonl(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // then
+        let tokens: Vec<&str> = loaded
+            .events()
+            .iter()
+            .map(|e| e.token_id.as_str())
+            .collect();
+        assert_eq!(tokens, vec!["yes", "no", "yes"]);
+        let first = &loaded.events()[0].orderbook;
+        assert!((first.best_bid().unwrap() - 0.39).abs() < 1e-9);
+        assert!((first.best_ask().unwrap() - 0.41).abs() < 1e-9);
+        assert_eq!(loaded.end(), Some(Utc.timestamp_opt(20, 0).unwrap()));
+    }
+}
@@ -0,0 +1,373 @@
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+
+use crate::error::DrmError;
+use crate::strategy::{EventDrivenStrategy, OrderEvent, Strategy};
+
+use super::data::BacktestData;
+use super::report::{BacktestReport, EquityPoint};
+use super::simulator::{SimFill, SimulatedExchange};
+
+#[derive(Debug, Clone, Default)]
+pub struct BacktestConfig {
+    /// Simulated interval between `on_tick`/`on_timer` calls. `None` uses
+    /// the strategy's `tick_interval_ms` for tick strategies and disables
+    /// the timer for event-driven ones.
+    pub tick_interval_ms: Option<u64>,
+    /// Abort on the first strategy error instead of recording and moving on.
+    pub stop_on_error: bool,
+    pub verbose: bool,
+}
+
+/// Replays [`BacktestData`] through a [`SimulatedExchange`] and a strategy
+/// on a simulated clock. Nothing touches the network or the wall clock.
+pub struct Backtest {
+    data: BacktestData,
+    config: BacktestConfig,
+    errors: Vec<String>,
+}
+
+impl Backtest {
+    pub fn new(data: BacktestData, config: BacktestConfig) -> Self {
+        Self {
+            data,
+            config,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Strategy errors recorded during the last run.
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    fn log(&self, message: &str) {
+        if self.config.verbose {
+            println!("[backtest] {message}");
+        }
+    }
+
+    fn record_error(&mut self, now: DateTime<Utc>, err: DrmError) -> Result<(), DrmError> {
+        if self.config.stop_on_error {
+            return Err(err);
+        }
+        self.log(&format!("{now}: strategy error: {err}"));
+        self.errors.push(format!("{now}: {err}"));
+        Ok(())
+    }
+
+    fn equity_point(exchange: &SimulatedExchange, timestamp: DateTime<Utc>) -> EquityPoint {
+        let (cash, inventory_value) = exchange.equity();
+        let equity = cash + inventory_value;
+        EquityPoint {
+            timestamp,
+            cash,
+            inventory_value,
+            equity,
+            pnl: equity - exchange.initial_balance(),
+            inventory: exchange.positions().iter().map(|p| p.size).sum(),
+        }
+    }
+
+    fn report(exchange: &SimulatedExchange, equity_curve: Vec<EquityPoint>) -> BacktestReport {
+        let final_inventory = exchange
+            .positions()
+            .into_iter()
+            .map(|p| (format!("{}:{}", p.market_id, p.outcome), p.size))
+            .collect();
+
+        BacktestReport::build(
+            exchange.initial_balance(),
+            equity_curve,
+            &exchange.fills(),
+            exchange.orders_placed(),
+            exchange.placed_volume(),
+            final_inventory,
+        )
+    }
+
+    /// Runs a tick-driven [`Strategy`]: `start`, then `on_tick` every tick
+    /// interval of simulated time, then `stop`.
+    pub async fn run<S: Strategy>(
+        &mut self,
+        exchange: &Arc<SimulatedExchange>,
+        strategy: &mut S,
+    ) -> Result<BacktestReport, DrmError> {
+        self.errors.clear();
+        let start = match self.data.start() {
+            Some(start) => start,
+            None => return Ok(Self::report(exchange, Vec::new())),
+        };
+        let interval_ms = self
+            .config
+            .tick_interval_ms
+            .unwrap_or(strategy.config().tick_interval_ms)
+            .max(1);
+        let interval = Duration::milliseconds(interval_ms as i64);
+
+        exchange.set_time(start);
+        strategy.start().await?;
+
+        let mut next_tick = start;
+        let mut equity_curve = Vec::with_capacity(self.data.len());
+        let events = self.data.events().to_vec();
+
+        for (i, event) in events.iter().enumerate() {
+            exchange.advance(event.timestamp, &event.token_id, &event.orderbook);
+
+            let batch_done = events
+                .get(i + 1)
+                .is_none_or(|next| next.timestamp > event.timestamp);
+            if !batch_done {
+                continue;
+            }
+
+            while next_tick <= event.timestamp {
+                if let Err(err) = strategy.on_tick().await {
+                    self.record_error(event.timestamp, err)?;
+                }
+                next_tick += interval;
+            }
+
+            equity_curve.push(Self::equity_point(exchange, event.timestamp));
+        }
+
+        strategy.stop().await?;
+        let report = Self::report(exchange, equity_curve);
+        self.log(&report.summary());
+        Ok(report)
+    }
+
+    /// Runs an [`EventDrivenStrategy`]: fills go to `on_fill`, every
+    /// replayed book to `on_orderbook`, and `on_timer` fires on the
+    /// configured interval.
+    pub async fn run_event_driven<S: EventDrivenStrategy>(
+        &mut self,
+        exchange: &Arc<SimulatedExchange>,
+        strategy: &mut S,
+    ) -> Result<BacktestReport, DrmError> {
+        self.errors.clear();
+        let start = match self.data.start() {
+            Some(start) => start,
+            None => return Ok(Self::report(exchange, Vec::new())),
+        };
+        let interval = self
+            .config
+            .tick_interval_ms
+            .map(|ms| Duration::milliseconds(ms.max(1) as i64));
+
+        exchange.set_time(start);
+        strategy.on_start().await?;
+
+        let mut next_timer = start;
+        let mut equity_curve = Vec::with_capacity(self.data.len());
+        let events = self.data.events().to_vec();
+
+        for event in &events {
+            if let Some(interval) = interval {
+                while next_timer < event.timestamp {
+                    exchange.set_time(next_timer);
+                    if let Err(err) = strategy.on_timer().await {
+                        self.record_error(next_timer, err)?;
+                    }
+                    next_timer += interval;
+                }
+            }
+
+            let fills = exchange.advance(event.timestamp, &event.token_id, &event.orderbook);
+            for fill in &fills {
+                if let Err(err) = Self::dispatch_fill(strategy, fill).await {
+                    self.record_error(event.timestamp, err)?;
+                }
+            }
+
+            let orderbook = Arc::new(event.orderbook.clone());
+            if let Err(err) = strategy.on_orderbook(&event.token_id, orderbook).await {
+                self.record_error(event.timestamp, err)?;
+            }
+
+            equity_curve.push(Self::equity_point(exchange, event.timestamp));
+        }
+
+        strategy.on_stop().await?;
+        let report = Self::report(exchange, equity_curve);
+        self.log(&report.summary());
+        Ok(report)
+    }
+
+    async fn dispatch_fill<S: EventDrivenStrategy>(
+        strategy: &mut S,
+        fill: &SimFill,
+    ) -> Result<(), DrmError> {
+        let event = if fill.order.is_filled() {
+            OrderEvent::Filled
+        } else {
+            OrderEvent::PartialFill
+        };
+        strategy.on_fill(event, &fill.order, fill.size).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::Exchange;
+    use crate::models::{OrderSide, PricePoint};
+    use crate::strategy::{MarketMaker, MarketMakingConfig, StrategyConfig, StrategyState};
+    use crate::testing::make_market;
+    use async_trait::async_trait;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    struct BuyOnce {
+        exchange: Arc<SimulatedExchange>,
+        config: StrategyConfig,
+        state: StrategyState,
+        bought: bool,
+    }
+
+    #[async_trait]
+    impl Strategy for BuyOnce {
+        fn name(&self) -> &str {
+            "buy_once"
+        }
+
+        fn config(&self) -> &StrategyConfig {
+            &self.config
+        }
+
+        fn state(&self) -> StrategyState {
+            self.state
+        }
+
+        async fn on_tick(&mut self) -> Result<(), DrmError> {
+            if !self.bought {
+                self.bought = true;
+                self.exchange
+                    .create_order(
+                        "market-1",
+                        "Yes",
+                        OrderSide::Buy,
+                        0.55,
+                        10.0,
+                        HashMap::new(),
+                    )
+                    .await?;
+            }
+            Ok(())
+        }
+
+        async fn start(&mut self) -> Result<(), DrmError> {
+            self.state = StrategyState::Running;
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<(), DrmError> {
+            self.state = StrategyState::Stopped;
+            Ok(())
+        }
+
+        fn pause(&mut self) {}
+
+        fn resume(&mut self) {}
+    }
+
+    fn price_series(prices: &[f64]) -> Vec<PricePoint> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, &price)| PricePoint {
+                timestamp: Utc.timestamp_opt(i as i64 * 60, 0).unwrap(),
+                price,
+                raw: serde_json::Value::Null,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_tick_strategy_report() {
+        // given
+        let data = BacktestData::from_price_history(
+            "market-1",
+            "yes-token",
+            &price_series(&[0.50, 0.40, 0.60]),
+            0.01,
+            100.0,
+        );
+        let exchange = Arc::new(SimulatedExchange::new(100.0).with_market(make_market("market-1")));
+        let mut strategy = BuyOnce {
+            exchange: exchange.clone(),
+            config: StrategyConfig {
+                tick_interval_ms: 60_000,
+                ..Default::default()
+            },
+            state: StrategyState::Stopped,
+            bought: false,
+        };
+
+        // when
+        let report = Backtest::new(data, BacktestConfig::default())
+            .run(&exchange, &mut strategy)
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!(report.equity_curve.len(), 3);
+        assert_eq!(report.orders_placed, 1);
+        assert_eq!(report.taker_fills, 1);
+        assert!((report.fill_ratio - 1.0).abs() < 1e-9);
+        assert!((report.total_pnl - 10.0 * (0.60 - 0.51)).abs() < 1e-9);
+        assert!((report.max_drawdown - 10.0 * (0.51 - 0.40)).abs() < 1e-9);
+        assert!((report.turnover - 0.051).abs() < 1e-9);
+        assert_eq!(report.final_inventory["market-1:Yes"], 10.0);
+    }
+
+    #[tokio::test]
+    async fn test_event_driven_market_maker_runs_offline() {
+        // given
+        let yes = BacktestData::from_price_history(
+            "market-1",
+            "yes-token",
+            &price_series(&[0.50, 0.46, 0.50, 0.54, 0.50]),
+            0.01,
+            50.0,
+        );
+        let no = BacktestData::from_price_history(
+            "market-1",
+            "no-token",
+            &price_series(&[0.50, 0.54, 0.50, 0.46, 0.50]),
+            0.01,
+            50.0,
+        );
+        let exchange = Arc::new(
+            SimulatedExchange::new(1000.0)
+                .with_market(make_market("market-1"))
+                .with_queue_model(super::super::QueueModel::Front),
+        );
+        let config = StrategyConfig {
+            spread_bps: 400,
+            ..Default::default()
+        };
+        let mut maker = MarketMaker::new(
+            exchange.clone(),
+            "market-1".to_string(),
+            config,
+            MarketMakingConfig::default(),
+        );
+
+        // when
+        let mut backtest = Backtest::new(yes.merge(no), BacktestConfig::default());
+        let report = backtest
+            .run_event_driven(&exchange, &mut maker)
+            .await
+            .unwrap();
+
+        // then
+        assert!(backtest.errors().is_empty());
+        assert_eq!(report.equity_curve.len(), 10);
+        assert!(report.orders_placed > 0);
+        assert!(report.fill_count > 0);
+        assert!(report.fill_ratio > 0.0 && report.fill_ratio <= 1.0);
+        assert!(exchange.fetch_open_orders(None).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,9 @@
+mod data;
+mod engine;
+mod report;
+mod simulator;
+
+pub use data::*;
+pub use engine::*;
+pub use report::*;
+pub use simulator::*;
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::simulator::SimFill;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub timestamp: DateTime<Utc>,
+    pub cash: f64,
+    pub inventory_value: f64,
+    pub equity: f64,
+    pub pnl: f64,
+    /// Total shares held across all outcomes.
+    pub inventory: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub initial_balance: f64,
+    pub final_equity: f64,
+    pub total_pnl: f64,
+    pub max_drawdown: f64,
+    /// Max drawdown as a fraction of the equity peak it fell from.
+    pub max_drawdown_pct: f64,
+    pub orders_placed: usize,
+    pub fill_count: usize,
+    /// Filled size divided by placed size.
+    pub fill_ratio: f64,
+    pub maker_fills: usize,
+    pub taker_fills: usize,
+    pub traded_notional: f64,
+    /// Traded notional divided by initial balance.
+    pub turnover: f64,
+    pub fees_paid: f64,
+    /// Final shares held, keyed by `market_id:outcome`.
+    pub final_inventory: HashMap<String, f64>,
+    pub equity_curve: Vec<EquityPoint>,
+}
+
+impl BacktestReport {
+    pub(crate) fn build(
+        initial_balance: f64,
+        equity_curve: Vec<EquityPoint>,
+        fills: &[SimFill],
+        orders_placed: usize,
+        placed_volume: f64,
+        final_inventory: HashMap<String, f64>,
+    ) -> Self {
+        let mut peak = initial_balance;
+        let mut max_drawdown = 0.0_f64;
+        let mut max_drawdown_pct = 0.0_f64;
+        for point in &equity_curve {
+            peak = peak.max(point.equity);
+            let drawdown = peak - point.equity;
+            if drawdown > max_drawdown {
+                max_drawdown = drawdown;
+                max_drawdown_pct = if peak > 0.0 { drawdown / peak } else { 0.0 };
+            }
+        }
+
+        let filled: f64 = fills.iter().map(|f| f.size).sum();
+        let traded_notional: f64 = fills.iter().map(|f| f.size * f.price).sum();
+        let final_equity = equity_curve
+            .last()
+            .map(|p| p.equity)
+            .unwrap_or(initial_balance);
+
+        Self {
+            start: equity_curve.first().map(|p| p.timestamp),
+            end: equity_curve.last().map(|p| p.timestamp),
+            initial_balance,
+            final_equity,
+            total_pnl: final_equity - initial_balance,
+            max_drawdown,
+            max_drawdown_pct,
+            orders_placed,
+            fill_count: fills.len(),
+            fill_ratio: if placed_volume > 0.0 {
+                filled / placed_volume
+            } else {
+                0.0
+            },
+            maker_fills: fills.iter().filter(|f| f.maker).count(),
+            taker_fills: fills.iter().filter(|f| !f.maker).count(),
+            traded_notional,
+            turnover: if initial_balance > 0.0 {
+                traded_notional / initial_balance
+            } else {
+                0.0
+            },
+            fees_paid: fills.iter().map(|f| f.fee).sum(),
+            final_inventory,
+            equity_curve,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "PnL {:+.2} ({:.2} -> {:.2}), max drawdown {:.2} ({:.1}%), {} fills on {} orders \
+             (fill ratio {:.1}%), turnover {:.2}x, fees {:.2}",
+            self.total_pnl,
+            self.initial_balance,
+            self.final_equity,
+            self.max_drawdown,
+            self.max_drawdown_pct * 100.0,
+            self.fill_count,
+            self.orders_placed,
+            self.fill_ratio * 100.0,
+            self.turnover,
+            self.fees_paid,
+        )
+    }
+}
@@ -0,0 +1,684 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::error::{DrmError, ExchangeError};
+use crate::exchange::{Exchange, FetchMarketsParams, FetchOrdersParams};
+use crate::models::{Market, Order, OrderSide, OrderStatus, Orderbook, Position, PriceLevel};
+
+/// How fees are charged on simulated fills.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FeeModel {
+    #[default]
+    None,
+    /// Fraction of fill notional, split by liquidity role.
+    Proportional { maker: f64, taker: f64 },
+    /// `rate * min(price, 1 - price) * size`, charged on taker fills only
+    /// (the curve used by Polymarket's fee-enabled markets).
+    PriceCurve { rate: f64 },
+}
+
+impl FeeModel {
+    pub fn fee(&self, price: f64, size: f64, maker: bool) -> f64 {
+        match *self {
+            FeeModel::None => 0.0,
+            FeeModel::Proportional { maker: m, taker: t } => {
+                price * size * if maker { m } else { t }
+            }
+            FeeModel::PriceCurve { rate } if !maker => rate * price.min(1.0 - price) * size,
+            FeeModel::PriceCurve { .. } => 0.0,
+        }
+    }
+}
+
+/// Where a resting order joins the queue at its price level.
+///
+/// Recorded books carry no trades, so volume traded at a level is inferred
+/// from its size shrinking between snapshots while the level survives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueueModel {
+    /// Ahead of everyone: any decrease at our level fills us.
+    Front,
+    /// Behind the size already displayed when the order arrived.
+    #[default]
+    Back,
+}
+
+/// A simulated execution.
+#[derive(Debug, Clone)]
+pub struct SimFill {
+    pub timestamp: DateTime<Utc>,
+    /// Order state after this fill.
+    pub order: Order,
+    pub token_id: String,
+    pub price: f64,
+    pub size: f64,
+    pub fee: f64,
+    pub maker: bool,
+}
+
+struct SimOrder {
+    order: Order,
+    token_id: String,
+    queue_ahead: f64,
+    level_size: f64,
+}
+
+struct SimState {
+    now: DateTime<Utc>,
+    cash: f64,
+    positions: HashMap<(String, String), Position>,
+    tokens: HashMap<(String, String), String>,
+    books: HashMap<String, Orderbook>,
+    orders: Vec<SimOrder>,
+    fills: Vec<SimFill>,
+    placed_volume: f64,
+    next_id: usize,
+}
+
+impl SimState {
+    fn held(&self, market_id: &str, outcome: &str) -> f64 {
+        self.positions
+            .get(&(market_id.to_string(), outcome.to_string()))
+            .map(|p| p.size)
+            .unwrap_or(0.0)
+    }
+
+    fn apply_fill(&mut self, idx: usize, price: f64, size: f64, fee: f64, maker: bool) {
+        let entry = &mut self.orders[idx];
+        entry.order.filled += size;
+        entry.order.status = if entry.order.remaining() <= 1e-9 {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::PartiallyFilled
+        };
+        entry.order.updated_at = Some(self.now);
+        let order = entry.order.clone();
+        let token_id = entry.token_id.clone();
+
+        let key = (order.market_id.clone(), order.outcome.clone());
+        match order.side {
+            OrderSide::Buy => {
+                self.cash -= price * size + fee;
+                let position = self.positions.entry(key).or_insert_with(|| Position {
+                    market_id: order.market_id.clone(),
+                    outcome: order.outcome.clone(),
+                    size: 0.0,
+                    average_price: 0.0,
+                    current_price: price,
+                });
+                let new_size = position.size + size;
+                position.average_price =
+                    (position.size * position.average_price + size * price) / new_size;
+                position.size = new_size;
+            }
+            OrderSide::Sell => {
+                self.cash += price * size - fee;
+                if let Some(position) = self.positions.get_mut(&key) {
+                    position.size -= size;
+                    if position.size <= 1e-9 {
+                        self.positions.remove(&key);
+                    }
+                }
+            }
+        }
+
+        self.fills.push(SimFill {
+            timestamp: self.now,
+            order,
+            token_id,
+            price,
+            size,
+            fee,
+            maker,
+        });
+    }
+
+    /// Caps a fill by available cash or shares.
+    fn affordable(&self, order: &Order, price: f64, size: f64, fee_model: FeeModel) -> f64 {
+        match order.side {
+            OrderSide::Buy => {
+                let unit = price + fee_model.fee(price, 1.0, false);
+                size.min(self.cash / unit)
+            }
+            OrderSide::Sell => size.min(self.held(&order.market_id, &order.outcome)),
+        }
+    }
+}
+
+fn same_side_levels(book: &Orderbook, side: OrderSide) -> &[PriceLevel] {
+    match side {
+        OrderSide::Buy => &book.bids,
+        OrderSide::Sell => &book.asks,
+    }
+}
+
+fn crossing_levels(book: &Orderbook, side: OrderSide, price: f64) -> Vec<PriceLevel> {
+    match side {
+        OrderSide::Buy => book
+            .asks
+            .iter()
+            .filter(|l| l.price <= price + 1e-9)
+            .copied()
+            .collect(),
+        OrderSide::Sell => book
+            .bids
+            .iter()
+            .filter(|l| l.price >= price - 1e-9)
+            .copied()
+            .collect(),
+    }
+}
+
+fn level_size(levels: &[PriceLevel], price: f64) -> f64 {
+    levels
+        .iter()
+        .find(|l| (l.price - price).abs() < 1e-9)
+        .map(|l| l.size)
+        .unwrap_or(0.0)
+}
+
+/// Deterministic [`Exchange`] used by the backtest engine.
+///
+/// Time only moves when [`SimulatedExchange::advance`] replays a book.
+/// Marketable orders take the crossing levels at arrival; resting orders
+/// fill at their limit when the opposite side trades through them or, per
+/// the [`QueueModel`], when volume at their own level is consumed. Replayed
+/// books are not depleted by our fills.
+pub struct SimulatedExchange {
+    markets: Mutex<Vec<Market>>,
+    fee_model: FeeModel,
+    queue_model: QueueModel,
+    initial_balance: f64,
+    state: Mutex<SimState>,
+}
+
+impl SimulatedExchange {
+    pub fn new(initial_balance: f64) -> Self {
+        Self {
+            markets: Mutex::new(Vec::new()),
+            fee_model: FeeModel::default(),
+            queue_model: QueueModel::default(),
+            initial_balance,
+            state: Mutex::new(SimState {
+                now: DateTime::<Utc>::UNIX_EPOCH,
+                cash: initial_balance,
+                positions: HashMap::new(),
+                tokens: HashMap::new(),
+                books: HashMap::new(),
+                orders: Vec::new(),
+                fills: Vec::new(),
+                placed_volume: 0.0,
+                next_id: 0,
+            }),
+        }
+    }
+
+    pub fn with_market(self, market: Market) -> Self {
+        self.markets.lock().unwrap().push(market);
+        self
+    }
+
+    pub fn with_fee_model(mut self, fee_model: FeeModel) -> Self {
+        self.fee_model = fee_model;
+        self
+    }
+
+    pub fn with_queue_model(mut self, queue_model: QueueModel) -> Self {
+        self.queue_model = queue_model;
+        self
+    }
+
+    pub fn initial_balance(&self) -> f64 {
+        self.initial_balance
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.state.lock().unwrap().now
+    }
+
+    pub fn set_time(&self, now: DateTime<Utc>) {
+        self.state.lock().unwrap().now = now;
+    }
+
+    pub fn cash(&self) -> f64 {
+        self.state.lock().unwrap().cash
+    }
+
+    pub fn orderbook(&self, token_id: &str) -> Option<Orderbook> {
+        self.state.lock().unwrap().books.get(token_id).cloned()
+    }
+
+    pub fn fills(&self) -> Vec<SimFill> {
+        self.state.lock().unwrap().fills.clone()
+    }
+
+    pub fn orders_placed(&self) -> usize {
+        self.state.lock().unwrap().next_id
+    }
+
+    /// Total size of every accepted order.
+    pub fn placed_volume(&self) -> f64 {
+        self.state.lock().unwrap().placed_volume
+    }
+
+    pub fn positions(&self) -> Vec<Position> {
+        self.state
+            .lock()
+            .unwrap()
+            .positions
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Cash plus positions marked at the mid of their last book (or average
+    /// price when no two-sided book has been seen).
+    pub fn equity(&self) -> (f64, f64) {
+        let state = self.state.lock().unwrap();
+        let inventory_value: f64 = state
+            .positions
+            .iter()
+            .map(|(key, p)| {
+                let mark = state
+                    .tokens
+                    .get(key)
+                    .and_then(|t| state.books.get(t))
+                    .and_then(|b| b.mid_price())
+                    .unwrap_or(p.average_price);
+                p.size * mark
+            })
+            .sum();
+        (state.cash, inventory_value)
+    }
+
+    /// Moves the clock to `timestamp`, stores `orderbook` for `token_id`
+    /// and matches resting orders on that token. Returns the new fills.
+    pub fn advance(
+        &self,
+        timestamp: DateTime<Utc>,
+        token_id: &str,
+        orderbook: &Orderbook,
+    ) -> Vec<SimFill> {
+        let mut state = self.state.lock().unwrap();
+        state.now = timestamp;
+        state.books.insert(token_id.to_string(), orderbook.clone());
+        let first_fill = state.fills.len();
+
+        for idx in 0..state.orders.len() {
+            let (order, level_before, queue_ahead) = {
+                let entry = &state.orders[idx];
+                if entry.token_id != token_id || !entry.order.is_active() {
+                    continue;
+                }
+                (entry.order.clone(), entry.level_size, entry.queue_ahead)
+            };
+
+            let level_now = level_size(same_side_levels(orderbook, order.side), order.price);
+            let traded_at_level = if level_now > 0.0 {
+                (level_before - level_now).max(0.0)
+            } else {
+                0.0
+            };
+            let consumed = traded_at_level.min(queue_ahead);
+            let from_queue = traded_at_level - consumed;
+            let through: f64 = crossing_levels(orderbook, order.side, order.price)
+                .iter()
+                .map(|l| l.size)
+                .sum();
+
+            {
+                let entry = &mut state.orders[idx];
+                entry.queue_ahead = queue_ahead - consumed;
+                entry.level_size = level_now;
+            }
+
+            let wanted = (from_queue + through).min(order.remaining());
+            let size = state.affordable(&order, order.price, wanted, self.fee_model);
+            if size > 1e-9 {
+                let fee = self.fee_model.fee(order.price, size, true);
+                state.apply_fill(idx, order.price, size, fee, true);
+            }
+        }
+
+        state.fills[first_fill..].to_vec()
+    }
+
+    fn find_token(&self, market_id: &str, outcome: &str) -> Option<String> {
+        self.markets
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|m| m.id == market_id)?
+            .get_outcome_tokens()
+            .into_iter()
+            .find(|t| t.outcome == outcome && !t.token_id.is_empty())
+            .map(|t| t.token_id)
+    }
+}
+
+#[async_trait]
+impl Exchange for SimulatedExchange {
+    fn id(&self) -> &'static str {
+        "backtest"
+    }
+
+    fn name(&self) -> &'static str {
+        "Backtest"
+    }
+
+    async fn fetch_markets(
+        &self,
+        _params: Option<FetchMarketsParams>,
+    ) -> Result<Vec<Market>, DrmError> {
+        Ok(self.markets.lock().unwrap().clone())
+    }
+
+    async fn fetch_market(&self, market_id: &str) -> Result<Market, DrmError> {
+        self.markets
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|m| m.id == market_id)
+            .cloned()
+            .ok_or_else(|| ExchangeError::MarketNotFound(market_id.to_string()).into())
+    }
+
+    async fn create_order(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+        params: HashMap<String, String>,
+    ) -> Result<Order, DrmError> {
+        if price <= 0.0 || price >= 1.0 {
+            return Err(ExchangeError::InvalidOrder("Price must be between 0 and 1".into()).into());
+        }
+        if size <= 0.0 {
+            return Err(ExchangeError::InvalidOrder("Size must be positive".into()).into());
+        }
+
+        let token_id = params
+            .get("token_id")
+            .cloned()
+            .or_else(|| self.find_token(market_id, outcome))
+            .ok_or_else(|| {
+                ExchangeError::InvalidOrder(format!("no token for {market_id}/{outcome}"))
+            })?;
+
+        let mut state = self.state.lock().unwrap();
+
+        let available = match side {
+            OrderSide::Buy => {
+                let reserved: f64 = state
+                    .orders
+                    .iter()
+                    .filter(|o| o.order.is_active() && o.order.side == OrderSide::Buy)
+                    .map(|o| o.order.remaining() * o.order.price)
+                    .sum();
+                (state.cash - reserved) / price
+            }
+            OrderSide::Sell => {
+                let reserved: f64 = state
+                    .orders
+                    .iter()
+                    .filter(|o| {
+                        o.order.is_active()
+                            && o.order.side == OrderSide::Sell
+                            && o.order.market_id == market_id
+                            && o.order.outcome == outcome
+                    })
+                    .map(|o| o.order.remaining())
+                    .sum();
+                state.held(market_id, outcome) - reserved
+            }
+        };
+        if size > available + 1e-9 {
+            return Err(ExchangeError::InsufficientFunds(format!(
+                "{size:.2} requested, {available:.2} available"
+            ))
+            .into());
+        }
+
+        let id = format!("bt-{}", state.next_id);
+        state.next_id += 1;
+        state.placed_volume += size;
+        state.tokens.insert(
+            (market_id.to_string(), outcome.to_string()),
+            token_id.clone(),
+        );
+
+        let book = state.books.get(&token_id).cloned().unwrap_or_default();
+        let level = level_size(same_side_levels(&book, side), price);
+        let queue_ahead = match self.queue_model {
+            QueueModel::Front => 0.0,
+            QueueModel::Back => level,
+        };
+
+        let now = state.now;
+        state.orders.push(SimOrder {
+            order: Order {
+                id: id.clone(),
+                market_id: market_id.to_string(),
+                outcome: outcome.to_string(),
+                side,
+                price,
+                size,
+                filled: 0.0,
+                status: OrderStatus::Open,
+                created_at: now,
+                updated_at: None,
+            },
+            token_id,
+            queue_ahead,
+            level_size: level,
+        });
+        let idx = state.orders.len() - 1;
+
+        // Take crossing liquidity level by level at arrival.
+        for level in crossing_levels(&book, side, price) {
+            let remaining = state.orders[idx].order.remaining();
+            if remaining <= 1e-9 {
+                break;
+            }
+            let order = state.orders[idx].order.clone();
+            let take = state.affordable(
+                &order,
+                level.price,
+                level.size.min(remaining),
+                self.fee_model,
+            );
+            if take <= 1e-9 {
+                break;
+            }
+            let fee = self.fee_model.fee(level.price, take, false);
+            state.apply_fill(idx, level.price, take, fee, false);
+        }
+
+        Ok(state.orders[idx].order.clone())
+    }
+
+    async fn cancel_order(
+        &self,
+        order_id: &str,
+        _market_id: Option<&str>,
+    ) -> Result<Order, DrmError> {
+        let mut state = self.state.lock().unwrap();
+        let now = state.now;
+        let entry = state
+            .orders
+            .iter_mut()
+            .find(|o| o.order.id == order_id)
+            .ok_or_else(|| ExchangeError::InvalidOrder(format!("unknown order {order_id}")))?;
+
+        if entry.order.is_active() {
+            entry.order.status = OrderStatus::Cancelled;
+            entry.order.updated_at = Some(now);
+        }
+        Ok(entry.order.clone())
+    }
+
+    async fn fetch_order(
+        &self,
+        order_id: &str,
+        _market_id: Option<&str>,
+    ) -> Result<Order, DrmError> {
+        self.state
+            .lock()
+            .unwrap()
+            .orders
+            .iter()
+            .find(|o| o.order.id == order_id)
+            .map(|o| o.order.clone())
+            .ok_or_else(|| ExchangeError::InvalidOrder(format!("unknown order {order_id}")).into())
+    }
+
+    async fn fetch_open_orders(
+        &self,
+        params: Option<FetchOrdersParams>,
+    ) -> Result<Vec<Order>, DrmError> {
+        let market_id = params.and_then(|p| p.market_id);
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .orders
+            .iter()
+            .map(|o| &o.order)
+            .filter(|o| o.is_active())
+            .filter(|o| market_id.as_ref().is_none_or(|id| &o.market_id == id))
+            .cloned()
+            .collect())
+    }
+
+    async fn fetch_positions(&self, market_id: Option<&str>) -> Result<Vec<Position>, DrmError> {
+        Ok(self
+            .positions()
+            .into_iter()
+            .filter(|p| market_id.is_none_or(|id| p.market_id == id))
+            .collect())
+    }
+
+    async fn fetch_balance(&self) -> Result<HashMap<String, f64>, DrmError> {
+        Ok(HashMap::from([("USDC".to_string(), self.cash())]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::make_market;
+    use chrono::TimeZone;
+
+    fn make_book(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Orderbook {
+        Orderbook {
+            market_id: "market-1".to_string(),
+            asset_id: "yes-token".to_string(),
+            bids: bids.iter().map(|&(p, s)| PriceLevel::new(p, s)).collect(),
+            asks: asks.iter().map(|&(p, s)| PriceLevel::new(p, s)).collect(),
+            last_update_id: None,
+            timestamp: None,
+        }
+    }
+
+    async fn rest_bid(queue_model: QueueModel) -> (SimulatedExchange, String) {
+        let exchange = SimulatedExchange::new(100.0)
+            .with_market(make_market("market-1"))
+            .with_queue_model(queue_model);
+        let t0 = Utc.timestamp_opt(0, 0).unwrap();
+        exchange.advance(
+            t0,
+            "yes-token",
+            &make_book(&[(0.40, 30.0)], &[(0.45, 10.0)]),
+        );
+        let order = exchange
+            .create_order(
+                "market-1",
+                "Yes",
+                OrderSide::Buy,
+                0.40,
+                10.0,
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+        (exchange, order.id)
+    }
+
+    #[tokio::test]
+    async fn test_queue_model_controls_passive_fills() {
+        // given
+        let (front, front_id) = rest_bid(QueueModel::Front).await;
+        let (back, back_id) = rest_bid(QueueModel::Back).await;
+        let t1 = Utc.timestamp_opt(1, 0).unwrap();
+        let traded = make_book(&[(0.40, 20.0)], &[(0.45, 10.0)]);
+
+        // when
+        let front_fills = front.advance(t1, "yes-token", &traded);
+        let back_fills = back.advance(t1, "yes-token", &traded);
+
+        // then
+        assert_eq!(front_fills.len(), 1);
+        assert!((front_fills[0].size - 10.0).abs() < 1e-9);
+        assert!(front_fills[0].maker);
+        assert!(back_fills.is_empty());
+        let back_order = back.fetch_order(&back_id, None).await.unwrap();
+        assert_eq!(back_order.status, OrderStatus::Open);
+        assert_eq!(
+            front.fetch_order(&front_id, None).await.unwrap().status,
+            OrderStatus::Filled
+        );
+
+        // when
+        let t2 = Utc.timestamp_opt(2, 0).unwrap();
+        let fills = back.advance(t2, "yes-token", &make_book(&[(0.38, 5.0)], &[(0.39, 4.0)]));
+
+        // then
+        assert_eq!(fills.len(), 1);
+        assert!((fills[0].size - 4.0).abs() < 1e-9);
+        assert!((fills[0].price - 0.40).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_marketable_order_pays_taker_fee() {
+        // given
+        let exchange = SimulatedExchange::new(100.0)
+            .with_market(make_market("market-1"))
+            .with_fee_model(FeeModel::Proportional {
+                maker: 0.0,
+                taker: 0.02,
+            });
+        let t0 = Utc.timestamp_opt(0, 0).unwrap();
+        exchange.advance(
+            t0,
+            "yes-token",
+            &make_book(&[(0.48, 10.0)], &[(0.50, 10.0), (0.55, 10.0)]),
+        );
+
+        // when
+        let order = exchange
+            .create_order(
+                "market-1",
+                "Yes",
+                OrderSide::Buy,
+                0.60,
+                15.0,
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!(order.status, OrderStatus::Filled);
+        let fills = exchange.fills();
+        assert_eq!(fills.len(), 2);
+        assert!(fills.iter().all(|f| !f.maker));
+        let notional = 10.0 * 0.50 + 5.0 * 0.55;
+        assert!((exchange.cash() - (100.0 - notional * 1.02)).abs() < 1e-9);
+        let (_, inventory) = exchange.equity();
+        assert!((inventory - 15.0 * 0.49).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,167 @@
+use ethers::abi::{parse_abi, Abi, Token};
+use ethers::types::{Address, Bytes, H256, U256};
+use std::sync::OnceLock;
+
+/// Index sets of the Yes and No outcome slots of a binary condition.
+pub const BINARY_PARTITION: [u64; 2] = [1, 2];
+
+pub fn conditional_tokens_abi() -> &'static Abi {
+    static ABI: OnceLock<Abi> = OnceLock::new();
+    ABI.get_or_init(|| {
+        parse_abi(&[
+            "function splitPosition(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] partition, uint256 amount)",
+            "function mergePositions(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] partition, uint256 amount)",
+            "function redeemPositions(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] indexSets)",
+        ])
+        .expect("valid ConditionalTokens abi")
+    })
+}
+
+/// Calldata for `name` on `abi`. Panics if `args` do not match the abi.
+pub fn encode_call(abi: &Abi, name: &str, args: &[Token]) -> Bytes {
+    abi.function(name)
+        .and_then(|f| f.encode_input(args))
+        .expect("arguments match abi")
+        .into()
+}
+
+pub fn binary_partition() -> Token {
+    Token::Array(
+        BINARY_PARTITION
+            .iter()
+            .map(|i| Token::Uint(U256::from(*i)))
+            .collect(),
+    )
+}
+
+/// A contract call to be executed by the wallet holding the tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CtfCall {
+    pub to: Address,
+    pub data: Bytes,
+}
+
+/// `ConditionalTokens.splitPosition` over the binary partition.
+pub fn split_position_call(
+    conditional_tokens: Address,
+    collateral: Address,
+    condition_id: H256,
+    amount: U256,
+) -> CtfCall {
+    position_call(
+        "splitPosition",
+        conditional_tokens,
+        collateral,
+        condition_id,
+        Some(amount),
+    )
+}
+
+/// `ConditionalTokens.mergePositions` over the binary partition.
+pub fn merge_positions_call(
+    conditional_tokens: Address,
+    collateral: Address,
+    condition_id: H256,
+    amount: U256,
+) -> CtfCall {
+    position_call(
+        "mergePositions",
+        conditional_tokens,
+        collateral,
+        condition_id,
+        Some(amount),
+    )
+}
+
+/// `ConditionalTokens.redeemPositions` for both outcome slots.
+pub fn redeem_positions_call(
+    conditional_tokens: Address,
+    collateral: Address,
+    condition_id: H256,
+) -> CtfCall {
+    position_call(
+        "redeemPositions",
+        conditional_tokens,
+        collateral,
+        condition_id,
+        None,
+    )
+}
+
+fn position_call(
+    name: &str,
+    conditional_tokens: Address,
+    collateral: Address,
+    condition_id: H256,
+    amount: Option<U256>,
+) -> CtfCall {
+    let mut args = vec![
+        Token::Address(collateral),
+        Token::FixedBytes(H256::zero().0.to_vec()),
+        Token::FixedBytes(condition_id.0.to_vec()),
+        binary_partition(),
+    ];
+    args.extend(amount.map(Token::Uint));
+
+    CtfCall {
+        to: conditional_tokens,
+        data: encode_call(conditional_tokens_abi(), name, &args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn condition_id() -> H256 {
+        "0x5e5c9b3b5bd63ab94e0d1b1e3d4f11d0b23c2c6b1f0b46c9e0e0df7c36d3d2a1"
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_split_encodes_binary_partition() {
+        // given
+        let conditional_tokens = Address::repeat_byte(1);
+        let collateral = Address::repeat_byte(2);
+
+        // when
+        let call = split_position_call(
+            conditional_tokens,
+            collateral,
+            condition_id(),
+            U256::from(2_500_000),
+        );
+
+        // then
+        assert_eq!(call.to, conditional_tokens);
+        let tokens = conditional_tokens_abi()
+            .function("splitPosition")
+            .unwrap()
+            .decode_input(&call.data[4..])
+            .unwrap();
+        assert_eq!(tokens[0], Token::Address(collateral));
+        assert_eq!(tokens[1], Token::FixedBytes(vec![0; 32]));
+        assert_eq!(tokens[2], Token::FixedBytes(condition_id().0.to_vec()));
+        assert_eq!(tokens[3], binary_partition());
+        assert_eq!(tokens[4], Token::Uint(U256::from(2_500_000)));
+    }
+
+    #[test]
+    fn test_redeem_has_no_amount() {
+        // given
+        let collateral = Address::repeat_byte(2);
+
+        // when
+        let call = redeem_positions_call(Address::repeat_byte(1), collateral, condition_id());
+
+        // then
+        let tokens = conditional_tokens_abi()
+            .function("redeemPositions")
+            .unwrap()
+            .decode_input(&call.data[4..])
+            .unwrap();
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[3], binary_partition());
+    }
+}
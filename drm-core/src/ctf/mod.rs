@@ -0,0 +1,10 @@
+//! ConditionalTokens and Gnosis Safe calls shared by the exchanges that
+//! settle on chain. Enabled by the `ctf` feature.
+
+mod calls;
+mod safe;
+mod transaction;
+
+pub use calls::*;
+pub use safe::*;
+pub use transaction::*;
@@ -0,0 +1,182 @@
+use ethers::abi::{parse_abi, Abi, Token};
+use ethers::types::{Address, Signature, U256};
+use ethers::utils::keccak256;
+use std::sync::OnceLock;
+
+use super::{encode_call, CtfCall};
+
+pub fn safe_abi() -> &'static Abi {
+    static ABI: OnceLock<Abi> = OnceLock::new();
+    ABI.get_or_init(|| {
+        parse_abi(&[
+            "function nonce() view returns (uint256)",
+            "function execTransaction(address to, uint256 value, bytes data, uint8 operation, uint256 safeTxGas, uint256 baseGas, uint256 gasPrice, address gasToken, address refundReceiver, bytes signatures) payable returns (bool)",
+        ])
+        .expect("valid Safe abi")
+    })
+}
+
+/// `nonce()` on `safe`.
+pub fn safe_nonce_call(safe: Address) -> CtfCall {
+    CtfCall {
+        to: safe,
+        data: encode_call(safe_abi(), "nonce", &[]),
+    }
+}
+
+/// EIP-712 hash a Safe owner signs to approve `call` at `nonce`.
+pub fn safe_tx_hash(safe: Address, chain_id: u64, call: &CtfCall, nonce: U256) -> [u8; 32] {
+    let domain_type_hash = keccak256(b"EIP712Domain(uint256 chainId,address verifyingContract)");
+    let domain_separator = keccak256(ethers::abi::encode(&[
+        Token::FixedBytes(domain_type_hash.to_vec()),
+        Token::Uint(U256::from(chain_id)),
+        Token::Address(safe),
+    ]));
+
+    let safe_tx_type_hash = keccak256(
+        b"SafeTx(address to,uint256 value,bytes data,uint8 operation,uint256 safeTxGas,uint256 baseGas,uint256 gasPrice,address gasToken,address refundReceiver,uint256 nonce)",
+    );
+    let struct_hash = keccak256(ethers::abi::encode(&[
+        Token::FixedBytes(safe_tx_type_hash.to_vec()),
+        Token::Address(call.to),
+        Token::Uint(U256::zero()),
+        Token::FixedBytes(keccak256(&call.data).to_vec()),
+        Token::Uint(U256::zero()),
+        Token::Uint(U256::zero()),
+        Token::Uint(U256::zero()),
+        Token::Uint(U256::zero()),
+        Token::Address(Address::zero()),
+        Token::Address(Address::zero()),
+        Token::Uint(nonce),
+    ]));
+
+    let mut payload = vec![0x19, 0x01];
+    payload.extend_from_slice(&domain_separator);
+    payload.extend_from_slice(&struct_hash);
+
+    keccak256(&payload)
+}
+
+/// `execTransaction` on `safe` for `call`, carrying one owner signature.
+pub fn safe_exec_call(safe: Address, call: &CtfCall, signature: &Signature) -> CtfCall {
+    CtfCall {
+        to: safe,
+        data: encode_call(
+            safe_abi(),
+            "execTransaction",
+            &[
+                Token::Address(call.to),
+                Token::Uint(U256::zero()),
+                Token::Bytes(call.data.to_vec()),
+                Token::Uint(U256::zero()),
+                Token::Uint(U256::zero()),
+                Token::Uint(U256::zero()),
+                Token::Uint(U256::zero()),
+                Token::Address(Address::zero()),
+                Token::Address(Address::zero()),
+                Token::Bytes(signature.to_vec()),
+            ],
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ctf::redeem_positions_call;
+    use ethers::signers::{LocalWallet, Signer};
+    use ethers::types::transaction::eip712::{Eip712, TypedData};
+    use ethers::types::H256;
+
+    const TEST_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn safe() -> Address {
+        "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+            .parse()
+            .unwrap()
+    }
+
+    fn redeem_call() -> CtfCall {
+        redeem_positions_call(
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            H256::repeat_byte(3),
+        )
+    }
+
+    #[test]
+    fn test_safe_tx_hash_matches_typed_data() {
+        // given
+        let call = redeem_call();
+        let typed: TypedData = serde_json::from_value(serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "SafeTx": [
+                    { "name": "to", "type": "address" },
+                    { "name": "value", "type": "uint256" },
+                    { "name": "data", "type": "bytes" },
+                    { "name": "operation", "type": "uint8" },
+                    { "name": "safeTxGas", "type": "uint256" },
+                    { "name": "baseGas", "type": "uint256" },
+                    { "name": "gasPrice", "type": "uint256" },
+                    { "name": "gasToken", "type": "address" },
+                    { "name": "refundReceiver", "type": "address" },
+                    { "name": "nonce", "type": "uint256" }
+                ]
+            },
+            "primaryType": "SafeTx",
+            "domain": { "chainId": 137, "verifyingContract": safe() },
+            "message": {
+                "to": call.to,
+                "value": "0",
+                "data": call.data,
+                "operation": 0,
+                "safeTxGas": "0",
+                "baseGas": "0",
+                "gasPrice": "0",
+                "gasToken": Address::zero(),
+                "refundReceiver": Address::zero(),
+                "nonce": "4"
+            }
+        }))
+        .unwrap();
+
+        // when
+        let hash = safe_tx_hash(safe(), 137, &call, U256::from(4));
+
+        // then
+        assert_eq!(hash, typed.encode_eip712().unwrap());
+    }
+
+    #[test]
+    fn test_safe_exec_call_carries_owner_signature() {
+        // given
+        let wallet: LocalWallet = TEST_KEY.parse().unwrap();
+        let call = redeem_call();
+        let hash = safe_tx_hash(safe(), 137, &call, U256::zero());
+        let signature = wallet.sign_hash(H256::from(hash)).unwrap();
+
+        // when
+        let exec = safe_exec_call(safe(), &call, &signature);
+
+        // then
+        assert_eq!(exec.to, safe());
+        let tokens = safe_abi()
+            .function("execTransaction")
+            .unwrap()
+            .decode_input(&exec.data[4..])
+            .unwrap();
+        let Token::Bytes(sig_bytes) = &tokens[9] else {
+            panic!("signatures not bytes");
+        };
+        let recovered = Signature::try_from(sig_bytes.as_slice())
+            .unwrap()
+            .recover(H256::from(hash))
+            .unwrap();
+        assert!(signature.v == 27 || signature.v == 28);
+        assert_eq!(recovered, wallet.address());
+    }
+}
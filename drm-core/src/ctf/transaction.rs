@@ -0,0 +1,69 @@
+use ethers::middleware::SignerMiddleware;
+use ethers::providers::{Http, Middleware, Provider};
+use ethers::signers::LocalWallet;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::{Address, TransactionReceipt, TransactionRequest, H256, U256, U64};
+
+use super::{safe_exec_call, safe_nonce_call, safe_tx_hash, CtfCall};
+use crate::error::CtfError;
+
+pub type SignerClient = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// Sends `tx` and waits for the receipt. Reverted transactions are reported
+/// as errors.
+pub async fn send_and_confirm(
+    client: &SignerClient,
+    tx: TypedTransaction,
+) -> Result<TransactionReceipt, CtfError> {
+    let pending = client
+        .send_transaction(tx, None)
+        .await
+        .map_err(|e| CtfError::Transaction(format!("send failed: {e}")))?;
+    let tx_hash = pending.tx_hash();
+
+    let receipt = pending
+        .await
+        .map_err(|e| CtfError::Transaction(format!("receipt failed: {e}")))?
+        .ok_or_else(|| CtfError::Transaction(format!("transaction {tx_hash:?} dropped")))?;
+
+    if receipt.status != Some(U64::one()) {
+        return Err(CtfError::Transaction(format!(
+            "transaction {tx_hash:?} reverted"
+        )));
+    }
+
+    Ok(receipt)
+}
+
+/// Reads a single `uint256` returned by a view call.
+pub async fn call_uint(client: &SignerClient, call: &CtfCall) -> Result<U256, CtfError> {
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(call.to)
+        .data(call.data.clone())
+        .into();
+    let raw = client
+        .call(&tx, None)
+        .await
+        .map_err(|e| CtfError::Transaction(format!("call failed: {e}")))?;
+
+    Ok(U256::from_big_endian(&raw))
+}
+
+/// Wraps `call` in an `execTransaction` on `safe`, signed by the client's
+/// key as a 1-of-1 owner at the Safe's current nonce.
+pub async fn safe_call(
+    client: &SignerClient,
+    safe: Address,
+    chain_id: u64,
+    call: &CtfCall,
+) -> Result<CtfCall, CtfError> {
+    let nonce = call_uint(client, &safe_nonce_call(safe)).await?;
+
+    let hash = safe_tx_hash(safe, chain_id, call, nonce);
+    let signature = client
+        .signer()
+        .sign_hash(H256::from(hash))
+        .map_err(|e| CtfError::Signing(format!("signing failed: {e}")))?;
+
+    Ok(safe_exec_call(safe, call, &signature))
+}
@@ -14,6 +14,9 @@ pub enum DrmError {
     #[error("signing error: {0}")]
     Signing(#[from] SigningError),
 
+    #[error("risk check failed: {0}")]
+    Risk(#[from] RiskError),
+
     #[error("rate limit exceeded")]
     RateLimitExceeded,
 
@@ -81,6 +84,37 @@ pub enum WebSocketError {
     Subscription(String),
 }
 
+/// Pre-trade check that rejected an order before it reached the exchange.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RiskError {
+    #[error("kill switch engaged: {0}")]
+    KillSwitch(String),
+
+    #[error("order notional {notional:.2} exceeds limit {limit:.2}")]
+    OrderNotional { notional: f64, limit: f64 },
+
+    #[error("position in {market_id}/{outcome} would be {projected:.2}, limit {limit:.2}")]
+    PositionLimit {
+        market_id: String,
+        outcome: String,
+        projected: f64,
+        limit: f64,
+    },
+
+    #[error("total exposure would be {projected:.2}, limit {limit:.2}")]
+    ExposureLimit { projected: f64, limit: f64 },
+
+    #[error("price {price:.4} is more than {max_deviation:.4} from mid {mid:.4}")]
+    PriceBand {
+        price: f64,
+        mid: f64,
+        max_deviation: f64,
+    },
+
+    #[error("more than {limit} orders in {window_ms}ms")]
+    OrderRate { limit: u32, window_ms: u64 },
+}
+
 #[derive(Debug, Error)]
 pub enum SigningError {
     #[error("invalid private key")]
@@ -92,3 +126,13 @@ pub enum SigningError {
     #[error("unsupported operation: {0}")]
     Unsupported(String),
 }
+
+/// Failure sending an on-chain ConditionalTokens or Safe transaction.
+#[derive(Debug, Error)]
+pub enum CtfError {
+    #[error("transaction error: {0}")]
+    Transaction(String),
+
+    #[error("signing error: {0}")]
+    Signing(String),
+}
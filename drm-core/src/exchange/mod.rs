@@ -1,9 +1,13 @@
 mod config;
 mod factory;
+mod paper;
 mod rate_limit;
+mod risk;
 mod traits;
 
 pub use config::*;
 pub use factory::*;
+pub use paper::*;
 pub use rate_limit::*;
+pub use risk::*;
 pub use traits::*;
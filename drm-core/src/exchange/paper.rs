@@ -0,0 +1,836 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::error::{DrmError, ExchangeError};
+use crate::models::{
+    Market, Order, OrderSide, OrderStatus, Orderbook, OrderbookManager, Position, PriceLevel,
+};
+
+use super::config::{FetchMarketsParams, FetchOrdersParams};
+use super::traits::{Exchange, ExchangeInfo, OrderbookSource};
+
+#[derive(Debug, Clone)]
+pub struct PaperConfig {
+    /// Starting USDC balance.
+    pub initial_balance: f64,
+    /// Fee charged on every fill, as a fraction of fill notional.
+    pub fee_rate: f64,
+    /// Delay before a new order can match.
+    pub latency: Duration,
+    pub verbose: bool,
+}
+
+impl Default for PaperConfig {
+    fn default() -> Self {
+        Self {
+            initial_balance: 1000.0,
+            fee_rate: 0.0,
+            latency: Duration::ZERO,
+            verbose: false,
+        }
+    }
+}
+
+impl PaperConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_initial_balance(mut self, balance: f64) -> Self {
+        self.initial_balance = balance;
+        self
+    }
+
+    pub fn with_fee_rate(mut self, fee_rate: f64) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+}
+
+/// A simulated execution against the live book.
+#[derive(Debug, Clone)]
+pub struct PaperFill {
+    pub order: Order,
+    pub fill_size: f64,
+    pub fill_price: f64,
+    pub fee: f64,
+}
+
+struct PaperOrder {
+    order: Order,
+    token_id: Option<String>,
+    eligible_at: Instant,
+    /// Set after the first matching pass. Later fills happen at the limit
+    /// price because the book crossed a resting order.
+    resting: bool,
+    /// Book the order was last matched against. Taken liquidity is only
+    /// tracked within one matching pass, so each book update can fill an
+    /// order only once.
+    last_book: Option<Arc<Orderbook>>,
+}
+
+#[derive(Default)]
+struct PaperState {
+    cash: f64,
+    fees_paid: f64,
+    positions: HashMap<(String, String), Position>,
+    tokens: HashMap<(String, String), String>,
+    orders: Vec<PaperOrder>,
+}
+
+impl PaperState {
+    fn held(&self, market_id: &str, outcome: &str) -> f64 {
+        self.positions
+            .get(&(market_id.to_string(), outcome.to_string()))
+            .map(|p| p.size)
+            .unwrap_or(0.0)
+    }
+
+    fn reserved_cash(&self, fee_rate: f64) -> f64 {
+        self.orders
+            .iter()
+            .filter(|o| o.order.is_active() && o.order.side == OrderSide::Buy)
+            .map(|o| o.order.remaining() * o.order.price * (1.0 + fee_rate))
+            .sum()
+    }
+
+    fn reserved_shares(&self, market_id: &str, outcome: &str) -> f64 {
+        self.orders
+            .iter()
+            .filter(|o| {
+                o.order.is_active()
+                    && o.order.side == OrderSide::Sell
+                    && o.order.market_id == market_id
+                    && o.order.outcome == outcome
+            })
+            .map(|o| o.order.remaining())
+            .sum()
+    }
+}
+
+/// Simulated [`Exchange`] for dry runs against real market data.
+///
+/// Markets come from the wrapped exchange and books from an
+/// [`OrderbookManager`], which can be fed by any websocket client sharing
+/// the same manager or, with [`PaperExchange::with_orderbook_source`], by
+/// polling the venue for the books of open orders and positions. Orders,
+/// balances and positions are virtual. An order fills when the book crosses
+/// it: on arrival it takes the crossing levels at their prices, afterwards
+/// it fills at its limit. Orders matched in the same pass share the book's
+/// liquidity. Matching runs on every read of account state and on
+/// [`PaperExchange::match_orders`].
+pub struct PaperExchange<E: Exchange> {
+    inner: Arc<E>,
+    orderbooks: OrderbookManager,
+    source: Option<Arc<dyn OrderbookSource>>,
+    config: PaperConfig,
+    markets: Mutex<HashMap<String, Market>>,
+    state: Mutex<PaperState>,
+    next_id: AtomicUsize,
+    fill_tx: broadcast::Sender<PaperFill>,
+}
+
+impl<E: Exchange> PaperExchange<E> {
+    pub fn new(inner: Arc<E>, orderbooks: OrderbookManager, config: PaperConfig) -> Self {
+        let (fill_tx, _) = broadcast::channel(100);
+        let state = PaperState {
+            cash: config.initial_balance,
+            ..Default::default()
+        };
+
+        Self {
+            inner,
+            orderbooks,
+            source: None,
+            config,
+            markets: Mutex::new(HashMap::new()),
+            state: Mutex::new(state),
+            next_id: AtomicUsize::new(0),
+            fill_tx,
+        }
+    }
+
+    /// Refreshes books from `source` before every match, for dry runs
+    /// without a websocket feeding the [`OrderbookManager`].
+    pub fn with_orderbook_source(mut self, source: Arc<dyn OrderbookSource>) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn inner(&self) -> &Arc<E> {
+        &self.inner
+    }
+
+    pub fn orderbook_manager(&self) -> &OrderbookManager {
+        &self.orderbooks
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<PaperFill> {
+        self.fill_tx.subscribe()
+    }
+
+    pub fn fees_paid(&self) -> f64 {
+        self.state.lock().unwrap().fees_paid
+    }
+
+    fn log(&self, message: &str) {
+        if self.config.verbose {
+            println!("[paper:{}] {}", self.inner.id(), message);
+        }
+    }
+
+    async fn cached_market(&self, market_id: &str) -> Result<Market, DrmError> {
+        if let Some(market) = self.markets.lock().unwrap().get(market_id) {
+            return Ok(market.clone());
+        }
+
+        let market = self.inner.fetch_market(market_id).await?;
+        self.markets
+            .lock()
+            .unwrap()
+            .insert(market_id.to_string(), market.clone());
+        Ok(market)
+    }
+
+    async fn resolve_token(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<Option<String>, DrmError> {
+        if let Some(token_id) = params.get("token_id") {
+            return Ok(Some(token_id.clone()));
+        }
+
+        let market = self.cached_market(market_id).await?;
+        Ok(market
+            .get_outcome_tokens()
+            .into_iter()
+            .find(|t| t.outcome == outcome && !t.token_id.is_empty())
+            .map(|t| t.token_id))
+    }
+
+    /// Fetches the books of open orders and held positions from the
+    /// orderbook source, if one is set. Tokens whose fetch fails keep their
+    /// previous book.
+    pub async fn refresh_books(&self) {
+        let Some(source) = &self.source else {
+            return;
+        };
+
+        let mut token_ids: Vec<String> = {
+            let state = self.state.lock().unwrap();
+            state
+                .orders
+                .iter()
+                .filter(|o| o.order.is_active())
+                .filter_map(|o| o.token_id.clone())
+                .chain(
+                    state
+                        .positions
+                        .keys()
+                        .filter_map(|key| state.tokens.get(key).cloned()),
+                )
+                .collect()
+        };
+        token_ids.sort();
+        token_ids.dedup();
+
+        let books = join_all(token_ids.iter().map(|id| source.fetch_book(id))).await;
+        for (token_id, book) in token_ids.into_iter().zip(books) {
+            match book {
+                Ok(book) => self.orderbooks.update(token_id, book),
+                Err(e) => self.log(&format!("Book refresh failed for {token_id}: {e}")),
+            }
+        }
+    }
+
+    async fn refresh_and_match(&self) -> Vec<PaperFill> {
+        self.refresh_books().await;
+        self.match_orders()
+    }
+
+    /// Matches every eligible open order against the current books and
+    /// returns the fills that happened.
+    pub fn match_orders(&self) -> Vec<PaperFill> {
+        let now = Instant::now();
+        let fee_rate = self.config.fee_rate;
+        let mut fills = Vec::new();
+        // Books with the liquidity taken earlier in this pass removed.
+        let mut depleted: HashMap<String, Orderbook> = HashMap::new();
+
+        {
+            let mut state = self.state.lock().unwrap();
+
+            for idx in 0..state.orders.len() {
+                let (order, token_id, resting) = {
+                    let entry = &state.orders[idx];
+                    if !entry.order.is_active() || entry.eligible_at > now {
+                        continue;
+                    }
+                    (entry.order.clone(), entry.token_id.clone(), entry.resting)
+                };
+                let (token_id, book) =
+                    match token_id.and_then(|id| self.orderbooks.get(&id).map(|b| (id, b))) {
+                        Some(found) => found,
+                        None => continue,
+                    };
+
+                let entry = &mut state.orders[idx];
+                if entry
+                    .last_book
+                    .as_ref()
+                    .is_some_and(|seen| Arc::ptr_eq(seen, &book))
+                {
+                    continue;
+                }
+                entry.last_book = Some(book.clone());
+                entry.resting = true;
+
+                let available = depleted
+                    .entry(token_id)
+                    .or_insert_with(|| Orderbook::clone(&book));
+                let (mut size, vwap) = match crossing_liquidity(&order, available) {
+                    Some(liquidity) => liquidity,
+                    None => continue,
+                };
+                let price = if resting { order.price } else { vwap };
+
+                size = size.min(order.remaining());
+                size = match order.side {
+                    OrderSide::Buy => size.min(state.cash / (price * (1.0 + fee_rate))),
+                    OrderSide::Sell => size.min(state.held(&order.market_id, &order.outcome)),
+                };
+                if size <= 1e-9 {
+                    continue;
+                }
+                take_liquidity(&order, available, size);
+
+                let fee = size * price * fee_rate;
+                apply_fill(&mut state, &order, size, price, fee);
+
+                let entry = &mut state.orders[idx].order;
+                entry.filled += size;
+                entry.status = if entry.remaining() <= 1e-9 {
+                    OrderStatus::Filled
+                } else {
+                    OrderStatus::PartiallyFilled
+                };
+                entry.updated_at = Some(Utc::now());
+
+                fills.push(PaperFill {
+                    order: entry.clone(),
+                    fill_size: size,
+                    fill_price: price,
+                    fee,
+                });
+            }
+        }
+
+        for fill in &fills {
+            self.log(&format!(
+                "Filled {:?} {} {:.2} @ {:.4} ({})",
+                fill.order.side, fill.order.outcome, fill.fill_size, fill.fill_price, fill.order.id
+            ));
+            let _ = self.fill_tx.send(fill.clone());
+        }
+
+        fills
+    }
+}
+
+/// Size available on the opposite side at or through the order's limit, and
+/// its volume-weighted price.
+fn crossing_liquidity(order: &Order, book: &Orderbook) -> Option<(f64, f64)> {
+    let crosses = |level: &PriceLevel| match order.side {
+        OrderSide::Buy => level.price <= order.price + 1e-9,
+        OrderSide::Sell => level.price >= order.price - 1e-9,
+    };
+    let levels = match order.side {
+        OrderSide::Buy => &book.asks,
+        OrderSide::Sell => &book.bids,
+    };
+
+    let mut remaining = order.remaining();
+    let mut size = 0.0;
+    let mut notional = 0.0;
+    for level in levels.iter().filter(|l| crosses(l) && l.size > 0.0) {
+        if remaining <= 0.0 {
+            break;
+        }
+        let take = level.size.min(remaining);
+        size += take;
+        notional += take * level.price;
+        remaining -= take;
+    }
+
+    (size > 0.0).then(|| (size, notional / size))
+}
+
+/// Removes `size` from the crossing levels, best price first.
+fn take_liquidity(order: &Order, book: &mut Orderbook, mut size: f64) {
+    let levels = match order.side {
+        OrderSide::Buy => &mut book.asks,
+        OrderSide::Sell => &mut book.bids,
+    };
+    for level in levels.iter_mut() {
+        if size <= 0.0 {
+            break;
+        }
+        let crosses = match order.side {
+            OrderSide::Buy => level.price <= order.price + 1e-9,
+            OrderSide::Sell => level.price >= order.price - 1e-9,
+        };
+        if !crosses || level.size <= 0.0 {
+            continue;
+        }
+        let take = level.size.min(size);
+        level.size -= take;
+        size -= take;
+    }
+}
+
+fn apply_fill(state: &mut PaperState, order: &Order, size: f64, price: f64, fee: f64) {
+    let key = (order.market_id.clone(), order.outcome.clone());
+    state.fees_paid += fee;
+
+    match order.side {
+        OrderSide::Buy => {
+            state.cash -= size * price + fee;
+            let position = state.positions.entry(key).or_insert_with(|| Position {
+                market_id: order.market_id.clone(),
+                outcome: order.outcome.clone(),
+                size: 0.0,
+                average_price: 0.0,
+                current_price: price,
+            });
+            let new_size = position.size + size;
+            position.average_price =
+                (position.size * position.average_price + size * price) / new_size;
+            position.size = new_size;
+            position.current_price = price;
+        }
+        OrderSide::Sell => {
+            state.cash += size * price - fee;
+            if let Some(position) = state.positions.get_mut(&key) {
+                position.size -= size;
+                position.current_price = price;
+                if position.size <= 1e-9 {
+                    state.positions.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<E: Exchange> Exchange for PaperExchange<E> {
+    fn id(&self) -> &'static str {
+        "paper"
+    }
+
+    fn name(&self) -> &'static str {
+        "Paper"
+    }
+
+    async fn fetch_markets(
+        &self,
+        params: Option<FetchMarketsParams>,
+    ) -> Result<Vec<Market>, DrmError> {
+        self.inner.fetch_markets(params).await
+    }
+
+    async fn fetch_market(&self, market_id: &str) -> Result<Market, DrmError> {
+        self.inner.fetch_market(market_id).await
+    }
+
+    async fn fetch_markets_by_slug(&self, slug: &str) -> Result<Vec<Market>, DrmError> {
+        self.inner.fetch_markets_by_slug(slug).await
+    }
+
+    async fn create_order(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+        params: HashMap<String, String>,
+    ) -> Result<Order, DrmError> {
+        if price <= 0.0 || price >= 1.0 {
+            return Err(ExchangeError::InvalidOrder("Price must be between 0 and 1".into()).into());
+        }
+        if size <= 0.0 {
+            return Err(ExchangeError::InvalidOrder("Size must be positive".into()).into());
+        }
+
+        let token_id = self.resolve_token(market_id, outcome, &params).await?;
+        let id = format!("paper-{}", self.next_id.fetch_add(1, Ordering::SeqCst));
+        let order = Order {
+            id: id.clone(),
+            market_id: market_id.to_string(),
+            outcome: outcome.to_string(),
+            side,
+            price,
+            size,
+            filled: 0.0,
+            status: OrderStatus::Open,
+            created_at: Utc::now(),
+            updated_at: None,
+        };
+
+        {
+            let mut state = self.state.lock().unwrap();
+            match side {
+                OrderSide::Buy => {
+                    let fee_rate = self.config.fee_rate;
+                    let available = state.cash - state.reserved_cash(fee_rate);
+                    let required = size * price * (1.0 + fee_rate);
+                    if required > available + 1e-9 {
+                        return Err(ExchangeError::InsufficientFunds(format!(
+                            "need {required:.2} USDC, {available:.2} available"
+                        ))
+                        .into());
+                    }
+                }
+                OrderSide::Sell => {
+                    let available =
+                        state.held(market_id, outcome) - state.reserved_shares(market_id, outcome);
+                    if size > available + 1e-9 {
+                        return Err(ExchangeError::InsufficientFunds(format!(
+                            "need {size:.2} {outcome} shares, {available:.2} available"
+                        ))
+                        .into());
+                    }
+                }
+            }
+
+            if let Some(token_id) = &token_id {
+                state.tokens.insert(
+                    (market_id.to_string(), outcome.to_string()),
+                    token_id.clone(),
+                );
+            }
+            state.orders.push(PaperOrder {
+                order,
+                token_id,
+                eligible_at: Instant::now() + self.config.latency,
+                resting: false,
+                last_book: None,
+            });
+        }
+
+        self.refresh_and_match().await;
+        self.fetch_order(&id, None).await
+    }
+
+    async fn cancel_order(
+        &self,
+        order_id: &str,
+        _market_id: Option<&str>,
+    ) -> Result<Order, DrmError> {
+        self.refresh_and_match().await;
+        let mut state = self.state.lock().unwrap();
+        let entry = state
+            .orders
+            .iter_mut()
+            .find(|o| o.order.id == order_id)
+            .ok_or_else(|| ExchangeError::InvalidOrder(format!("unknown order {order_id}")))?;
+
+        if entry.order.is_active() {
+            entry.order.status = OrderStatus::Cancelled;
+            entry.order.updated_at = Some(Utc::now());
+        }
+        Ok(entry.order.clone())
+    }
+
+    async fn fetch_order(
+        &self,
+        order_id: &str,
+        _market_id: Option<&str>,
+    ) -> Result<Order, DrmError> {
+        self.refresh_and_match().await;
+        self.state
+            .lock()
+            .unwrap()
+            .orders
+            .iter()
+            .find(|o| o.order.id == order_id)
+            .map(|o| o.order.clone())
+            .ok_or_else(|| ExchangeError::InvalidOrder(format!("unknown order {order_id}")).into())
+    }
+
+    async fn fetch_open_orders(
+        &self,
+        params: Option<FetchOrdersParams>,
+    ) -> Result<Vec<Order>, DrmError> {
+        self.refresh_and_match().await;
+        let market_id = params.and_then(|p| p.market_id);
+        Ok(self
+            .state
+            .lock()
+            .unwrap()
+            .orders
+            .iter()
+            .map(|o| &o.order)
+            .filter(|o| o.is_active())
+            .filter(|o| market_id.as_ref().is_none_or(|id| &o.market_id == id))
+            .cloned()
+            .collect())
+    }
+
+    async fn fetch_positions(&self, market_id: Option<&str>) -> Result<Vec<Position>, DrmError> {
+        self.refresh_and_match().await;
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .positions
+            .iter()
+            .filter(|((market, _), _)| market_id.is_none_or(|id| market == id))
+            .map(|(key, position)| {
+                let mut position = position.clone();
+                if let Some(mid) = state
+                    .tokens
+                    .get(key)
+                    .and_then(|token_id| self.orderbooks.get(token_id))
+                    .and_then(|book| book.mid_price())
+                {
+                    position.current_price = mid;
+                }
+                position
+            })
+            .collect())
+    }
+
+    async fn fetch_balance(&self) -> Result<HashMap<String, f64>, DrmError> {
+        self.refresh_and_match().await;
+        let cash = self.state.lock().unwrap().cash;
+        Ok(HashMap::from([("USDC".to_string(), cash)]))
+    }
+
+    fn describe(&self) -> ExchangeInfo {
+        ExchangeInfo {
+            id: self.id(),
+            name: self.name(),
+            has_fetch_markets: true,
+            has_create_order: true,
+            has_websocket: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{make_market, MockExchange};
+
+    fn make_book(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Orderbook {
+        Orderbook {
+            market_id: "market-1".to_string(),
+            asset_id: "yes-token".to_string(),
+            bids: bids.iter().map(|&(p, s)| PriceLevel::new(p, s)).collect(),
+            asks: asks.iter().map(|&(p, s)| PriceLevel::new(p, s)).collect(),
+            last_update_id: None,
+            timestamp: None,
+        }
+    }
+
+    fn make_exchange(config: PaperConfig) -> PaperExchange<MockExchange> {
+        let inner = Arc::new(MockExchange::with_market(make_market("market-1")));
+        PaperExchange::new(inner, OrderbookManager::new(), config)
+    }
+
+    async fn order(
+        exchange: &PaperExchange<MockExchange>,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+    ) -> Result<Order, DrmError> {
+        exchange
+            .create_order("market-1", "Yes", side, price, size, HashMap::new())
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_marketable_order_takes_crossing_levels() {
+        // given
+        let exchange = make_exchange(PaperConfig::new().with_fee_rate(0.01));
+        exchange.orderbook_manager().update(
+            "yes-token",
+            make_book(
+                &[(0.48, 100.0)],
+                &[(0.50, 10.0), (0.52, 10.0), (0.60, 10.0)],
+            ),
+        );
+
+        // when
+        let filled = order(&exchange, OrderSide::Buy, 0.55, 20.0).await.unwrap();
+
+        // then
+        assert_eq!(filled.status, OrderStatus::Filled);
+        let positions = exchange.fetch_positions(Some("market-1")).await.unwrap();
+        assert_eq!(positions.len(), 1);
+        assert!((positions[0].size - 20.0).abs() < 1e-9);
+        assert!((positions[0].average_price - 0.51).abs() < 1e-9);
+        assert!((positions[0].current_price - 0.49).abs() < 1e-9);
+        let usdc = exchange.fetch_balance().await.unwrap()["USDC"];
+        assert!((usdc - (1000.0 - 10.2 - 0.102)).abs() < 1e-9);
+        assert!((exchange.fees_paid() - 0.102).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_resting_order_fills_when_book_crosses() {
+        // given
+        let exchange = make_exchange(PaperConfig::new());
+        let mut fills = exchange.subscribe();
+        exchange
+            .orderbook_manager()
+            .update("yes-token", make_book(&[(0.40, 10.0)], &[(0.45, 10.0)]));
+        let resting = order(&exchange, OrderSide::Buy, 0.42, 15.0).await.unwrap();
+        assert_eq!(resting.status, OrderStatus::Open);
+
+        // when
+        exchange
+            .orderbook_manager()
+            .update("yes-token", make_book(&[(0.38, 10.0)], &[(0.41, 5.0)]));
+        let open = exchange.fetch_open_orders(None).await.unwrap();
+
+        // then
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].status, OrderStatus::PartiallyFilled);
+        let fill = fills.try_recv().unwrap();
+        assert!((fill.fill_size - 5.0).abs() < 1e-9);
+        assert!((fill.fill_price - 0.42).abs() < 1e-9);
+
+        // when
+        let sell = order(&exchange, OrderSide::Sell, 0.30, 5.0).await.unwrap();
+
+        // then
+        assert_eq!(sell.status, OrderStatus::Filled);
+        assert!(exchange.fetch_positions(None).await.unwrap().is_empty());
+        let usdc = exchange.fetch_balance().await.unwrap()["USDC"];
+        assert!((usdc - (1000.0 - 2.1 + 1.9)).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_orders_in_one_pass_share_liquidity() {
+        // given
+        let exchange = make_exchange(PaperConfig::new().with_latency(Duration::from_millis(20)));
+        exchange
+            .orderbook_manager()
+            .update("yes-token", make_book(&[], &[(0.40, 100.0)]));
+        let first = order(&exchange, OrderSide::Buy, 0.45, 10.0).await.unwrap();
+        let second = order(&exchange, OrderSide::Buy, 0.45, 10.0).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        // when
+        exchange
+            .orderbook_manager()
+            .update("yes-token", make_book(&[], &[(0.42, 12.0)]));
+        let fills = exchange.match_orders();
+
+        // then
+        assert_eq!(fills.len(), 2);
+        let total: f64 = fills.iter().map(|f| f.fill_size).sum();
+        assert!((total - 12.0).abs() < 1e-9);
+        let first = exchange.fetch_order(&first.id, None).await.unwrap();
+        let second = exchange.fetch_order(&second.id, None).await.unwrap();
+        assert_eq!(first.status, OrderStatus::Filled);
+        assert!((second.filled - 2.0).abs() < 1e-9);
+    }
+
+    struct StaticSource(Mutex<Orderbook>);
+
+    #[async_trait]
+    impl OrderbookSource for StaticSource {
+        async fn fetch_book(&self, book_id: &str) -> Result<Orderbook, DrmError> {
+            let mut book = self.0.lock().unwrap().clone();
+            book.asset_id = book_id.to_string();
+            Ok(book)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_orderbook_source_refreshes_books() {
+        // given
+        let source = Arc::new(StaticSource(Mutex::new(make_book(
+            &[(0.40, 10.0)],
+            &[(0.45, 10.0)],
+        ))));
+        let exchange = make_exchange(PaperConfig::new()).with_orderbook_source(source.clone());
+        let resting = order(&exchange, OrderSide::Buy, 0.42, 5.0).await.unwrap();
+        assert_eq!(resting.status, OrderStatus::Open);
+        assert!(exchange.orderbook_manager().has_data("yes-token"));
+
+        // when
+        *source.0.lock().unwrap() = make_book(&[(0.38, 10.0)], &[(0.41, 10.0)]);
+        let filled = exchange.fetch_order(&resting.id, None).await.unwrap();
+
+        // then
+        assert_eq!(filled.status, OrderStatus::Filled);
+        assert_eq!(
+            exchange.orderbook_manager().get_best_bid_ask("yes-token"),
+            (Some(0.38), Some(0.41))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_latency_delays_matching() {
+        // given
+        let exchange = make_exchange(PaperConfig::new().with_latency(Duration::from_millis(30)));
+        exchange
+            .orderbook_manager()
+            .update("yes-token", make_book(&[], &[(0.50, 10.0)]));
+
+        // when
+        let placed = order(&exchange, OrderSide::Buy, 0.50, 5.0).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let later = exchange.fetch_order(&placed.id, None).await.unwrap();
+
+        // then
+        assert_eq!(placed.status, OrderStatus::Open);
+        assert_eq!(later.status, OrderStatus::Filled);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unfunded_orders_and_cancels() {
+        // given
+        let exchange = make_exchange(PaperConfig::new().with_initial_balance(10.0));
+
+        // when
+        let too_big = order(&exchange, OrderSide::Buy, 0.5, 30.0).await;
+        let naked_sell = order(&exchange, OrderSide::Sell, 0.5, 1.0).await;
+        let resting = order(&exchange, OrderSide::Buy, 0.5, 20.0).await.unwrap();
+        let second = order(&exchange, OrderSide::Buy, 0.5, 1.0).await;
+        let cancelled = exchange.cancel_order(&resting.id, None).await.unwrap();
+
+        // then
+        assert!(matches!(
+            too_big,
+            Err(DrmError::Exchange(ExchangeError::InsufficientFunds(_)))
+        ));
+        assert!(matches!(
+            naked_sell,
+            Err(DrmError::Exchange(ExchangeError::InsufficientFunds(_)))
+        ));
+        assert!(second.is_err());
+        assert_eq!(cancelled.status, OrderStatus::Cancelled);
+        assert!(exchange.fetch_open_orders(None).await.unwrap().is_empty());
+        assert!(order(&exchange, OrderSide::Buy, 0.5, 1.0).await.is_ok());
+    }
+}
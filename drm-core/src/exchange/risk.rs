@@ -0,0 +1,555 @@
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::error::{DrmError, RiskError};
+use crate::models::{Market, Order, OrderSide, OrderbookManager, Position};
+use crate::strategy::StrategyConfig;
+
+use super::config::{FetchMarketsParams, FetchOrdersParams};
+use super::traits::{Exchange, ExchangeInfo};
+
+/// Limits enforced by [`RiskManagedExchange`]. `None` disables a check.
+#[derive(Debug, Clone, Default)]
+pub struct RiskLimits {
+    /// Maximum `price * size` of a single order.
+    pub max_order_notional: Option<f64>,
+    /// Maximum shares held per market and outcome after a buy fills.
+    pub max_position_size: Option<f64>,
+    /// Maximum value of all positions plus resting buy orders.
+    pub max_total_exposure: Option<f64>,
+    /// Maximum absolute distance between order price and current mid.
+    pub max_price_deviation: Option<f64>,
+    /// Maximum orders accepted within `rate_window`.
+    pub max_orders_per_window: Option<u32>,
+    pub rate_window: Duration,
+}
+
+impl RiskLimits {
+    pub fn new() -> Self {
+        Self {
+            rate_window: Duration::from_secs(1),
+            ..Default::default()
+        }
+    }
+
+    /// Enforces `max_position_size` of a strategy config.
+    pub fn from_strategy_config(config: &StrategyConfig) -> Self {
+        Self::new().with_max_position_size(config.max_position_size)
+    }
+
+    pub fn with_max_order_notional(mut self, limit: f64) -> Self {
+        self.max_order_notional = Some(limit);
+        self
+    }
+
+    pub fn with_max_position_size(mut self, limit: f64) -> Self {
+        self.max_position_size = Some(limit);
+        self
+    }
+
+    pub fn with_max_total_exposure(mut self, limit: f64) -> Self {
+        self.max_total_exposure = Some(limit);
+        self
+    }
+
+    pub fn with_max_price_deviation(mut self, deviation: f64) -> Self {
+        self.max_price_deviation = Some(deviation);
+        self
+    }
+
+    pub fn with_order_rate(mut self, max_orders: u32, window: Duration) -> Self {
+        self.max_orders_per_window = Some(max_orders);
+        self.rate_window = window;
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RiskEvent {
+    Rejected {
+        market_id: String,
+        outcome: String,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+        error: RiskError,
+    },
+    KillSwitchEngaged(String),
+    KillSwitchReleased,
+}
+
+/// [`Exchange`] decorator that runs pre-trade checks on every `create_order`
+/// and forwards everything else unchanged.
+///
+/// Position and exposure checks query the inner exchange, so they cost one or
+/// two extra requests per order when enabled. The price band uses the mid
+/// from the attached [`OrderbookManager`] (keyed by the `token_id` order
+/// param) and falls back to the market's last price; orders with no
+/// reference price skip the band check.
+pub struct RiskManagedExchange<E: Exchange> {
+    inner: Arc<E>,
+    limits: RiskLimits,
+    orderbooks: Option<OrderbookManager>,
+    kill_reason: Mutex<Option<String>>,
+    killed: AtomicBool,
+    recent_orders: Mutex<VecDeque<Instant>>,
+    event_tx: broadcast::Sender<RiskEvent>,
+}
+
+impl<E: Exchange> RiskManagedExchange<E> {
+    pub fn new(inner: Arc<E>, limits: RiskLimits) -> Self {
+        let (event_tx, _) = broadcast::channel(100);
+
+        Self {
+            inner,
+            limits,
+            orderbooks: None,
+            kill_reason: Mutex::new(None),
+            killed: AtomicBool::new(false),
+            recent_orders: Mutex::new(VecDeque::new()),
+            event_tx,
+        }
+    }
+
+    pub fn with_orderbook_manager(mut self, orderbooks: OrderbookManager) -> Self {
+        self.orderbooks = Some(orderbooks);
+        self
+    }
+
+    pub fn inner(&self) -> &Arc<E> {
+        &self.inner
+    }
+
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<RiskEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Rejects every new order until [`release_kill_switch`] is called.
+    /// Cancels are still forwarded.
+    ///
+    /// [`release_kill_switch`]: Self::release_kill_switch
+    pub fn engage_kill_switch(&self, reason: impl Into<String>) {
+        let reason = reason.into();
+        *self.kill_reason.lock().unwrap() = Some(reason.clone());
+        self.killed.store(true, Ordering::SeqCst);
+        let _ = self.event_tx.send(RiskEvent::KillSwitchEngaged(reason));
+    }
+
+    pub fn release_kill_switch(&self) {
+        if self.killed.swap(false, Ordering::SeqCst) {
+            *self.kill_reason.lock().unwrap() = None;
+            let _ = self.event_tx.send(RiskEvent::KillSwitchReleased);
+        }
+    }
+
+    pub fn is_killed(&self) -> bool {
+        self.killed.load(Ordering::SeqCst)
+    }
+
+    async fn reference_mid(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<Option<f64>, DrmError> {
+        let book_mid = params
+            .get("token_id")
+            .zip(self.orderbooks.as_ref())
+            .and_then(|(token_id, orderbooks)| orderbooks.get(token_id))
+            .and_then(|ob| ob.mid_price());
+        if book_mid.is_some() {
+            return Ok(book_mid);
+        }
+
+        let market = self.inner.fetch_market(market_id).await?;
+        Ok(market.prices.get(outcome).copied().filter(|&p| p > 0.0))
+    }
+
+    async fn check_order(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+        params: &HashMap<String, String>,
+    ) -> Result<(), DrmError> {
+        if self.is_killed() {
+            let reason = self.kill_reason.lock().unwrap().clone().unwrap_or_default();
+            return Err(RiskError::KillSwitch(reason).into());
+        }
+
+        let notional = price * size;
+        if let Some(limit) = self.limits.max_order_notional {
+            if notional > limit {
+                return Err(RiskError::OrderNotional { notional, limit }.into());
+            }
+        }
+
+        if let Some(max_deviation) = self.limits.max_price_deviation {
+            if let Some(mid) = self.reference_mid(market_id, outcome, params).await? {
+                if (price - mid).abs() > max_deviation + f64::EPSILON {
+                    return Err(RiskError::PriceBand {
+                        price,
+                        mid,
+                        max_deviation,
+                    }
+                    .into());
+                }
+            }
+        }
+
+        if side == OrderSide::Buy {
+            if let Some(limit) = self.limits.max_position_size {
+                let (positions, open_orders) = tokio::try_join!(
+                    self.inner.fetch_positions(Some(market_id)),
+                    self.inner.fetch_open_orders(Some(FetchOrdersParams {
+                        market_id: Some(market_id.to_string()),
+                    })),
+                )?;
+                let held: f64 = positions
+                    .iter()
+                    .filter(|p| p.outcome == outcome)
+                    .map(|p| p.size)
+                    .sum();
+                let resting: f64 = open_orders
+                    .iter()
+                    .filter(|o| {
+                        o.side == OrderSide::Buy && o.market_id == market_id && o.outcome == outcome
+                    })
+                    .map(|o| o.remaining())
+                    .sum();
+                let projected = held + resting + size;
+                if projected > limit {
+                    return Err(RiskError::PositionLimit {
+                        market_id: market_id.to_string(),
+                        outcome: outcome.to_string(),
+                        projected,
+                        limit,
+                    }
+                    .into());
+                }
+            }
+
+            if let Some(limit) = self.limits.max_total_exposure {
+                let (positions, open_orders) = tokio::try_join!(
+                    self.inner.fetch_positions(None),
+                    self.inner.fetch_open_orders(None),
+                )?;
+                let resting: f64 = open_orders
+                    .iter()
+                    .filter(|o| o.side == OrderSide::Buy)
+                    .map(|o| o.remaining() * o.price)
+                    .sum();
+                let held: f64 = positions.iter().map(|p| p.current_value()).sum();
+                let projected = held + resting + notional;
+                if projected > limit {
+                    return Err(RiskError::ExposureLimit { projected, limit }.into());
+                }
+            }
+        }
+
+        if let Some(limit) = self.limits.max_orders_per_window {
+            let window = self.limits.rate_window;
+            let now = Instant::now();
+            let mut recent = self.recent_orders.lock().unwrap();
+            while recent
+                .front()
+                .is_some_and(|&t| now.duration_since(t) >= window)
+            {
+                recent.pop_front();
+            }
+            if recent.len() >= limit as usize {
+                return Err(RiskError::OrderRate {
+                    limit,
+                    window_ms: window.as_millis() as u64,
+                }
+                .into());
+            }
+            recent.push_back(now);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<E: Exchange> Exchange for RiskManagedExchange<E> {
+    fn id(&self) -> &'static str {
+        self.inner.id()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn fetch_markets(
+        &self,
+        params: Option<FetchMarketsParams>,
+    ) -> Result<Vec<Market>, DrmError> {
+        self.inner.fetch_markets(params).await
+    }
+
+    async fn fetch_market(&self, market_id: &str) -> Result<Market, DrmError> {
+        self.inner.fetch_market(market_id).await
+    }
+
+    async fn fetch_markets_by_slug(&self, slug: &str) -> Result<Vec<Market>, DrmError> {
+        self.inner.fetch_markets_by_slug(slug).await
+    }
+
+    async fn create_order(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+        params: HashMap<String, String>,
+    ) -> Result<Order, DrmError> {
+        if let Err(err) = self
+            .check_order(market_id, outcome, side, price, size, &params)
+            .await
+        {
+            if let DrmError::Risk(error) = &err {
+                let _ = self.event_tx.send(RiskEvent::Rejected {
+                    market_id: market_id.to_string(),
+                    outcome: outcome.to_string(),
+                    side,
+                    price,
+                    size,
+                    error: error.clone(),
+                });
+            }
+            return Err(err);
+        }
+
+        self.inner
+            .create_order(market_id, outcome, side, price, size, params)
+            .await
+    }
+
+    async fn cancel_order(
+        &self,
+        order_id: &str,
+        market_id: Option<&str>,
+    ) -> Result<Order, DrmError> {
+        self.inner.cancel_order(order_id, market_id).await
+    }
+
+    async fn fetch_order(
+        &self,
+        order_id: &str,
+        market_id: Option<&str>,
+    ) -> Result<Order, DrmError> {
+        self.inner.fetch_order(order_id, market_id).await
+    }
+
+    async fn fetch_open_orders(
+        &self,
+        params: Option<FetchOrdersParams>,
+    ) -> Result<Vec<Order>, DrmError> {
+        self.inner.fetch_open_orders(params).await
+    }
+
+    async fn fetch_positions(&self, market_id: Option<&str>) -> Result<Vec<Position>, DrmError> {
+        self.inner.fetch_positions(market_id).await
+    }
+
+    async fn fetch_balance(&self) -> Result<HashMap<String, f64>, DrmError> {
+        self.inner.fetch_balance().await
+    }
+
+    fn describe(&self) -> ExchangeInfo {
+        self.inner.describe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{make_market, MockExchange};
+
+    fn make_exchange(limits: RiskLimits) -> RiskManagedExchange<MockExchange> {
+        let inner = Arc::new(MockExchange::with_market(make_market("market-1")));
+        RiskManagedExchange::new(inner, limits)
+    }
+
+    async fn buy(
+        exchange: &RiskManagedExchange<MockExchange>,
+        price: f64,
+        size: f64,
+    ) -> Result<Order, DrmError> {
+        exchange
+            .create_order(
+                "market-1",
+                "Yes",
+                OrderSide::Buy,
+                price,
+                size,
+                HashMap::new(),
+            )
+            .await
+    }
+
+    fn risk_error(result: Result<Order, DrmError>) -> RiskError {
+        match result {
+            Err(DrmError::Risk(err)) => err,
+            other => panic!("expected risk error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_large_notional_and_reports_event() {
+        // given
+        let exchange = make_exchange(RiskLimits::new().with_max_order_notional(100.0));
+        let mut events = exchange.subscribe();
+
+        // when
+        let result = buy(&exchange, 0.99, 10_000.0).await;
+
+        // then
+        assert!(matches!(
+            risk_error(result),
+            RiskError::OrderNotional { .. }
+        ));
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            RiskEvent::Rejected { size, .. } if size == 10_000.0
+        ));
+        assert!(exchange.inner().orders.lock().unwrap().is_empty());
+        assert!(buy(&exchange, 0.5, 100.0).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_enforces_position_and_exposure_limits() {
+        // given
+        let exchange = make_exchange(
+            RiskLimits::from_strategy_config(&StrategyConfig::default())
+                .with_max_total_exposure(45.0),
+        );
+        exchange.inner().positions.lock().unwrap().push(Position {
+            market_id: "market-1".to_string(),
+            outcome: "Yes".to_string(),
+            size: 90.0,
+            average_price: 0.5,
+            current_price: 0.5,
+        });
+
+        // when
+        let too_many = buy(&exchange, 0.5, 20.0).await;
+        let too_exposed = buy(&exchange, 0.5, 10.0).await;
+
+        // then
+        assert!(matches!(
+            risk_error(too_many),
+            RiskError::PositionLimit { projected, .. } if projected == 110.0
+        ));
+        assert!(matches!(
+            risk_error(too_exposed),
+            RiskError::ExposureLimit { .. }
+        ));
+        assert!(exchange
+            .create_order(
+                "market-1",
+                "Yes",
+                OrderSide::Sell,
+                0.5,
+                50.0,
+                HashMap::new()
+            )
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_resting_buys_count_toward_position_limit() {
+        // given
+        let exchange = make_exchange(RiskLimits::new().with_max_position_size(100.0));
+        buy(&exchange, 0.5, 60.0).await.unwrap();
+
+        // when
+        let second = buy(&exchange, 0.5, 60.0).await;
+        let within = buy(&exchange, 0.5, 40.0).await;
+
+        // then
+        assert!(matches!(
+            risk_error(second),
+            RiskError::PositionLimit { projected, .. } if projected == 120.0
+        ));
+        assert!(within.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_price_band_uses_orderbook_mid() {
+        // given
+        let orderbooks = OrderbookManager::new();
+        orderbooks.update(
+            "yes-token",
+            crate::models::Orderbook {
+                bids: vec![crate::models::PriceLevel::new(0.69, 10.0)],
+                asks: vec![crate::models::PriceLevel::new(0.71, 10.0)],
+                ..Default::default()
+            },
+        );
+        let exchange = make_exchange(RiskLimits::new().with_max_price_deviation(0.05))
+            .with_orderbook_manager(orderbooks);
+        let params = HashMap::from([("token_id".to_string(), "yes-token".to_string())]);
+
+        // when
+        let far = exchange
+            .create_order("market-1", "Yes", OrderSide::Buy, 0.5, 1.0, params.clone())
+            .await;
+        let near = exchange
+            .create_order("market-1", "Yes", OrderSide::Buy, 0.68, 1.0, params)
+            .await;
+        let fallback = buy(&exchange, 0.6, 1.0).await;
+
+        // then
+        assert!(matches!(risk_error(far), RiskError::PriceBand { .. }));
+        assert!(near.is_ok());
+        assert!(matches!(
+            risk_error(fallback),
+            RiskError::PriceBand { mid, .. } if mid == 0.5
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_and_kill_switch() {
+        // given
+        let exchange = make_exchange(RiskLimits::new().with_order_rate(2, Duration::from_secs(60)));
+        let mut events = exchange.subscribe();
+
+        // when
+        assert!(buy(&exchange, 0.5, 1.0).await.is_ok());
+        assert!(buy(&exchange, 0.5, 1.0).await.is_ok());
+        let throttled = buy(&exchange, 0.5, 1.0).await;
+        exchange.engage_kill_switch("manual");
+        let killed = buy(&exchange, 0.5, 1.0).await;
+
+        // then
+        assert!(matches!(
+            risk_error(throttled),
+            RiskError::OrderRate { limit: 2, .. }
+        ));
+        assert_eq!(
+            risk_error(killed),
+            RiskError::KillSwitch("manual".to_string())
+        );
+        assert!(exchange.cancel_order("order-0", None).await.is_ok());
+
+        exchange.release_kill_switch();
+        assert!(!exchange.is_killed());
+        let kinds: Vec<RiskEvent> = std::iter::from_fn(|| events.try_recv().ok()).collect();
+        assert!(matches!(kinds[1], RiskEvent::KillSwitchEngaged(_)));
+        assert!(matches!(kinds[3], RiskEvent::KillSwitchReleased));
+    }
+}
@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use std::collections::HashMap;
 
 use crate::error::DrmError;
-use crate::models::{Market, Order, OrderSide, Position};
+use crate::models::{Market, Order, OrderSide, Orderbook, Position};
 
 use super::config::{FetchMarketsParams, FetchOrdersParams};
 
@@ -72,3 +72,23 @@ pub struct ExchangeInfo {
     pub has_create_order: bool,
     pub has_websocket: bool,
 }
+
+/// Anything that can return a current book by venue-specific id (token id,
+/// ticker, slug). Lets scanners poll exchanges whose `fetch_orderbook`
+/// lives outside the [`Exchange`] trait.
+#[async_trait]
+pub trait OrderbookSource: Send + Sync {
+    async fn fetch_book(&self, book_id: &str) -> Result<Orderbook, DrmError>;
+}
+
+/// On-chain conversion between collateral and complete sets (one share of
+/// every outcome) on CTF-style venues. Amounts are in shares; returns the
+/// transaction hash.
+#[async_trait]
+pub trait CompleteSetConverter: Send + Sync {
+    /// Locks `amount` collateral into `amount` shares of each outcome.
+    async fn split(&self, market_id: &str, amount: f64) -> Result<String, DrmError>;
+
+    /// Burns `amount` shares of each outcome for `amount` collateral.
+    async fn merge(&self, market_id: &str, amount: f64) -> Result<String, DrmError>;
+}
@@ -0,0 +1,654 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+
+use crate::error::DrmError;
+use crate::exchange::Exchange;
+use crate::models::OrderSide;
+use crate::strategy::{CallbackId, OrderEvent, OrderTracker};
+
+const EPSILON: f64 = 1e-9;
+
+/// The order to work. `limit_price` is the worst price any child may use.
+#[derive(Debug, Clone)]
+pub struct ParentOrder {
+    pub market_id: String,
+    pub outcome: String,
+    pub side: OrderSide,
+    pub size: f64,
+    pub limit_price: f64,
+    pub token_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExecutionStyle {
+    /// Releases `size / slices` every `duration_ms / slices`. Unfilled
+    /// quantity from earlier slices is rolled into the next child.
+    Twap { duration_ms: u64, slices: u32 },
+    /// Keeps at most `show_size` resting, replenishing as children fill.
+    Iceberg { show_size: f64 },
+    /// Rests the whole remainder at `passive_price` for `passive_ms`, then
+    /// reprices it to the parent's limit.
+    PassiveThenAggressive { passive_price: f64, passive_ms: u64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionConfig {
+    pub poll_interval_ms: u64,
+    /// Remainders below this are not sent; the parent completes instead.
+    pub min_child_size: f64,
+    pub verbose: bool,
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 1000,
+            min_child_size: 0.0,
+            verbose: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionStatus {
+    Working,
+    Completed,
+    Cancelled,
+    /// A child could not be cancelled before repricing, so the parent
+    /// stopped rather than send more children alongside it.
+    Failed,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionProgress {
+    pub status: ExecutionStatus,
+    pub size: f64,
+    pub filled: f64,
+    pub remaining: f64,
+    /// Volume-weighted limit price of the filled children; zero before the
+    /// first fill. An approximation, since `Order` carries no fill price.
+    pub average_price: f64,
+    pub children_placed: usize,
+    pub working_children: Vec<String>,
+    /// Children whose cancel failed. They may still be live and stay in
+    /// the tracker.
+    pub uncancelled_children: Vec<String>,
+}
+
+#[derive(Default)]
+struct AlgoState {
+    started_at: Option<Instant>,
+    children: Vec<String>,
+    uncancelled: Vec<String>,
+    filled: f64,
+    notional: f64,
+    slices_sent: u32,
+    aggressive: bool,
+    done: Option<ExecutionStatus>,
+}
+
+/// Slices a [`ParentOrder`] into child orders according to an
+/// [`ExecutionStyle`]. Child fills are observed through the
+/// [`OrderTracker`], so fills reported by a websocket or reconciler sharing
+/// the tracker count as well. The fill callback is removed from the tracker
+/// once the parent completes or is cancelled.
+pub struct ExecutionAlgo<E: Exchange + ?Sized> {
+    exchange: Arc<E>,
+    tracker: Arc<OrderTracker>,
+    callback_id: CallbackId,
+    parent: ParentOrder,
+    style: ExecutionStyle,
+    config: ExecutionConfig,
+    state: Arc<Mutex<AlgoState>>,
+}
+
+impl<E: Exchange + ?Sized + 'static> ExecutionAlgo<E> {
+    pub fn new(
+        exchange: Arc<E>,
+        tracker: Arc<OrderTracker>,
+        parent: ParentOrder,
+        style: ExecutionStyle,
+    ) -> Result<Self, DrmError> {
+        if parent.size <= 0.0 || !(0.0..=1.0).contains(&parent.limit_price) {
+            return Err(DrmError::InvalidInput(format!(
+                "invalid parent order: {} @ {}",
+                parent.size, parent.limit_price
+            )));
+        }
+        match style {
+            ExecutionStyle::Twap { slices: 0, .. } => {
+                return Err(DrmError::InvalidInput(
+                    "TWAP needs at least one slice".into(),
+                ));
+            }
+            ExecutionStyle::Iceberg { show_size } if show_size <= 0.0 => {
+                return Err(DrmError::InvalidInput("show size must be positive".into()));
+            }
+            ExecutionStyle::PassiveThenAggressive { passive_price, .. }
+                if !(0.0..=1.0).contains(&passive_price) =>
+            {
+                return Err(DrmError::InvalidInput(format!(
+                    "invalid passive price: {passive_price}"
+                )));
+            }
+            _ => {}
+        }
+
+        let state = Arc::new(Mutex::new(AlgoState::default()));
+        let state_clone = state.clone();
+        let callback_id = tracker.register_callback(move |event, order, fill_size| {
+            if !matches!(event, OrderEvent::PartialFill | OrderEvent::Filled) {
+                return;
+            }
+            let mut state = state_clone.lock().unwrap();
+            if state.children.contains(&order.id) {
+                state.filled += fill_size;
+                state.notional += fill_size * order.price;
+            }
+        });
+
+        Ok(Self {
+            exchange,
+            tracker,
+            callback_id,
+            parent,
+            style,
+            config: ExecutionConfig::default(),
+            state,
+        })
+    }
+
+    pub fn with_config(mut self, config: ExecutionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn parent(&self) -> &ParentOrder {
+        &self.parent
+    }
+
+    fn log(&self, message: &str) {
+        if self.config.verbose {
+            println!("[execution:{}] {}", self.parent.market_id, message);
+        }
+    }
+
+    fn finish(&self, status: ExecutionStatus) {
+        self.state.lock().unwrap().done = Some(status);
+        self.tracker.remove_callback(self.callback_id);
+    }
+
+    fn working_children(&self) -> Vec<String> {
+        let children = self.state.lock().unwrap().children.clone();
+        children
+            .into_iter()
+            .filter(|id| self.tracker.get_tracked(id).is_some())
+            .collect()
+    }
+
+    pub fn progress(&self) -> ExecutionProgress {
+        let working = self.working_children();
+        let state = self.state.lock().unwrap();
+
+        ExecutionProgress {
+            status: state.done.clone().unwrap_or(ExecutionStatus::Working),
+            size: self.parent.size,
+            filled: state.filled,
+            remaining: (self.parent.size - state.filled).max(0.0),
+            average_price: if state.filled > EPSILON {
+                state.notional / state.filled
+            } else {
+                0.0
+            },
+            children_placed: state.children.len(),
+            working_children: working,
+            uncancelled_children: state.uncancelled.clone(),
+        }
+    }
+
+    /// Fetches every working child and feeds it to the tracker.
+    async fn poll_children(&self) {
+        for id in self.working_children() {
+            match self
+                .exchange
+                .fetch_order(&id, Some(&self.parent.market_id))
+                .await
+            {
+                Ok(order) => {
+                    self.tracker.handle_order_update(&order);
+                }
+                Err(err) => self.log(&format!("fetch_order {id} failed: {err}")),
+            }
+        }
+    }
+
+    /// Cancels working children, applying the final snapshots so fills that
+    /// raced the cancel are counted. Children whose cancel fails are recorded
+    /// as uncancelled; returns whether every cancel succeeded.
+    async fn cancel_children(&self) -> bool {
+        let mut all_cancelled = true;
+
+        for id in self.working_children() {
+            match self
+                .exchange
+                .cancel_order(&id, Some(&self.parent.market_id))
+                .await
+            {
+                Ok(order) => {
+                    self.tracker.handle_order_update(&order);
+                    self.tracker.untrack_order(&id);
+                    self.state.lock().unwrap().uncancelled.retain(|u| *u != id);
+                }
+                Err(err) => {
+                    self.log(&format!("cancel {id} failed: {err}"));
+                    let mut state = self.state.lock().unwrap();
+                    if !state.uncancelled.contains(&id) {
+                        state.uncancelled.push(id);
+                    }
+                    all_cancelled = false;
+                }
+            }
+        }
+
+        all_cancelled
+    }
+
+    /// Cancels working children before new ones are sent. If any cancel
+    /// fails the parent is finished as failed and its progress returned.
+    async fn cancel_before_reprice(&self) -> Option<ExecutionProgress> {
+        if self.cancel_children().await {
+            return None;
+        }
+        self.finish(ExecutionStatus::Failed);
+        self.log("child cancel failed, parent stopped");
+        Some(self.progress())
+    }
+
+    async fn place_child(&self, size: f64, price: f64) -> Result<(), DrmError> {
+        let mut params = HashMap::new();
+        if let Some(token_id) = &self.parent.token_id {
+            params.insert("token_id".to_string(), token_id.clone());
+        }
+
+        let order = self
+            .exchange
+            .create_order(
+                &self.parent.market_id,
+                &self.parent.outcome,
+                self.parent.side,
+                price,
+                size,
+                params,
+            )
+            .await?;
+        self.log(&format!("child {} {size} @ {price}", order.id));
+
+        self.state.lock().unwrap().children.push(order.id.clone());
+        self.tracker.track_order(order.clone());
+        // Orders can fill on placement; pick that up right away.
+        self.tracker.handle_order_update(&order);
+        Ok(())
+    }
+
+    /// One decision cycle: refresh child fills, then place, reprice or
+    /// cancel children as the style dictates.
+    pub async fn step(&self) -> Result<ExecutionProgress, DrmError> {
+        if self.state.lock().unwrap().done.is_some() {
+            return Ok(self.progress());
+        }
+
+        self.poll_children().await;
+
+        let (elapsed, filled) = {
+            let mut state = self.state.lock().unwrap();
+            let started_at = *state.started_at.get_or_insert_with(Instant::now);
+            (started_at.elapsed(), state.filled)
+        };
+        let remaining = self.parent.size - filled;
+        if remaining <= EPSILON.max(self.config.min_child_size) && remaining < self.parent.size {
+            self.cancel_children().await;
+            self.finish(ExecutionStatus::Completed);
+            return Ok(self.progress());
+        }
+        let idle = self.working_children().is_empty();
+        let elapsed_ms = elapsed.as_millis() as u64;
+
+        match self.style {
+            ExecutionStyle::Twap {
+                duration_ms,
+                slices,
+            } => {
+                let slice_ms = (duration_ms / slices as u64).max(1);
+                let due = ((elapsed_ms / slice_ms) as u32 + 1).min(slices);
+                if due > self.state.lock().unwrap().slices_sent {
+                    if let Some(progress) = self.cancel_before_reprice().await {
+                        return Ok(progress);
+                    }
+                    let filled = self.state.lock().unwrap().filled;
+                    let target = self.parent.size * due as f64 / slices as f64;
+                    let size = target - filled;
+                    if size > EPSILON && size >= self.config.min_child_size {
+                        self.place_child(size, self.parent.limit_price).await?;
+                    }
+                    self.state.lock().unwrap().slices_sent = due;
+                }
+            }
+            ExecutionStyle::Iceberg { show_size } => {
+                if idle {
+                    self.place_child(show_size.min(remaining), self.parent.limit_price)
+                        .await?;
+                }
+            }
+            ExecutionStyle::PassiveThenAggressive {
+                passive_price,
+                passive_ms,
+            } => {
+                let aggressive = self.state.lock().unwrap().aggressive;
+                if elapsed_ms < passive_ms {
+                    if idle {
+                        self.place_child(remaining, passive_price).await?;
+                    }
+                } else if !aggressive || idle {
+                    if let Some(progress) = self.cancel_before_reprice().await {
+                        return Ok(progress);
+                    }
+                    self.state.lock().unwrap().aggressive = true;
+                    let remaining = self.parent.size - self.state.lock().unwrap().filled;
+                    if remaining > EPSILON {
+                        self.place_child(remaining, self.parent.limit_price).await?;
+                    }
+                }
+            }
+        }
+
+        Ok(self.progress())
+    }
+
+    /// Cancels the parent: every working child is cancelled and no new ones
+    /// are sent. The parent is cancelled even if some children are not; see
+    /// [`ExecutionProgress::uncancelled_children`].
+    pub async fn cancel(&self) -> Result<ExecutionProgress, DrmError> {
+        if self.state.lock().unwrap().done.is_none() {
+            self.cancel_children().await;
+            self.finish(ExecutionStatus::Cancelled);
+            self.log("parent cancelled");
+        }
+        Ok(self.progress())
+    }
+
+    /// Runs [`step`](Self::step) every `poll_interval_ms` until the parent
+    /// completes or the handle cancels it.
+    pub fn spawn(self) -> ExecutionHandle {
+        let (stop_tx, mut stop_rx) = watch::channel(false);
+        let period = Duration::from_millis(self.config.poll_interval_ms.max(1));
+        let algo = Arc::new(self);
+        let progress_algo = algo.clone();
+
+        let task = tokio::spawn(async move {
+            let mut ticker = interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        match algo.step().await {
+                            Ok(progress) if progress.status != ExecutionStatus::Working => {
+                                return progress;
+                            }
+                            Ok(_) => {}
+                            Err(err) => algo.log(&format!("step failed: {err}")),
+                        }
+                    }
+                    changed = stop_rx.changed() => {
+                        if changed.is_err() || *stop_rx.borrow() {
+                            return match algo.cancel().await {
+                                Ok(progress) => progress,
+                                Err(err) => {
+                                    algo.log(&format!("cancel failed: {err}"));
+                                    algo.progress()
+                                }
+                            };
+                        }
+                    }
+                }
+            }
+        });
+
+        ExecutionHandle {
+            progress: Box::new(move || progress_algo.progress()),
+            stop_tx,
+            task,
+        }
+    }
+}
+
+impl<E: Exchange + ?Sized> Drop for ExecutionAlgo<E> {
+    fn drop(&mut self) {
+        self.tracker.remove_callback(self.callback_id);
+    }
+}
+
+pub struct ExecutionHandle {
+    progress: Box<dyn Fn() -> ExecutionProgress + Send + Sync>,
+    stop_tx: watch::Sender<bool>,
+    task: JoinHandle<ExecutionProgress>,
+}
+
+impl ExecutionHandle {
+    pub fn progress(&self) -> ExecutionProgress {
+        (self.progress)()
+    }
+
+    /// Waits for the parent to complete.
+    pub async fn wait(self) -> Result<ExecutionProgress, DrmError> {
+        self.task
+            .await
+            .map_err(|e| DrmError::Other(format!("execution task failed: {e}")))
+    }
+
+    /// Cancels the parent and all of its children.
+    pub async fn cancel(self) -> Result<ExecutionProgress, DrmError> {
+        self.stop_tx.send_replace(true);
+        self.wait().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OrderStatus;
+    use crate::testing::{make_market, MockExchange};
+
+    fn parent(size: f64) -> ParentOrder {
+        ParentOrder {
+            market_id: "market-1".to_string(),
+            outcome: "Yes".to_string(),
+            side: OrderSide::Buy,
+            size,
+            limit_price: 0.50,
+            token_id: None,
+        }
+    }
+
+    fn fill(exchange: &MockExchange, index: usize, filled: f64) {
+        let mut orders = exchange.orders.lock().unwrap();
+        let order = &mut orders[index];
+        order.filled = filled;
+        order.status = if filled >= order.size {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::PartiallyFilled
+        };
+    }
+
+    fn child_sizes(exchange: &MockExchange) -> Vec<(f64, f64)> {
+        exchange
+            .orders
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|o| (o.size, o.price))
+            .collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_twap_rolls_unfilled_slices_and_cancel_cancels_children() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let tracker = Arc::new(OrderTracker::new(false));
+        let algo = ExecutionAlgo::new(
+            exchange.clone(),
+            tracker.clone(),
+            parent(30.0),
+            ExecutionStyle::Twap {
+                duration_ms: 3000,
+                slices: 3,
+            },
+        )
+        .unwrap();
+
+        // when
+        algo.step().await.unwrap();
+        fill(&exchange, 0, 10.0);
+        tokio::time::advance(Duration::from_millis(1000)).await;
+        algo.step().await.unwrap();
+        tokio::time::advance(Duration::from_millis(1000)).await;
+        algo.step().await.unwrap();
+        fill(&exchange, 2, 5.0);
+        let progress = algo.cancel().await.unwrap();
+
+        // then
+        assert_eq!(
+            child_sizes(&exchange),
+            vec![(10.0, 0.5), (10.0, 0.5), (20.0, 0.5)]
+        );
+        assert_eq!(progress.status, ExecutionStatus::Cancelled);
+        assert_eq!(progress.filled, 15.0);
+        assert_eq!(progress.average_price, 0.5);
+        assert!(progress.working_children.is_empty());
+        assert_eq!(
+            *exchange.cancelled.lock().unwrap(),
+            vec!["order-1".to_string(), "order-2".to_string()]
+        );
+        assert_eq!(tracker.tracked_count(), 0);
+        assert_eq!(tracker.callback_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_finishes_parent_when_a_child_cancel_fails() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let tracker = Arc::new(OrderTracker::new(false));
+        let algo = ExecutionAlgo::new(
+            exchange.clone(),
+            tracker.clone(),
+            parent(10.0),
+            ExecutionStyle::Iceberg { show_size: 5.0 },
+        )
+        .unwrap();
+        algo.step().await.unwrap();
+        exchange.orders.lock().unwrap().clear();
+
+        // when
+        let progress = algo.cancel().await.unwrap();
+
+        // then
+        assert_eq!(progress.status, ExecutionStatus::Cancelled);
+        assert_eq!(progress.uncancelled_children, vec!["order-0".to_string()]);
+        assert_eq!(tracker.tracked_count(), 1);
+        assert_eq!(tracker.callback_count(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failed_cancel_stops_twap_before_next_slice() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let tracker = Arc::new(OrderTracker::new(false));
+        let algo = ExecutionAlgo::new(
+            exchange.clone(),
+            tracker,
+            parent(30.0),
+            ExecutionStyle::Twap {
+                duration_ms: 3000,
+                slices: 3,
+            },
+        )
+        .unwrap();
+        algo.step().await.unwrap();
+        exchange.orders.lock().unwrap().clear();
+
+        // when
+        tokio::time::advance(Duration::from_millis(1000)).await;
+        let progress = algo.step().await.unwrap();
+
+        // then
+        assert_eq!(progress.status, ExecutionStatus::Failed);
+        assert_eq!(progress.uncancelled_children, vec!["order-0".to_string()]);
+        assert_eq!(progress.children_placed, 1);
+        assert!(exchange.orders.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_iceberg_replenishes_show_size() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let tracker = Arc::new(OrderTracker::new(false));
+        let algo = ExecutionAlgo::new(
+            exchange.clone(),
+            tracker.clone(),
+            parent(12.0),
+            ExecutionStyle::Iceberg { show_size: 5.0 },
+        )
+        .unwrap();
+
+        // when
+        let mut progress = algo.step().await.unwrap();
+        for i in 0..3 {
+            let size = exchange.orders.lock().unwrap()[i].size;
+            fill(&exchange, i, size);
+            progress = algo.step().await.unwrap();
+        }
+
+        // then
+        assert_eq!(
+            child_sizes(&exchange),
+            vec![(5.0, 0.5), (5.0, 0.5), (2.0, 0.5)]
+        );
+        assert_eq!(progress.status, ExecutionStatus::Completed);
+        assert_eq!(progress.filled, 12.0);
+        assert_eq!(tracker.callback_count(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_passive_then_aggressive_reprices_to_limit() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let tracker = Arc::new(OrderTracker::new(false));
+        let algo = ExecutionAlgo::new(
+            exchange.clone(),
+            tracker,
+            parent(10.0),
+            ExecutionStyle::PassiveThenAggressive {
+                passive_price: 0.45,
+                passive_ms: 1000,
+            },
+        )
+        .unwrap();
+        let handle = algo.spawn();
+
+        // when
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        fill(&exchange, 0, 4.0);
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+        fill(&exchange, 1, 6.0);
+        let progress = handle.wait().await.unwrap();
+
+        // then
+        assert_eq!(child_sizes(&exchange), vec![(10.0, 0.45), (6.0, 0.5)]);
+        assert_eq!(progress.status, ExecutionStatus::Completed);
+        assert!((progress.average_price - 0.48).abs() < 1e-9);
+    }
+}
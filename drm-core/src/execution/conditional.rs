@@ -0,0 +1,789 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+
+use crate::error::DrmError;
+use crate::exchange::{Exchange, OrderbookSource};
+use crate::models::{Order, OrderSide, Orderbook, OrderbookManager};
+use crate::strategy::{PersistedState, StateStore};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceSource {
+    /// Last traded price, fed through
+    /// [`ConditionalOrderEngine::on_last_price`].
+    Last,
+    BestBid,
+    BestAsk,
+    Mid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerDirection {
+    AtOrAbove,
+    AtOrBelow,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerCondition {
+    pub token_id: String,
+    pub source: PriceSource,
+    pub direction: TriggerDirection,
+    pub level: f64,
+}
+
+impl TriggerCondition {
+    pub fn is_met(&self, price: f64) -> bool {
+        match self.direction {
+            TriggerDirection::AtOrAbove => price >= self.level,
+            TriggerDirection::AtOrBelow => price <= self.level,
+        }
+    }
+}
+
+/// The order sent when a condition triggers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderAction {
+    pub market_id: String,
+    pub outcome: String,
+    pub side: OrderSide,
+    pub price: f64,
+    pub size: f64,
+    /// Token to trade. Defaults to the condition's token, so set it when
+    /// the trigger watches a different book, e.g. selling No on a Yes move.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum ConditionalStatus {
+    Pending,
+    /// Sent to the exchange, result not yet recorded. An order still in
+    /// this state after a restart is not re-armed, so a crash mid-send can
+    /// never submit it twice.
+    Firing,
+    Triggered {
+        order_id: String,
+    },
+    Cancelled,
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalOrder {
+    /// Assigned on submission when empty.
+    #[serde(default)]
+    pub id: String,
+    pub condition: TriggerCondition,
+    pub action: OrderAction,
+    /// Orders sharing a group are one-cancels-other.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oco_group: Option<String>,
+    pub status: ConditionalStatus,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub triggered_at: Option<DateTime<Utc>>,
+}
+
+impl ConditionalOrder {
+    pub fn new(condition: TriggerCondition, action: OrderAction) -> Self {
+        Self {
+            id: String::new(),
+            condition,
+            action,
+            oco_group: None,
+            status: ConditionalStatus::Pending,
+            created_at: Utc::now(),
+            triggered_at: None,
+        }
+    }
+
+    /// Sells `action.size` once the best bid of `token_id` falls to `stop`.
+    pub fn stop_loss(token_id: &str, stop: f64, action: OrderAction) -> Self {
+        Self::new(
+            TriggerCondition {
+                token_id: token_id.to_string(),
+                source: PriceSource::BestBid,
+                direction: TriggerDirection::AtOrBelow,
+                level: stop,
+            },
+            action,
+        )
+    }
+
+    /// Sells `action.size` once the best bid of `token_id` reaches `target`.
+    pub fn take_profit(token_id: &str, target: f64, action: OrderAction) -> Self {
+        Self::new(
+            TriggerCondition {
+                token_id: token_id.to_string(),
+                source: PriceSource::BestBid,
+                direction: TriggerDirection::AtOrAbove,
+                level: target,
+            },
+            action,
+        )
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.status == ConditionalStatus::Pending
+    }
+}
+
+/// Prices of one token that triggers are evaluated against.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PriceQuotes {
+    pub last: Option<f64>,
+    pub best_bid: Option<f64>,
+    pub best_ask: Option<f64>,
+}
+
+impl PriceQuotes {
+    pub fn from_book(book: &Orderbook) -> Self {
+        Self {
+            last: None,
+            best_bid: book.best_bid(),
+            best_ask: book.best_ask(),
+        }
+    }
+
+    pub fn get(&self, source: PriceSource) -> Option<f64> {
+        match source {
+            PriceSource::Last => self.last,
+            PriceSource::BestBid => self.best_bid,
+            PriceSource::BestAsk => self.best_ask,
+            PriceSource::Mid => match (self.best_bid, self.best_ask) {
+                (Some(bid), Some(ask)) => Some((bid + ask) / 2.0),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Where a running engine reads books from.
+#[derive(Clone)]
+pub enum PriceFeed {
+    /// Books kept current by websocket clients.
+    Stream(OrderbookManager),
+    /// Books fetched over REST on every poll.
+    Poll(Arc<dyn OrderbookSource>),
+}
+
+/// Key of the conditional order section in `PersistedState::extra`.
+pub const CONDITIONAL_ORDERS_KEY: &str = "conditional_orders";
+
+/// Conditional orders saved in `state` by a [`ConditionalOrderEngine`].
+pub fn stored_conditional_orders(
+    state: &PersistedState,
+) -> Result<Vec<ConditionalOrder>, DrmError> {
+    match state.extra.get(CONDITIONAL_ORDERS_KEY) {
+        Some(section) => Ok(serde_json::from_value(section.clone())?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// A conditional order that fired, with the result of its `create_order`.
+#[derive(Debug)]
+pub struct TriggeredOrder {
+    pub id: String,
+    pub result: Result<Order, DrmError>,
+}
+
+/// Emulates stop-loss, take-profit and OCO orders locally: pending triggers
+/// are checked against prices and fire `create_order` when met. With a
+/// [`StateStore`] attached, pending orders are saved under
+/// [`CONDITIONAL_ORDERS_KEY`] in `PersistedState::extra` on every change
+/// and reloaded on
+/// construction. Save failures while firing are logged rather than
+/// returned, so callers always learn which orders were sent.
+pub struct ConditionalOrderEngine<E: Exchange + ?Sized> {
+    exchange: Arc<E>,
+    orders: Mutex<Vec<ConditionalOrder>>,
+    store: Option<Arc<dyn StateStore>>,
+    fire_lock: tokio::sync::Mutex<()>,
+    next_id: AtomicU64,
+    verbose: bool,
+}
+
+impl<E: Exchange + ?Sized + 'static> ConditionalOrderEngine<E> {
+    pub fn new(exchange: Arc<E>) -> Self {
+        Self {
+            exchange,
+            orders: Mutex::new(Vec::new()),
+            store: None,
+            fire_lock: tokio::sync::Mutex::new(()),
+            next_id: AtomicU64::new(0),
+            verbose: false,
+        }
+    }
+
+    /// Attaches a store and restores the pending orders saved in it.
+    pub fn with_store(mut self, store: Arc<dyn StateStore>) -> Result<Self, DrmError> {
+        if let Some(state) = store.load()? {
+            let (pending, interrupted): (Vec<ConditionalOrder>, Vec<ConditionalOrder>) =
+                stored_conditional_orders(&state)?
+                    .into_iter()
+                    .partition(ConditionalOrder::is_pending);
+            self.log(&format!("restored {} pending orders", pending.len()));
+            for order in interrupted {
+                self.log(&format!(
+                    "{} was firing at shutdown, not re-armed",
+                    order.id
+                ));
+            }
+            *self.orders.get_mut().unwrap() = pending;
+        }
+        self.store = Some(store);
+        Ok(self)
+    }
+
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    fn log(&self, message: &str) {
+        if self.verbose {
+            println!("[conditional:{}] {}", self.exchange.id(), message);
+        }
+    }
+
+    fn generate_id(&self) -> String {
+        let n = self.next_id.fetch_add(1, Ordering::SeqCst);
+        format!("cond-{}-{n}", Utc::now().timestamp_millis())
+    }
+
+    /// Saves pending and firing orders, keeping the rest of the stored
+    /// state.
+    fn persist(&self) -> Result<(), DrmError> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        let mut state = store.load()?.unwrap_or_default();
+        let saved: Vec<ConditionalOrder> = self
+            .orders
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|o| o.is_pending() || o.status == ConditionalStatus::Firing)
+            .cloned()
+            .collect();
+        state.extra.insert(
+            CONDITIONAL_ORDERS_KEY.to_string(),
+            serde_json::to_value(saved)?,
+        );
+        store.save(&state)
+    }
+
+    fn persist_or_log(&self) {
+        if let Err(err) = self.persist() {
+            self.log(&format!("saving conditional orders failed: {err}"));
+        }
+    }
+
+    fn validate(order: &ConditionalOrder) -> Result<(), DrmError> {
+        let action = &order.action;
+        if action.size <= 0.0 || !(0.0..=1.0).contains(&action.price) {
+            return Err(DrmError::InvalidInput(format!(
+                "invalid conditional order action: {} @ {}",
+                action.size, action.price
+            )));
+        }
+        Ok(())
+    }
+
+    /// Arms a conditional order and returns its id.
+    pub fn submit(&self, mut order: ConditionalOrder) -> Result<String, DrmError> {
+        Self::validate(&order)?;
+        if order.id.is_empty() {
+            order.id = self.generate_id();
+        }
+        order.status = ConditionalStatus::Pending;
+        let id = order.id.clone();
+
+        {
+            let mut orders = self.orders.lock().unwrap();
+            if orders.iter().any(|o| o.id == id) {
+                return Err(DrmError::InvalidInput(format!(
+                    "conditional order {id} already exists"
+                )));
+            }
+            orders.push(order);
+        }
+        self.persist()?;
+        Ok(id)
+    }
+
+    /// Arms two orders as a one-cancels-other pair, e.g. a stop-loss and a
+    /// take-profit bracketing a position.
+    pub fn submit_oco(
+        &self,
+        mut first: ConditionalOrder,
+        mut second: ConditionalOrder,
+    ) -> Result<(String, String), DrmError> {
+        Self::validate(&first)?;
+        Self::validate(&second)?;
+        let group = self.generate_id().replacen("cond", "oco", 1);
+        first.oco_group = Some(group.clone());
+        second.oco_group = Some(group);
+
+        let first_id = self.submit(first)?;
+        match self.submit(second) {
+            Ok(second_id) => Ok((first_id, second_id)),
+            Err(err) => {
+                self.cancel(&first_id)?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Disarms a pending order. Its OCO sibling stays armed.
+    pub fn cancel(&self, id: &str) -> Result<(), DrmError> {
+        {
+            let mut orders = self.orders.lock().unwrap();
+            let order = orders
+                .iter_mut()
+                .find(|o| o.id == id && o.is_pending())
+                .ok_or_else(|| {
+                    DrmError::InvalidInput(format!("no pending conditional order {id}"))
+                })?;
+            order.status = ConditionalStatus::Cancelled;
+        }
+        self.persist()
+    }
+
+    pub fn get(&self, id: &str) -> Option<ConditionalOrder> {
+        self.orders
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|o| o.id == id)
+            .cloned()
+    }
+
+    pub fn pending(&self) -> Vec<ConditionalOrder> {
+        self.orders
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|o| o.is_pending())
+            .cloned()
+            .collect()
+    }
+
+    /// Tokens with at least one pending trigger.
+    pub fn watched_tokens(&self) -> Vec<String> {
+        let mut tokens: Vec<String> = self
+            .orders
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|o| o.is_pending())
+            .map(|o| o.condition.token_id.clone())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        tokens.sort();
+        tokens
+    }
+
+    pub async fn on_book(&self, token_id: &str, book: &Orderbook) -> Vec<TriggeredOrder> {
+        self.evaluate(token_id, PriceQuotes::from_book(book)).await
+    }
+
+    pub async fn on_last_price(&self, token_id: &str, price: f64) -> Vec<TriggeredOrder> {
+        let quotes = PriceQuotes {
+            last: Some(price),
+            ..Default::default()
+        };
+        self.evaluate(token_id, quotes).await
+    }
+
+    /// Fires every pending order on `token_id` whose condition `quotes`
+    /// meet, in submission order. Each order is saved as `Firing` before it
+    /// is sent and with its outcome right after. Once an OCO leg fires its
+    /// siblings are cancelled. A failed `create_order` marks the order
+    /// `Failed` and is reported in the result rather than retried.
+    pub async fn evaluate(&self, token_id: &str, quotes: PriceQuotes) -> Vec<TriggeredOrder> {
+        let _guard = self.fire_lock.lock().await;
+
+        let candidates: Vec<ConditionalOrder> = self
+            .orders
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|o| o.is_pending() && o.condition.token_id == token_id)
+            .filter(|o| {
+                quotes
+                    .get(o.condition.source)
+                    .is_some_and(|price| o.condition.is_met(price))
+            })
+            .cloned()
+            .collect();
+        if candidates.is_empty() {
+            return Vec::new();
+        }
+
+        let mut fired_groups = HashSet::new();
+        let mut triggered = Vec::new();
+        for order in candidates {
+            if let Some(group) = &order.oco_group {
+                if fired_groups.contains(group) {
+                    continue;
+                }
+            }
+
+            {
+                let mut orders = self.orders.lock().unwrap();
+                match orders.iter_mut().find(|o| o.id == order.id) {
+                    Some(stored) if stored.is_pending() => {
+                        stored.status = ConditionalStatus::Firing;
+                    }
+                    _ => continue,
+                }
+            }
+            self.persist_or_log();
+
+            let action = &order.action;
+            let action_token = action
+                .token_id
+                .as_deref()
+                .unwrap_or(&order.condition.token_id);
+            let result = self
+                .exchange
+                .create_order(
+                    &action.market_id,
+                    &action.outcome,
+                    action.side,
+                    action.price,
+                    action.size,
+                    HashMap::from([("token_id".to_string(), action_token.to_string())]),
+                )
+                .await;
+
+            {
+                let mut orders = self.orders.lock().unwrap();
+                let status = match &result {
+                    Ok(created) => ConditionalStatus::Triggered {
+                        order_id: created.id.clone(),
+                    },
+                    Err(err) => ConditionalStatus::Failed {
+                        error: err.to_string(),
+                    },
+                };
+                if let Some(stored) = orders.iter_mut().find(|o| o.id == order.id) {
+                    stored.status = status;
+                    stored.triggered_at = Some(Utc::now());
+                }
+
+                if let (Ok(_), Some(group)) = (&result, &order.oco_group) {
+                    fired_groups.insert(group.clone());
+                    for sibling in orders
+                        .iter_mut()
+                        .filter(|o| o.oco_group.as_ref() == Some(group) && o.is_pending())
+                    {
+                        sibling.status = ConditionalStatus::Cancelled;
+                    }
+                }
+            }
+
+            match &result {
+                Ok(created) => self.log(&format!("{} fired as {}", order.id, created.id)),
+                Err(err) => self.log(&format!("{} failed: {err}", order.id)),
+            }
+            self.persist_or_log();
+            triggered.push(TriggeredOrder {
+                id: order.id,
+                result,
+            });
+        }
+
+        triggered
+    }
+
+    /// Evaluates every watched token once against `feed`.
+    pub async fn poll_once(&self, feed: &PriceFeed) -> Vec<TriggeredOrder> {
+        let mut triggered = Vec::new();
+
+        for token_id in self.watched_tokens() {
+            let book = match feed {
+                PriceFeed::Stream(manager) => match manager.get(&token_id) {
+                    Some(book) => book,
+                    None => continue,
+                },
+                PriceFeed::Poll(source) => match source.fetch_book(&token_id).await {
+                    Ok(book) => Arc::new(book),
+                    Err(err) => {
+                        self.log(&format!("fetch_book {token_id} failed: {err}"));
+                        continue;
+                    }
+                },
+            };
+            triggered.extend(self.on_book(&token_id, &book).await);
+        }
+
+        triggered
+    }
+
+    /// Runs [`poll_once`](Self::poll_once) every `interval_ms` until the
+    /// returned handle is stopped.
+    pub fn spawn(self: Arc<Self>, feed: PriceFeed, interval_ms: u64) -> ConditionalHandle {
+        let (stop_tx, mut stop_rx) = watch::channel(false);
+        let period = Duration::from_millis(interval_ms.max(1));
+
+        let task = tokio::spawn(async move {
+            let mut ticker = interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        self.poll_once(&feed).await;
+                    }
+                    changed = stop_rx.changed() => {
+                        if changed.is_err() || *stop_rx.borrow() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        ConditionalHandle { stop_tx, task }
+    }
+}
+
+pub struct ConditionalHandle {
+    stop_tx: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl ConditionalHandle {
+    pub async fn stop(self) {
+        self.stop_tx.send_replace(true);
+        let _ = self.task.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PriceLevel;
+    use crate::strategy::JsonFileStore;
+    use crate::testing::{make_market, MockExchange};
+    use std::sync::atomic::AtomicBool;
+
+    fn sell(size: f64, price: f64) -> OrderAction {
+        OrderAction {
+            market_id: "market-1".to_string(),
+            outcome: "Yes".to_string(),
+            side: OrderSide::Sell,
+            price,
+            size,
+            token_id: None,
+        }
+    }
+
+    fn book(bid: f64, ask: f64) -> Orderbook {
+        Orderbook {
+            bids: vec![PriceLevel::new(bid, 100.0)],
+            asks: vec![PriceLevel::new(ask, 100.0)],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oco_bracket_fires_one_leg_and_cancels_other() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let engine = ConditionalOrderEngine::new(exchange.clone());
+        let (stop, target) = engine
+            .submit_oco(
+                ConditionalOrder::stop_loss("yes-token", 0.40, sell(10.0, 0.38)),
+                ConditionalOrder::take_profit("yes-token", 0.60, sell(10.0, 0.60)),
+            )
+            .unwrap();
+        let manager = OrderbookManager::new();
+        let feed = PriceFeed::Stream(manager.clone());
+
+        // when
+        manager.update("yes-token", book(0.50, 0.52));
+        let quiet = engine.poll_once(&feed).await;
+        manager.update("yes-token", book(0.61, 0.63));
+        let fired = engine.poll_once(&feed).await;
+
+        // then
+        assert!(quiet.is_empty());
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].id, target);
+        let order = fired[0].result.as_ref().unwrap();
+        assert_eq!(
+            (order.side, order.price, order.size),
+            (OrderSide::Sell, 0.60, 10.0)
+        );
+        assert_eq!(
+            engine.get(&target).unwrap().status,
+            ConditionalStatus::Triggered {
+                order_id: order.id.clone()
+            }
+        );
+        assert_eq!(
+            engine.get(&stop).unwrap().status,
+            ConditionalStatus::Cancelled
+        );
+        assert!(engine.pending().is_empty());
+        assert_eq!(exchange.orders.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pending_triggers_survive_restart() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let path =
+            std::env::temp_dir().join(format!("drm-conditional-{}.json", std::process::id()));
+        let store: Arc<dyn StateStore> = Arc::new(JsonFileStore::new(&path));
+        let engine = ConditionalOrderEngine::new(exchange.clone())
+            .with_store(store.clone())
+            .unwrap();
+        let mut stop = ConditionalOrder::stop_loss("yes-token", 0.40, sell(5.0, 0.35));
+        stop.condition.source = PriceSource::Last;
+        let id = engine.submit(stop).unwrap();
+        drop(engine);
+
+        // when
+        let restarted = ConditionalOrderEngine::new(exchange.clone())
+            .with_store(store.clone())
+            .unwrap();
+        let above = restarted.on_last_price("yes-token", 0.45).await;
+        let fired = restarted.on_last_price("yes-token", 0.39).await;
+        let saved = store.load().unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // then
+        assert_eq!(restarted.pending().len(), 0);
+        assert!(above.is_empty());
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].id, id);
+        assert!(stored_conditional_orders(&saved).unwrap().is_empty());
+        assert_eq!(exchange.orders.lock().unwrap()[0].price, 0.35);
+    }
+
+    #[tokio::test]
+    async fn test_action_trades_its_own_token() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let engine = ConditionalOrderEngine::new(exchange.clone());
+        let action = OrderAction {
+            outcome: "No".to_string(),
+            token_id: Some("no-token".to_string()),
+            ..sell(5.0, 0.55)
+        };
+        let id = engine
+            .submit(ConditionalOrder::take_profit("yes-token", 0.40, action))
+            .unwrap();
+
+        // when
+        let fired = engine.on_book("yes-token", &book(0.40, 0.42)).await;
+
+        // then
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].id, id);
+        let params = exchange.params.lock().unwrap().clone();
+        assert_eq!(params[0]["token_id"], "no-token");
+    }
+
+    /// Keeps every saved state; saves fail while `failing` is set.
+    #[derive(Default)]
+    struct RecordingStore {
+        saves: Mutex<Vec<PersistedState>>,
+        failing: AtomicBool,
+    }
+
+    impl StateStore for RecordingStore {
+        fn load(&self) -> Result<Option<PersistedState>, DrmError> {
+            Ok(self.saves.lock().unwrap().last().cloned())
+        }
+
+        fn save(&self, state: &PersistedState) -> Result<(), DrmError> {
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(DrmError::Other("disk full".into()));
+            }
+            self.saves.lock().unwrap().push(state.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_firing_is_saved_before_sending_and_not_rearmed() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let store = Arc::new(RecordingStore::default());
+        let engine = ConditionalOrderEngine::new(exchange.clone())
+            .with_store(store.clone())
+            .unwrap();
+        let id = engine
+            .submit(ConditionalOrder::stop_loss(
+                "yes-token",
+                0.40,
+                sell(5.0, 0.35),
+            ))
+            .unwrap();
+
+        // when
+        engine.on_book("yes-token", &book(0.39, 0.41)).await;
+        let saves = store.saves.lock().unwrap().clone();
+        let before_send = saves[saves.len() - 2].clone();
+        store.saves.lock().unwrap().push(before_send.clone());
+        let restarted = ConditionalOrderEngine::new(exchange.clone())
+            .with_store(store.clone())
+            .unwrap();
+
+        // then
+        let firing = stored_conditional_orders(&before_send).unwrap();
+        assert_eq!(firing[0].id, id);
+        assert_eq!(firing[0].status, ConditionalStatus::Firing);
+        assert!(stored_conditional_orders(saves.last().unwrap())
+            .unwrap()
+            .is_empty());
+        assert!(restarted.pending().is_empty());
+        restarted.on_book("yes-token", &book(0.30, 0.32)).await;
+        assert_eq!(exchange.orders.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fired_orders_returned_when_save_fails() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let store = Arc::new(RecordingStore::default());
+        let engine = ConditionalOrderEngine::new(exchange.clone())
+            .with_store(store.clone())
+            .unwrap();
+        let id = engine
+            .submit(ConditionalOrder::stop_loss(
+                "yes-token",
+                0.40,
+                sell(5.0, 0.35),
+            ))
+            .unwrap();
+        store.failing.store(true, Ordering::SeqCst);
+
+        // when
+        let fired = engine.on_book("yes-token", &book(0.39, 0.41)).await;
+
+        // then
+        assert_eq!(fired.len(), 1);
+        assert!(fired[0].result.is_ok());
+        assert!(matches!(
+            engine.get(&id).unwrap().status,
+            ConditionalStatus::Triggered { .. }
+        ));
+    }
+}
@@ -0,0 +1,5 @@
+mod algo;
+mod conditional;
+
+pub use algo::*;
+pub use conditional::*;
@@ -1,12 +1,27 @@
+pub mod accounting;
+pub mod arbitrage;
+pub mod backtest;
+#[cfg(feature = "ctf")]
+pub mod ctf;
 pub mod error;
 pub mod exchange;
+pub mod execution;
+pub mod matching;
 pub mod models;
 pub mod strategy;
 pub mod utils;
 pub mod websocket;
 
+#[cfg(test)]
+mod testing;
+
+pub use accounting::*;
+pub use arbitrage::*;
+pub use backtest::*;
 pub use error::*;
 pub use exchange::*;
+pub use execution::*;
+pub use matching::*;
 pub use models::*;
 pub use strategy::*;
 pub use utils::*;
@@ -0,0 +1,427 @@
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::models::{normalize_token_symbol, CryptoHourlyMarket, Market};
+
+use super::overrides::{MarketKey, MatchOverrides};
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "at", "be", "by", "for", "in", "is", "of", "on", "or", "the", "than", "to",
+    "will",
+];
+
+/// Lowercases, drops punctuation and filler words, joins digit groups
+/// ("100,000" -> "100000") and maps coin names to symbols.
+pub fn normalize_question(question: &str) -> String {
+    let chars: Vec<char> = question.chars().collect();
+    let mut cleaned = String::with_capacity(question.len());
+
+    for (i, &c) in chars.iter().enumerate() {
+        let between_digits = i > 0
+            && chars[i - 1].is_ascii_digit()
+            && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit());
+        if c == ',' && between_digits {
+            continue;
+        }
+        if c.is_alphanumeric() || (c == '.' && between_digits) {
+            cleaned.extend(c.to_lowercase());
+        } else {
+            cleaned.push(' ');
+        }
+    }
+
+    cleaned
+        .split_whitespace()
+        .filter(|word| !STOPWORDS.contains(word))
+        .map(|word| normalize_token_symbol(word).to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn dice(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+    2.0 * a.intersection(b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+#[derive(Debug, Clone)]
+pub struct MatcherConfig {
+    /// Pairs scoring below this are not reported.
+    pub min_score: f64,
+    /// Close times this close count as identical.
+    pub close_time_tolerance: Duration,
+    /// Close times further apart than this rule a pair out.
+    pub max_close_time_diff: Duration,
+    pub text_weight: f64,
+    pub close_time_weight: f64,
+    pub outcome_weight: f64,
+}
+
+impl Default for MatcherConfig {
+    fn default() -> Self {
+        Self {
+            min_score: 0.6,
+            close_time_tolerance: Duration::hours(1),
+            max_close_time_diff: Duration::days(7),
+            text_weight: 0.6,
+            close_time_weight: 0.2,
+            outcome_weight: 0.2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketRef {
+    pub exchange: String,
+    pub market_id: String,
+    pub question: String,
+}
+
+impl MarketRef {
+    pub fn key(&self) -> MarketKey {
+        MarketKey::new(&self.exchange, &self.market_id)
+    }
+}
+
+/// Per-component similarities in `0.0..=1.0` and their weighted total.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MatchScore {
+    pub total: f64,
+    pub text: f64,
+    /// 0.5 when either side has no close time.
+    pub close_time: f64,
+    pub outcomes: f64,
+    /// Both questions parsed as the same crypto contract.
+    pub crypto: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchSource {
+    Scored,
+    Override,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchCandidate {
+    pub left: MarketRef,
+    pub right: MarketRef,
+    pub score: MatchScore,
+    pub inverted: bool,
+    pub source: MatchSource,
+}
+
+struct Prepared<'a> {
+    market: &'a Market,
+    words: HashSet<String>,
+    outcomes: HashSet<String>,
+    crypto: Option<CryptoHourlyMarket>,
+}
+
+impl<'a> Prepared<'a> {
+    fn new(market: &'a Market) -> Self {
+        Self {
+            market,
+            words: normalize_question(&market.question)
+                .split(' ')
+                .filter(|w| !w.is_empty())
+                .map(String::from)
+                .collect(),
+            outcomes: market
+                .outcomes
+                .iter()
+                .map(|o| o.trim().to_lowercase())
+                .collect(),
+            crypto: CryptoHourlyMarket::parse(
+                &market.question,
+                market.close_time.unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+/// Scores markets from two venues as candidates for the same question.
+pub struct MarketMatcher {
+    config: MatcherConfig,
+    overrides: MatchOverrides,
+}
+
+impl MarketMatcher {
+    pub fn new(config: MatcherConfig) -> Self {
+        Self {
+            config,
+            overrides: MatchOverrides::default(),
+        }
+    }
+
+    pub fn with_overrides(mut self, overrides: MatchOverrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    pub fn config(&self) -> &MatcherConfig {
+        &self.config
+    }
+
+    pub fn overrides(&self) -> &MatchOverrides {
+        &self.overrides
+    }
+
+    pub fn overrides_mut(&mut self) -> &mut MatchOverrides {
+        &mut self.overrides
+    }
+
+    /// Similarity of two markets, or `None` when a hard constraint rules the
+    /// pair out: close times too far apart or conflicting crypto terms.
+    pub fn score(&self, left: &Market, right: &Market) -> Option<MatchScore> {
+        self.score_prepared(&Prepared::new(left), &Prepared::new(right))
+    }
+
+    fn score_prepared(&self, left: &Prepared, right: &Prepared) -> Option<MatchScore> {
+        let close_time = match (left.market.close_time, right.market.close_time) {
+            (Some(a), Some(b)) => {
+                let diff = (a - b).abs();
+                if diff > self.config.max_close_time_diff {
+                    return None;
+                }
+                if diff <= self.config.close_time_tolerance {
+                    1.0
+                } else {
+                    let span = (self.config.max_close_time_diff - self.config.close_time_tolerance)
+                        .num_seconds()
+                        .max(1) as f64;
+                    let over = (diff - self.config.close_time_tolerance).num_seconds() as f64;
+                    1.0 - over / span
+                }
+            }
+            _ => 0.5,
+        };
+
+        let crypto = match (&left.crypto, &right.crypto) {
+            (Some(a), Some(b)) => {
+                let same_strike = match (a.strike_price, b.strike_price) {
+                    (Some(x), Some(y)) => (x - y).abs() <= x.abs().max(y.abs()) * 1e-3,
+                    (None, None) => true,
+                    _ => false,
+                };
+                if a.token_symbol != b.token_symbol
+                    || a.market_type != b.market_type
+                    || a.direction != b.direction
+                    || !same_strike
+                {
+                    return None;
+                }
+                true
+            }
+            _ => false,
+        };
+
+        // Venues word the same crypto contract very differently, so agreeing
+        // terms stand in for the text.
+        let text = if crypto {
+            1.0
+        } else {
+            dice(&left.words, &right.words)
+        };
+        let outcomes = dice(&left.outcomes, &right.outcomes);
+
+        let weight_sum =
+            self.config.text_weight + self.config.close_time_weight + self.config.outcome_weight;
+        let total = if weight_sum > 0.0 {
+            (text * self.config.text_weight
+                + close_time * self.config.close_time_weight
+                + outcomes * self.config.outcome_weight)
+                / weight_sum
+        } else {
+            0.0
+        };
+
+        Some(MatchScore {
+            total,
+            text,
+            close_time,
+            outcomes,
+            crypto,
+        })
+    }
+
+    /// Every pair scoring at least `min_score`, plus every override link
+    /// between the two lists. Rejected pairs are dropped, and markets pinned
+    /// by a link get no scored candidates. Links come first, then by score.
+    pub fn match_markets(
+        &self,
+        left_exchange: &str,
+        left: &[Market],
+        right_exchange: &str,
+        right: &[Market],
+    ) -> Vec<MatchCandidate> {
+        let left_prepared: Vec<Prepared> = left.iter().map(Prepared::new).collect();
+        let right_prepared: Vec<Prepared> = right.iter().map(Prepared::new).collect();
+        let mut candidates = Vec::new();
+
+        for l in &left_prepared {
+            let left_key = MarketKey::new(left_exchange, &l.market.id);
+            let left_pinned = self.overrides.is_linked(&left_key);
+
+            for r in &right_prepared {
+                let right_key = MarketKey::new(right_exchange, &r.market.id);
+                if self.overrides.is_rejected(&left_key, &right_key) {
+                    continue;
+                }
+
+                let link = self.overrides.find_link(&left_key, &right_key);
+                if link.is_none() && (left_pinned || self.overrides.is_linked(&right_key)) {
+                    continue;
+                }
+
+                let score = self.score_prepared(l, r);
+                let (score, source) = match (link, score) {
+                    (Some(_), Some(score)) => (score, MatchSource::Override),
+                    (Some(_), None) => (
+                        MatchScore {
+                            total: 0.0,
+                            text: 0.0,
+                            close_time: 0.0,
+                            outcomes: 0.0,
+                            crypto: false,
+                        },
+                        MatchSource::Override,
+                    ),
+                    (None, Some(score)) if score.total >= self.config.min_score => {
+                        (score, MatchSource::Scored)
+                    }
+                    _ => continue,
+                };
+
+                candidates.push(MatchCandidate {
+                    left: MarketRef {
+                        exchange: left_exchange.to_string(),
+                        market_id: l.market.id.clone(),
+                        question: l.market.question.clone(),
+                    },
+                    right: MarketRef {
+                        exchange: right_exchange.to_string(),
+                        market_id: r.market.id.clone(),
+                        question: r.market.question.clone(),
+                    },
+                    score,
+                    inverted: link.is_some_and(|l| l.inverted),
+                    source,
+                });
+            }
+        }
+
+        candidates.sort_by(|a, b| {
+            (b.source == MatchSource::Override)
+                .cmp(&(a.source == MatchSource::Override))
+                .then(b.score.total.total_cmp(&a.score.total))
+        });
+        candidates
+    }
+
+    /// Greedily keeps the best candidate for each market so every market
+    /// appears in at most one pair.
+    pub fn best_matches(candidates: &[MatchCandidate]) -> Vec<MatchCandidate> {
+        let mut used: HashSet<MarketKey> = HashSet::new();
+        let mut best = Vec::new();
+
+        for candidate in candidates {
+            let (left, right) = (candidate.left.key(), candidate.right.key());
+            if used.contains(&left) || used.contains(&right) {
+                continue;
+            }
+            used.insert(left);
+            used.insert(right);
+            best.push(candidate.clone());
+        }
+        best
+    }
+}
+
+impl Default for MarketMatcher {
+    fn default() -> Self {
+        Self::new(MatcherConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use std::collections::HashMap;
+
+    fn market(id: &str, question: &str, close_day: u32) -> Market {
+        Market {
+            id: id.to_string(),
+            question: question.to_string(),
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            close_time: Some(Utc.with_ymd_and_hms(2026, 12, close_day, 0, 0, 0).unwrap()),
+            volume: 0.0,
+            liquidity: 0.0,
+            prices: HashMap::new(),
+            metadata: serde_json::Value::Null,
+            tick_size: 0.01,
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_matches_reworded_questions_and_rejects_other_strikes() {
+        // given
+        let polymarket = vec![
+            market("pm-fed", "Will the Fed cut rates in December?", 10),
+            market(
+                "pm-btc",
+                "Will Bitcoin be above $100,000 on December 31?",
+                31,
+            ),
+            market("pm-eth", "Will ETH be above $5,000 on December 31?", 31),
+        ];
+        let kalshi = vec![
+            market("FED-CUT", "Fed cuts rates in December", 10),
+            market("BTC-100K", "BTC above 100000 on Dec 31", 31),
+        ];
+        let matcher = MarketMatcher::default();
+
+        // when
+        let candidates = matcher.match_markets("polymarket", &polymarket, "kalshi", &kalshi);
+
+        // then
+        let pairs: Vec<(&str, &str)> = candidates
+            .iter()
+            .map(|c| (c.left.market_id.as_str(), c.right.market_id.as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("pm-btc", "BTC-100K"), ("pm-fed", "FED-CUT")]);
+        assert!(candidates[0].score.crypto);
+        assert!(matcher.score(&polymarket[2], &kalshi[1]).is_none());
+    }
+
+    #[test]
+    fn test_overrides_pin_and_reject_pairs() {
+        // given
+        let left = vec![
+            market("a", "Will candidate X win the election?", 5),
+            market("b", "Will candidate X lose the election?", 5),
+        ];
+        let right = vec![market("z", "Candidate X wins the election", 5)];
+        let mut overrides = MatchOverrides::new();
+        overrides.link(
+            MarketKey::new("left", "b"),
+            MarketKey::new("right", "z"),
+            true,
+        );
+
+        // when
+        let candidates = MarketMatcher::default()
+            .with_overrides(overrides)
+            .match_markets("left", &left, "right", &right);
+
+        // then
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].left.market_id, "b");
+        assert_eq!(candidates[0].source, MatchSource::Override);
+        assert!(candidates[0].inverted);
+    }
+}
@@ -0,0 +1,5 @@
+mod matcher;
+mod overrides;
+
+pub use matcher::*;
+pub use overrides::*;
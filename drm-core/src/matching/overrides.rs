@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::error::DrmError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MarketKey {
+    pub exchange: String,
+    pub market_id: String,
+}
+
+impl MarketKey {
+    pub fn new(exchange: impl Into<String>, market_id: impl Into<String>) -> Self {
+        Self {
+            exchange: exchange.into(),
+            market_id: market_id.into(),
+        }
+    }
+}
+
+/// A manually reviewed pair of markets.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverrideLink {
+    pub left: MarketKey,
+    pub right: MarketKey,
+    /// Yes on `left` pays out when No on `right` does, e.g. "Will X win?"
+    /// against "Will X lose?".
+    #[serde(default)]
+    pub inverted: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl OverrideLink {
+    fn is_pair(&self, a: &MarketKey, b: &MarketKey) -> bool {
+        (&self.left == a && &self.right == b) || (&self.left == b && &self.right == a)
+    }
+
+    pub fn involves(&self, key: &MarketKey) -> bool {
+        &self.left == key || &self.right == key
+    }
+}
+
+/// Manual decisions that take precedence over scoring: `links` are always
+/// reported as matches, `rejections` never are. Stored as JSON so other
+/// tools can read and edit the same file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MatchOverrides {
+    #[serde(default)]
+    pub links: Vec<OverrideLink>,
+    #[serde(default)]
+    pub rejections: Vec<OverrideLink>,
+}
+
+impl MatchOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DrmError> {
+        let contents = std::fs::read_to_string(path.as_ref())
+            .map_err(|e| DrmError::InvalidInput(format!("cannot open override file: {e}")))?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Like [`load`](Self::load), but a missing file yields no overrides.
+    pub fn load_or_default(path: impl AsRef<Path>) -> Result<Self, DrmError> {
+        if path.as_ref().exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), DrmError> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path.as_ref(), contents)
+            .map_err(|e| DrmError::InvalidInput(format!("cannot write override file: {e}")))
+    }
+
+    /// Records `left` and `right` as the same question, replacing any
+    /// earlier decision about the pair.
+    pub fn link(&mut self, left: MarketKey, right: MarketKey, inverted: bool) {
+        self.remove(&left, &right);
+        self.links.push(OverrideLink {
+            left,
+            right,
+            inverted,
+            note: None,
+        });
+    }
+
+    /// Records `left` and `right` as different questions, replacing any
+    /// earlier decision about the pair.
+    pub fn reject(&mut self, left: MarketKey, right: MarketKey) {
+        self.remove(&left, &right);
+        self.rejections.push(OverrideLink {
+            left,
+            right,
+            inverted: false,
+            note: None,
+        });
+    }
+
+    pub fn remove(&mut self, a: &MarketKey, b: &MarketKey) {
+        self.links.retain(|l| !l.is_pair(a, b));
+        self.rejections.retain(|l| !l.is_pair(a, b));
+    }
+
+    pub fn find_link(&self, a: &MarketKey, b: &MarketKey) -> Option<&OverrideLink> {
+        self.links.iter().find(|l| l.is_pair(a, b))
+    }
+
+    pub fn is_rejected(&self, a: &MarketKey, b: &MarketKey) -> bool {
+        self.rejections.iter().any(|l| l.is_pair(a, b))
+    }
+
+    pub fn is_linked(&self, key: &MarketKey) -> bool {
+        self.links.iter().any(|l| l.involves(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_round_trip_and_replace_decisions() {
+        // given
+        let pm = MarketKey::new("polymarket", "0xabc");
+        let kalshi = MarketKey::new("kalshi", "FED-25DEC-CUT");
+        let mut overrides = MatchOverrides::new();
+        overrides.reject(pm.clone(), kalshi.clone());
+        overrides.link(kalshi.clone(), pm.clone(), true);
+        let path = std::env::temp_dir().join(format!("drm-overrides-{}.json", std::process::id()));
+
+        // when
+        overrides.save(&path).unwrap();
+        let loaded = MatchOverrides::load_or_default(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // then
+        assert!(!loaded.is_rejected(&pm, &kalshi));
+        assert!(loaded.find_link(&pm, &kalshi).unwrap().inverted);
+        assert!(loaded.is_linked(&pm));
+        assert!(MatchOverrides::load_or_default(&path)
+            .unwrap()
+            .links
+            .is_empty());
+    }
+}
@@ -0,0 +1,269 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::l2_book::DEFAULT_BOOK_TICK;
+use super::orderbook::{Orderbook, PriceLevel};
+
+/// Which outcome token a consolidated level's liquidity comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelSource {
+    /// Resting directly on the Yes token book.
+    Yes,
+    /// Synthesized from the No token book at `1 - price`.
+    No,
+    /// Both tokens contribute at this price.
+    Both,
+}
+
+/// A Yes-denominated price level with its size split by source token.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidatedLevel {
+    pub price: f64,
+    pub yes_size: f64,
+    pub no_size: f64,
+}
+
+impl ConsolidatedLevel {
+    pub fn size(&self) -> f64 {
+        self.yes_size + self.no_size
+    }
+
+    pub fn source(&self) -> LevelSource {
+        match (self.yes_size > 0.0, self.no_size > 0.0) {
+            (true, true) => LevelSource::Both,
+            (false, true) => LevelSource::No,
+            _ => LevelSource::Yes,
+        }
+    }
+}
+
+/// Binary market book that merges the Yes and No token books.
+///
+/// A No bid at `p` is a Yes ask at `1 - p`, and a No ask at `p` is a Yes bid
+/// at `1 - p`, so the best prices here are the best available across both
+/// tokens.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsolidatedOrderbook {
+    pub market_id: String,
+    pub yes_asset_id: String,
+    pub no_asset_id: String,
+    pub bids: Vec<ConsolidatedLevel>,
+    pub asks: Vec<ConsolidatedLevel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl ConsolidatedOrderbook {
+    pub fn best_bid(&self) -> Option<f64> {
+        self.bids.first().map(|l| l.price)
+    }
+
+    pub fn best_ask(&self) -> Option<f64> {
+        self.asks.first().map(|l| l.price)
+    }
+
+    pub fn best_bid_source(&self) -> Option<LevelSource> {
+        self.bids.first().map(|l| l.source())
+    }
+
+    pub fn best_ask_source(&self) -> Option<LevelSource> {
+        self.asks.first().map(|l| l.source())
+    }
+
+    pub fn mid_price(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some((bid + ask) / 2.0),
+            _ => None,
+        }
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some(ask - bid),
+            _ => None,
+        }
+    }
+
+    pub fn has_data(&self) -> bool {
+        !self.bids.is_empty() && !self.asks.is_empty()
+    }
+
+    /// Flattens to a plain Yes-denominated [`Orderbook`] with summed sizes.
+    pub fn to_orderbook(&self) -> Orderbook {
+        let flatten = |levels: &[ConsolidatedLevel]| -> Vec<PriceLevel> {
+            levels
+                .iter()
+                .map(|l| PriceLevel::new(l.price, l.size()))
+                .collect()
+        };
+
+        Orderbook {
+            market_id: self.market_id.clone(),
+            asset_id: self.yes_asset_id.clone(),
+            bids: flatten(&self.bids),
+            asks: flatten(&self.asks),
+            last_update_id: None,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+/// Re-expresses a No token book as Yes-denominated levels: its asks become
+/// bids at `1 - p` and its bids become asks at `1 - p`.
+pub fn complement_orderbook(no: &Orderbook) -> Orderbook {
+    let flip = |levels: &[PriceLevel]| -> Vec<PriceLevel> {
+        levels
+            .iter()
+            .map(|l| PriceLevel::new(1.0 - l.price, l.size))
+            .collect()
+    };
+
+    Orderbook {
+        market_id: no.market_id.clone(),
+        asset_id: no.asset_id.clone(),
+        bids: flip(&no.asks),
+        asks: flip(&no.bids),
+        last_update_id: no.last_update_id,
+        timestamp: no.timestamp,
+    }
+}
+
+/// Merges the Yes and No token books of a binary market into a single
+/// Yes-denominated book. Levels at the same price (to [`DEFAULT_BOOK_TICK`])
+/// are combined and keep per-token sizes.
+pub fn consolidate_binary_books(yes: &Orderbook, no: &Orderbook) -> ConsolidatedOrderbook {
+    let synthetic = complement_orderbook(no);
+
+    let merge = |direct: &[PriceLevel], complement: &[PriceLevel], descending: bool| {
+        let mut levels: BTreeMap<u64, ConsolidatedLevel> = BTreeMap::new();
+        let key = |price: f64| (price / DEFAULT_BOOK_TICK).round() as u64;
+
+        for l in direct.iter().filter(|l| l.price > 0.0 && l.size > 0.0) {
+            let entry = levels.entry(key(l.price)).or_insert(ConsolidatedLevel {
+                price: l.price,
+                yes_size: 0.0,
+                no_size: 0.0,
+            });
+            entry.yes_size += l.size;
+        }
+
+        for l in complement.iter().filter(|l| l.price > 0.0 && l.size > 0.0) {
+            let entry = levels.entry(key(l.price)).or_insert(ConsolidatedLevel {
+                price: key(l.price) as f64 * DEFAULT_BOOK_TICK,
+                yes_size: 0.0,
+                no_size: 0.0,
+            });
+            entry.no_size += l.size;
+        }
+
+        if descending {
+            levels.into_values().rev().collect::<Vec<_>>()
+        } else {
+            levels.into_values().collect()
+        }
+    };
+
+    let timestamp = match (yes.timestamp, no.timestamp) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    };
+
+    let market_id = if yes.market_id.is_empty() {
+        no.market_id.clone()
+    } else {
+        yes.market_id.clone()
+    };
+
+    ConsolidatedOrderbook {
+        market_id,
+        yes_asset_id: yes.asset_id.clone(),
+        no_asset_id: no.asset_id.clone(),
+        bids: merge(&yes.bids, &synthetic.bids, true),
+        asks: merge(&yes.asks, &synthetic.asks, false),
+        timestamp,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OrderbookManager;
+
+    fn make_book(asset_id: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> Orderbook {
+        Orderbook {
+            market_id: "market".to_string(),
+            asset_id: asset_id.to_string(),
+            bids: bids.iter().map(|&(p, s)| PriceLevel::new(p, s)).collect(),
+            asks: asks.iter().map(|&(p, s)| PriceLevel::new(p, s)).collect(),
+            last_update_id: None,
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_complement_orderbook_flips_sides() {
+        // given
+        let no = make_book("no", &[(0.40, 10.0)], &[(0.45, 5.0)]);
+
+        // when
+        let yes_view = complement_orderbook(&no);
+
+        // then
+        assert!((yes_view.bids[0].price - 0.55).abs() < 1e-10);
+        assert!((yes_view.asks[0].price - 0.60).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_consolidate_picks_best_across_tokens() {
+        // given
+        let yes = make_book("yes", &[(0.50, 10.0)], &[(0.58, 10.0)]);
+        let no = make_book("no", &[(0.44, 7.0)], &[(0.48, 3.0)]);
+
+        // when
+        let book = consolidate_binary_books(&yes, &no);
+
+        // then
+        assert!((book.best_bid().unwrap() - 0.52).abs() < 1e-10);
+        assert_eq!(book.best_bid_source(), Some(LevelSource::No));
+        assert!((book.best_ask().unwrap() - 0.56).abs() < 1e-10);
+        assert_eq!(book.best_ask_source(), Some(LevelSource::No));
+        assert_eq!(book.bids.len(), 2);
+        assert_eq!(book.asks.len(), 2);
+    }
+
+    #[test]
+    fn test_consolidate_merges_same_price() {
+        // given
+        let yes = make_book("yes", &[(0.50, 10.0)], &[]);
+        let no = make_book("no", &[], &[(0.50, 4.0)]);
+
+        // when
+        let book = consolidate_binary_books(&yes, &no);
+
+        // then
+        assert_eq!(book.bids.len(), 1);
+        assert_eq!(book.bids[0].source(), LevelSource::Both);
+        assert!((book.bids[0].size() - 14.0).abs() < 1e-10);
+        assert!((book.to_orderbook().bids[0].size - 14.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_orderbook_manager_consolidated() {
+        // given
+        let manager = OrderbookManager::new();
+        manager.update("yes", make_book("yes", &[(0.50, 10.0)], &[(0.58, 10.0)]));
+
+        // when
+        let (bid, ask) = manager.get_consolidated_best_bid_ask("yes", "no");
+        manager.update("no", make_book("no", &[(0.44, 7.0)], &[]));
+        let (_, tighter_ask) = manager.get_consolidated_best_bid_ask("yes", "no");
+
+        // then
+        assert!((bid.unwrap() - 0.50).abs() < 1e-10);
+        assert!((ask.unwrap() - 0.58).abs() < 1e-10);
+        assert!((tighter_ask.unwrap() - 0.56).abs() < 1e-10);
+        assert!(manager.get_consolidated("a", "b").is_none());
+    }
+}
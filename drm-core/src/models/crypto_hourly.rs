@@ -1,5 +1,7 @@
 use chrono::{DateTime, Utc};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -24,6 +26,62 @@ pub struct CryptoHourlyMarket {
     pub market_type: Option<CryptoMarketType>,
 }
 
+impl CryptoHourlyMarket {
+    /// Recognizes "BTC Up or Down" and strike questions such as
+    /// "Will Bitcoin be above $100,000 ...". Returns `None` for anything else.
+    pub fn parse(question: &str, expiry_time: DateTime<Utc>) -> Option<Self> {
+        static UP_DOWN: OnceLock<Regex> = OnceLock::new();
+        static STRIKE: OnceLock<Regex> = OnceLock::new();
+
+        let up_down = UP_DOWN.get_or_init(|| {
+            Regex::new(r"(?i)(?P<token>Bitcoin|Ethereum|Solana|BTC|ETH|SOL|XRP)\s+Up or Down")
+                .unwrap()
+        });
+        if let Some(caps) = up_down.captures(question) {
+            return Some(Self {
+                token_symbol: normalize_token_symbol(caps.name("token")?.as_str()),
+                expiry_time,
+                strike_price: None,
+                direction: None,
+                market_type: Some(CryptoMarketType::UpDown),
+            });
+        }
+
+        let strike = STRIKE.get_or_init(|| {
+            Regex::new(
+                r"(?i)(?:(?P<token1>BTC|ETH|SOL|BITCOIN|ETHEREUM|SOLANA)\s+.*?(?P<direction>above|below|over|under|reach)\s+[\$]?(?P<price1>[\d,]+(?:\.\d+)?))|(?:[\$]?(?P<price2>[\d,]+(?:\.\d+)?)\s+.*?(?P<token2>BTC|ETH|SOL|BITCOIN|ETHEREUM|SOLANA))",
+            )
+            .unwrap()
+        });
+        let caps = strike.captures(question)?;
+
+        let token = caps
+            .name("token1")
+            .or_else(|| caps.name("token2"))
+            .map(|m| m.as_str())
+            .unwrap_or("");
+        let price = caps
+            .name("price1")
+            .or_else(|| caps.name("price2"))
+            .map(|m| m.as_str())
+            .unwrap_or("0");
+        let direction = caps
+            .name("direction")
+            .map(|m| match m.as_str().to_lowercase().as_str() {
+                "above" | "over" | "reach" => MarketDirection::Up,
+                _ => MarketDirection::Down,
+            });
+
+        Some(Self {
+            token_symbol: normalize_token_symbol(token),
+            expiry_time,
+            strike_price: Some(price.replace(',', "").parse().unwrap_or(0.0)),
+            direction,
+            market_type: Some(CryptoMarketType::StrikePrice),
+        })
+    }
+}
+
 pub fn normalize_token_symbol(token: &str) -> String {
     match token.to_uppercase().as_str() {
         "BITCOIN" => "BTC".to_string(),
@@ -0,0 +1,392 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::orderbook::{Orderbook, PriceLevel};
+
+/// Default price granularity for [`L2Book`]. Fine enough for every supported
+/// venue (Polymarket trades down to 0.001, Kalshi in cents).
+pub const DEFAULT_BOOK_TICK: f64 = 0.0001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookIntegrity {
+    /// Best bid strictly below best ask, or one side empty.
+    Valid,
+    /// Best bid equals best ask.
+    Locked,
+    /// Best bid above best ask.
+    Crossed,
+}
+
+/// Sorted, incrementally updatable L2 orderbook.
+///
+/// Levels are keyed by integer tick so lookups, inserts and removals are
+/// `O(log n)` and iteration is always in price priority order. Use
+/// [`L2Book::to_orderbook`] to get a plain [`Orderbook`] snapshot.
+#[derive(Debug, Clone)]
+pub struct L2Book {
+    pub market_id: String,
+    pub asset_id: String,
+    tick_size: f64,
+    bids: BTreeMap<u64, f64>,
+    asks: BTreeMap<u64, f64>,
+    pub last_update_id: Option<u64>,
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+impl L2Book {
+    pub fn new(asset_id: impl Into<String>) -> Self {
+        Self::with_tick_size(asset_id, DEFAULT_BOOK_TICK)
+    }
+
+    pub fn with_tick_size(asset_id: impl Into<String>, tick_size: f64) -> Self {
+        let tick_size = if tick_size > 0.0 {
+            tick_size
+        } else {
+            DEFAULT_BOOK_TICK
+        };
+
+        Self {
+            market_id: String::new(),
+            asset_id: asset_id.into(),
+            tick_size,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            last_update_id: None,
+            timestamp: None,
+        }
+    }
+
+    pub fn with_market_id(mut self, market_id: impl Into<String>) -> Self {
+        self.market_id = market_id.into();
+        self
+    }
+
+    pub fn from_orderbook(orderbook: &Orderbook, tick_size: f64) -> Self {
+        let mut book = Self::with_tick_size(orderbook.asset_id.clone(), tick_size)
+            .with_market_id(orderbook.market_id.clone());
+        book.apply_snapshot(
+            orderbook.bids.iter().copied(),
+            orderbook.asks.iter().copied(),
+        );
+        book.last_update_id = orderbook.last_update_id;
+        book.timestamp = orderbook.timestamp;
+        book
+    }
+
+    pub fn tick_size(&self) -> f64 {
+        self.tick_size
+    }
+
+    fn to_tick(&self, price: f64) -> Option<u64> {
+        if !price.is_finite() || price <= 0.0 {
+            return None;
+        }
+        Some((price / self.tick_size).round() as u64)
+    }
+
+    fn to_price(&self, tick: u64) -> f64 {
+        tick as f64 * self.tick_size
+    }
+
+    fn side_mut(&mut self, side: BookSide) -> &mut BTreeMap<u64, f64> {
+        match side {
+            BookSide::Bid => &mut self.bids,
+            BookSide::Ask => &mut self.asks,
+        }
+    }
+
+    /// Replaces both sides with the given levels. Non-positive prices and
+    /// sizes are dropped; duplicate prices keep the last size.
+    pub fn apply_snapshot<B, A>(&mut self, bids: B, asks: A)
+    where
+        B: IntoIterator<Item = PriceLevel>,
+        A: IntoIterator<Item = PriceLevel>,
+    {
+        self.bids.clear();
+        self.asks.clear();
+
+        for level in bids {
+            self.apply_delta(BookSide::Bid, level.price, level.size);
+        }
+        for level in asks {
+            self.apply_delta(BookSide::Ask, level.price, level.size);
+        }
+
+        self.timestamp = Some(Utc::now());
+    }
+
+    /// Sets the absolute size at `price`. A size of zero (or less) removes
+    /// the level. Does not touch `timestamp`; callers stamp once per batch.
+    pub fn apply_delta(&mut self, side: BookSide, price: f64, size: f64) {
+        let tick = match self.to_tick(price) {
+            Some(t) => t,
+            None => return,
+        };
+
+        let levels = self.side_mut(side);
+        if size > 0.0 && size.is_finite() {
+            levels.insert(tick, size);
+        } else {
+            levels.remove(&tick);
+        }
+    }
+
+    /// Drops every level on `side` that is strictly better than `price`.
+    /// Useful when the venue reports a top of book that disagrees with the
+    /// locally maintained levels.
+    pub fn remove_better_than(&mut self, side: BookSide, price: f64) {
+        let tick = match self.to_tick(price) {
+            Some(t) => t,
+            None => return,
+        };
+
+        match side {
+            BookSide::Bid => {
+                self.bids.split_off(&(tick + 1));
+            }
+            BookSide::Ask => {
+                self.asks = self.asks.split_off(&tick);
+            }
+        }
+    }
+
+    pub fn size_at(&self, side: BookSide, price: f64) -> Option<f64> {
+        let tick = self.to_tick(price)?;
+        match side {
+            BookSide::Bid => self.bids.get(&tick).copied(),
+            BookSide::Ask => self.asks.get(&tick).copied(),
+        }
+    }
+
+    pub fn best_bid_level(&self) -> Option<PriceLevel> {
+        self.bids
+            .iter()
+            .next_back()
+            .map(|(&tick, &size)| PriceLevel::new(self.to_price(tick), size))
+    }
+
+    pub fn best_ask_level(&self) -> Option<PriceLevel> {
+        self.asks
+            .iter()
+            .next()
+            .map(|(&tick, &size)| PriceLevel::new(self.to_price(tick), size))
+    }
+
+    pub fn best_bid(&self) -> Option<f64> {
+        self.best_bid_level().map(|l| l.price)
+    }
+
+    pub fn best_ask(&self) -> Option<f64> {
+        self.best_ask_level().map(|l| l.price)
+    }
+
+    pub fn mid_price(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some((bid + ask) / 2.0),
+            _ => None,
+        }
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        match (self.best_bid(), self.best_ask()) {
+            (Some(bid), Some(ask)) => Some(ask - bid),
+            _ => None,
+        }
+    }
+
+    /// Levels on `side` in priority order (bids descending, asks ascending).
+    pub fn levels(&self, side: BookSide) -> Box<dyn Iterator<Item = PriceLevel> + '_> {
+        match side {
+            BookSide::Bid => Box::new(
+                self.bids
+                    .iter()
+                    .rev()
+                    .map(|(&tick, &size)| PriceLevel::new(self.to_price(tick), size)),
+            ),
+            BookSide::Ask => Box::new(
+                self.asks
+                    .iter()
+                    .map(|(&tick, &size)| PriceLevel::new(self.to_price(tick), size)),
+            ),
+        }
+    }
+
+    pub fn depth(&self, side: BookSide) -> usize {
+        match side {
+            BookSide::Bid => self.bids.len(),
+            BookSide::Ask => self.asks.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    pub fn has_data(&self) -> bool {
+        !self.bids.is_empty() && !self.asks.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+    }
+
+    pub fn integrity(&self) -> BookIntegrity {
+        let best_bid = self.bids.keys().next_back();
+        let best_ask = self.asks.keys().next();
+
+        match (best_bid, best_ask) {
+            (Some(bid), Some(ask)) if bid > ask => BookIntegrity::Crossed,
+            (Some(bid), Some(ask)) if bid == ask => BookIntegrity::Locked,
+            _ => BookIntegrity::Valid,
+        }
+    }
+
+    pub fn is_crossed(&self) -> bool {
+        self.integrity() == BookIntegrity::Crossed
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.integrity() == BookIntegrity::Locked
+    }
+
+    pub fn to_orderbook(&self) -> Orderbook {
+        self.to_orderbook_depth(usize::MAX)
+    }
+
+    /// Snapshot limited to the top `depth` levels per side.
+    pub fn to_orderbook_depth(&self, depth: usize) -> Orderbook {
+        Orderbook {
+            market_id: self.market_id.clone(),
+            asset_id: self.asset_id.clone(),
+            bids: self.levels(BookSide::Bid).take(depth).collect(),
+            asks: self.levels(BookSide::Ask).take(depth).collect(),
+            last_update_id: self.last_update_id,
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+impl From<&L2Book> for Orderbook {
+    fn from(book: &L2Book) -> Self {
+        book.to_orderbook()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_book() -> L2Book {
+        let mut book = L2Book::with_tick_size("token", 0.01);
+        book.apply_snapshot(
+            vec![
+                PriceLevel::new(0.45, 10.0),
+                PriceLevel::new(0.47, 5.0),
+                PriceLevel::new(0.46, 7.0),
+            ],
+            vec![
+                PriceLevel::new(0.52, 3.0),
+                PriceLevel::new(0.50, 4.0),
+                PriceLevel::new(0.0, 9.0),
+            ],
+        );
+        book
+    }
+
+    #[test]
+    fn test_snapshot_is_sorted() {
+        // given
+        let book = make_book();
+
+        // when
+        let ob = book.to_orderbook();
+
+        // then
+        let bid_prices: Vec<f64> = ob.bids.iter().map(|l| l.price).collect();
+        let ask_prices: Vec<f64> = ob.asks.iter().map(|l| l.price).collect();
+        assert_eq!(bid_prices.len(), 3);
+        assert!((bid_prices[0] - 0.47).abs() < 1e-10);
+        assert!((bid_prices[2] - 0.45).abs() < 1e-10);
+        assert_eq!(ask_prices.len(), 2);
+        assert!((ask_prices[0] - 0.50).abs() < 1e-10);
+        assert!((ob.mid_price().unwrap() - 0.485).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_apply_delta_updates_and_removes_levels() {
+        // given
+        let mut book = make_book();
+
+        // when
+        book.apply_delta(BookSide::Bid, 0.48, 2.0);
+        book.apply_delta(BookSide::Bid, 0.47, 0.0);
+        book.apply_delta(BookSide::Ask, 0.52, 8.0);
+
+        // then
+        assert!((book.best_bid().unwrap() - 0.48).abs() < 1e-10);
+        assert!(book.size_at(BookSide::Bid, 0.47).is_none());
+        assert_eq!(book.size_at(BookSide::Ask, 0.52), Some(8.0));
+        assert_eq!(book.depth(BookSide::Bid), 3);
+    }
+
+    #[test]
+    fn test_integrity_detects_locked_and_crossed() {
+        // given
+        let mut book = make_book();
+        assert_eq!(book.integrity(), BookIntegrity::Valid);
+
+        // when
+        book.apply_delta(BookSide::Bid, 0.50, 1.0);
+
+        // then
+        assert!(book.is_locked());
+
+        // when
+        book.apply_delta(BookSide::Bid, 0.51, 1.0);
+
+        // then
+        assert!(book.is_crossed());
+    }
+
+    #[test]
+    fn test_remove_better_than() {
+        // given
+        let mut book = make_book();
+        book.apply_delta(BookSide::Bid, 0.55, 1.0);
+        book.apply_delta(BookSide::Ask, 0.40, 1.0);
+
+        // when
+        book.remove_better_than(BookSide::Bid, 0.47);
+        book.remove_better_than(BookSide::Ask, 0.50);
+
+        // then
+        assert!((book.best_bid().unwrap() - 0.47).abs() < 1e-10);
+        assert!((book.best_ask().unwrap() - 0.50).abs() < 1e-10);
+        assert_eq!(book.integrity(), BookIntegrity::Valid);
+    }
+
+    #[test]
+    fn test_round_trip_through_orderbook() {
+        // given
+        let book = make_book().with_market_id("market");
+
+        // when
+        let restored = L2Book::from_orderbook(&book.to_orderbook(), 0.01);
+
+        // then
+        assert_eq!(restored.market_id, "market");
+        assert_eq!(restored.depth(BookSide::Bid), 3);
+        assert_eq!(restored.depth(BookSide::Ask), 2);
+        assert_eq!(book.to_orderbook_depth(1).bids.len(), 1);
+    }
+}
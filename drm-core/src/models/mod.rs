@@ -1,13 +1,19 @@
+mod consolidated;
 mod crypto_hourly;
+mod l2_book;
 mod market;
 mod order;
 mod orderbook;
+mod orderbook_manager;
 mod position;
 mod trade;
 
+pub use consolidated::*;
 pub use crypto_hourly::*;
+pub use l2_book::*;
 pub use market::*;
 pub use order::*;
 pub use orderbook::*;
+pub use orderbook_manager::*;
 pub use position::*;
 pub use trade::*;
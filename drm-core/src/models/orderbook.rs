@@ -1,6 +1,5 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct PriceLevel {
@@ -111,55 +110,3 @@ pub struct RestPriceLevel {
     pub price: String,
     pub size: String,
 }
-
-#[derive(Debug, Default)]
-pub struct OrderbookManager {
-    orderbooks: HashMap<String, Orderbook>,
-}
-
-impl OrderbookManager {
-    pub fn new() -> Self {
-        Self {
-            orderbooks: HashMap::new(),
-        }
-    }
-
-    pub fn update(&mut self, token_id: impl Into<String>, orderbook: Orderbook) {
-        self.orderbooks.insert(token_id.into(), orderbook);
-    }
-
-    pub fn get(&self, token_id: &str) -> Option<&Orderbook> {
-        self.orderbooks.get(token_id)
-    }
-
-    pub fn get_best_bid_ask(&self, token_id: &str) -> (Option<f64>, Option<f64>) {
-        match self.get(token_id) {
-            Some(ob) => (ob.best_bid(), ob.best_ask()),
-            None => (None, None),
-        }
-    }
-
-    pub fn has_data(&self, token_id: &str) -> bool {
-        self.get(token_id).is_some_and(|ob| ob.has_data())
-    }
-
-    pub fn has_all_data(&self, token_ids: &[&str]) -> bool {
-        token_ids.iter().all(|id| self.has_data(id))
-    }
-
-    pub fn clear(&mut self) {
-        self.orderbooks.clear();
-    }
-
-    pub fn len(&self) -> usize {
-        self.orderbooks.len()
-    }
-
-    pub fn is_empty(&self) -> bool {
-        self.orderbooks.is_empty()
-    }
-
-    pub fn iter(&self) -> impl Iterator<Item = (&String, &Orderbook)> {
-        self.orderbooks.iter()
-    }
-}
@@ -0,0 +1,450 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use tokio::sync::{mpsc, watch};
+
+use super::consolidated::{consolidate_binary_books, ConsolidatedOrderbook};
+use super::market::Market;
+use super::orderbook::Orderbook;
+
+pub type OrderbookReceiver = watch::Receiver<Option<Arc<Orderbook>>>;
+
+/// Notification sent to threshold subscribers when the top of book has moved
+/// at least the requested amount since the last notification.
+#[derive(Debug, Clone)]
+pub struct BookChange {
+    pub token_id: String,
+    pub previous: Option<Arc<Orderbook>>,
+    pub current: Arc<Orderbook>,
+    /// Largest absolute move of best bid or best ask. Infinite when a side
+    /// appeared or disappeared.
+    pub top_of_book_move: f64,
+}
+
+struct ThresholdWatcher {
+    threshold: f64,
+    reference: Option<Arc<Orderbook>>,
+    tx: mpsc::UnboundedSender<BookChange>,
+}
+
+struct TokenSlot {
+    tx: watch::Sender<Option<Arc<Orderbook>>>,
+    watchers: Vec<ThresholdWatcher>,
+    publishers: usize,
+}
+
+impl TokenSlot {
+    fn new() -> Self {
+        let (tx, _) = watch::channel(None);
+        Self {
+            tx,
+            watchers: Vec::new(),
+            publishers: 0,
+        }
+    }
+
+    fn is_unused(&self) -> bool {
+        self.publishers == 0
+            && self.tx.receiver_count() == 0
+            && self.watchers.iter().all(|w| w.tx.is_closed())
+    }
+}
+
+/// Thread-safe store of the latest orderbook per token.
+///
+/// Cloning is cheap and every clone shares the same state, so one manager
+/// can be injected into several websocket clients and read from any task.
+/// Snapshots are handed out as `Arc<Orderbook>`, so readers never hold a
+/// lock while inspecting a book.
+#[derive(Clone, Default)]
+pub struct OrderbookManager {
+    slots: Arc<RwLock<HashMap<String, TokenSlot>>>,
+}
+
+impl OrderbookManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes a new book for `token_id`, waking `watch` receivers and
+    /// threshold subscribers.
+    pub fn update(&self, token_id: impl Into<String>, orderbook: Orderbook) {
+        let token_id = token_id.into();
+        let current = Arc::new(orderbook);
+
+        let mut slots = self.slots.write().unwrap();
+        let slot = slots.entry(token_id.clone()).or_insert_with(TokenSlot::new);
+        slot.tx.send_replace(Some(current.clone()));
+
+        slot.watchers.retain_mut(|watcher| {
+            if watcher.tx.is_closed() {
+                return false;
+            }
+
+            let top_of_book_move = match &watcher.reference {
+                Some(previous) => top_of_book_move(previous, &current),
+                None => f64::INFINITY,
+            };
+
+            if top_of_book_move >= watcher.threshold {
+                let change = BookChange {
+                    token_id: token_id.clone(),
+                    previous: watcher.reference.replace(current.clone()),
+                    current: current.clone(),
+                    top_of_book_move,
+                };
+                return watcher.tx.send(change).is_ok();
+            }
+
+            true
+        });
+    }
+
+    pub fn get(&self, token_id: &str) -> Option<Arc<Orderbook>> {
+        let slots = self.slots.read().unwrap();
+        slots
+            .get(token_id)
+            .and_then(|slot| slot.tx.borrow().clone())
+    }
+
+    /// Receiver that always holds the latest book for `token_id`. Use
+    /// `changed().await` to wait for the next update.
+    pub fn watch(&self, token_id: &str) -> OrderbookReceiver {
+        let mut slots = self.slots.write().unwrap();
+        slots
+            .entry(token_id.to_string())
+            .or_insert_with(TokenSlot::new)
+            .tx
+            .subscribe()
+    }
+
+    /// Waits for the next update of `token_id`. Returns `None` if the book
+    /// is removed while waiting.
+    pub async fn wait_for_update(&self, token_id: &str) -> Option<Arc<Orderbook>> {
+        let mut rx = self.watch(token_id);
+        rx.changed().await.ok()?;
+        let book = rx.borrow_and_update().clone();
+        book
+    }
+
+    /// Subscribes to updates where best bid or best ask moved by at least
+    /// `threshold` (in price units) since the last notification. The first
+    /// book received always notifies.
+    pub fn subscribe_threshold(
+        &self,
+        token_id: &str,
+        threshold: f64,
+    ) -> mpsc::UnboundedReceiver<BookChange> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut slots = self.slots.write().unwrap();
+        let slot = slots
+            .entry(token_id.to_string())
+            .or_insert_with(TokenSlot::new);
+
+        slot.watchers.push(ThresholdWatcher {
+            threshold,
+            reference: None,
+            tx,
+        });
+
+        rx
+    }
+
+    /// Registers a feed publishing books for `token_id`. Pair with
+    /// [`release`](Self::release) when the feed unsubscribes.
+    pub fn acquire(&self, token_id: &str) {
+        let mut slots = self.slots.write().unwrap();
+        slots
+            .entry(token_id.to_string())
+            .or_insert_with(TokenSlot::new)
+            .publishers += 1;
+    }
+
+    /// Unregisters a publisher of `token_id`. The book is only dropped once
+    /// the last publisher has left; receivers and threshold subscriptions
+    /// stay registered either way.
+    pub fn release(&self, token_id: &str) -> Option<Arc<Orderbook>> {
+        let mut slots = self.slots.write().unwrap();
+        let slot = slots.get_mut(token_id)?;
+        slot.publishers = slot.publishers.saturating_sub(1);
+        if slot.publishers > 0 {
+            return None;
+        }
+        Self::drop_book(&mut slots, token_id)
+    }
+
+    /// Drops the stored book for `token_id` regardless of publishers.
+    /// Receivers and threshold subscriptions stay registered and see the
+    /// next update.
+    pub fn remove(&self, token_id: &str) -> Option<Arc<Orderbook>> {
+        let mut slots = self.slots.write().unwrap();
+        Self::drop_book(&mut slots, token_id)
+    }
+
+    fn drop_book(slots: &mut HashMap<String, TokenSlot>, token_id: &str) -> Option<Arc<Orderbook>> {
+        let slot = slots.get(token_id)?;
+        let previous = slot.tx.send_replace(None);
+        if slot.is_unused() {
+            slots.remove(token_id);
+        }
+        previous
+    }
+
+    pub fn get_best_bid_ask(&self, token_id: &str) -> (Option<f64>, Option<f64>) {
+        match self.get(token_id) {
+            Some(ob) => (ob.best_bid(), ob.best_ask()),
+            None => (None, None),
+        }
+    }
+
+    pub fn has_data(&self, token_id: &str) -> bool {
+        self.get(token_id).is_some_and(|ob| ob.has_data())
+    }
+
+    pub fn has_all_data(&self, token_ids: &[&str]) -> bool {
+        token_ids.iter().all(|id| self.has_data(id))
+    }
+
+    /// Consolidated Yes-denominated view of a binary market's two token
+    /// books. Returns `None` until at least one of them has been received.
+    pub fn get_consolidated(
+        &self,
+        yes_token_id: &str,
+        no_token_id: &str,
+    ) -> Option<ConsolidatedOrderbook> {
+        let yes = self.get(yes_token_id);
+        let no = self.get(no_token_id);
+        if yes.is_none() && no.is_none() {
+            return None;
+        }
+
+        let empty_yes = Orderbook {
+            asset_id: yes_token_id.to_string(),
+            ..Default::default()
+        };
+        let empty_no = Orderbook {
+            asset_id: no_token_id.to_string(),
+            ..Default::default()
+        };
+
+        Some(consolidate_binary_books(
+            yes.as_deref().unwrap_or(&empty_yes),
+            no.as_deref().unwrap_or(&empty_no),
+        ))
+    }
+
+    pub fn get_consolidated_for_market(&self, market: &Market) -> Option<ConsolidatedOrderbook> {
+        if !market.is_binary() {
+            return None;
+        }
+
+        let token_ids = market.get_token_ids();
+        if token_ids.len() != 2 {
+            return None;
+        }
+
+        let mut book = self.get_consolidated(&token_ids[0], &token_ids[1])?;
+        book.market_id = market.id.clone();
+        Some(book)
+    }
+
+    pub fn get_consolidated_best_bid_ask(
+        &self,
+        yes_token_id: &str,
+        no_token_id: &str,
+    ) -> (Option<f64>, Option<f64>) {
+        match self.get_consolidated(yes_token_id, no_token_id) {
+            Some(book) => (book.best_bid(), book.best_ask()),
+            None => (None, None),
+        }
+    }
+
+    /// Drops every stored book. Existing receivers and threshold
+    /// subscriptions stay registered and see the next update.
+    pub fn clear(&self) {
+        let slots = self.slots.read().unwrap();
+        for slot in slots.values() {
+            slot.tx.send_replace(None);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        let slots = self.slots.read().unwrap();
+        slots.values().filter(|s| s.tx.borrow().is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn token_ids(&self) -> Vec<String> {
+        let slots = self.slots.read().unwrap();
+        slots
+            .iter()
+            .filter(|(_, s)| s.tx.borrow().is_some())
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    pub fn snapshots(&self) -> Vec<(String, Arc<Orderbook>)> {
+        let slots = self.slots.read().unwrap();
+        slots
+            .iter()
+            .filter_map(|(id, s)| s.tx.borrow().clone().map(|ob| (id.clone(), ob)))
+            .collect()
+    }
+}
+
+impl fmt::Debug for OrderbookManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OrderbookManager")
+            .field("tokens", &self.len())
+            .finish()
+    }
+}
+
+fn top_of_book_move(previous: &Orderbook, current: &Orderbook) -> f64 {
+    let side_move = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs(),
+        (None, None) => 0.0,
+        _ => f64::INFINITY,
+    };
+
+    side_move(previous.best_bid(), current.best_bid())
+        .max(side_move(previous.best_ask(), current.best_ask()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PriceLevel;
+    use std::time::Duration;
+
+    fn make_book(bid: f64, ask: f64) -> Orderbook {
+        Orderbook {
+            market_id: "market".to_string(),
+            asset_id: "token".to_string(),
+            bids: vec![PriceLevel::new(bid, 10.0)],
+            asks: vec![PriceLevel::new(ask, 10.0)],
+            last_update_id: None,
+            timestamp: None,
+        }
+    }
+
+    #[test]
+    fn test_update_shared_between_clones() {
+        // given
+        let manager = OrderbookManager::new();
+        let reader = manager.clone();
+
+        // when
+        std::thread::spawn(move || manager.update("token", make_book(0.40, 0.60)))
+            .join()
+            .unwrap();
+
+        // then
+        assert_eq!(reader.len(), 1);
+        assert_eq!(reader.get_best_bid_ask("token"), (Some(0.40), Some(0.60)));
+        assert!(reader.has_all_data(&["token"]));
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_update() {
+        // given
+        let manager = OrderbookManager::new();
+        let publisher = manager.clone();
+
+        // when
+        let waiter = tokio::spawn(async move { manager.wait_for_update("token").await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        publisher.update("token", make_book(0.45, 0.55));
+
+        // then
+        let book = waiter.await.unwrap().unwrap();
+        assert_eq!(book.best_bid(), Some(0.45));
+    }
+
+    #[test]
+    fn test_threshold_notifications() {
+        // given
+        let manager = OrderbookManager::new();
+        let mut rx = manager.subscribe_threshold("token", 0.015);
+
+        // when
+        manager.update("token", make_book(0.40, 0.60));
+        manager.update("token", make_book(0.41, 0.60));
+        manager.update("token", make_book(0.42, 0.60));
+
+        // then
+        let first = rx.try_recv().unwrap();
+        assert!(first.previous.is_none());
+        let second = rx.try_recv().unwrap();
+        assert!((second.top_of_book_move - 0.02).abs() < 1e-9);
+        assert_eq!(second.current.best_bid(), Some(0.42));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_clear_keeps_watchers() {
+        // given
+        let manager = OrderbookManager::new();
+        let rx = manager.watch("token");
+        manager.update("token", make_book(0.40, 0.60));
+
+        // when
+        manager.clear();
+
+        // then
+        assert!(manager.is_empty());
+        assert!(rx.borrow().is_none());
+        manager.update("token", make_book(0.40, 0.60));
+        assert!(rx.borrow().is_some());
+    }
+
+    #[test]
+    fn test_release_keeps_book_until_last_publisher() {
+        // given
+        let manager = OrderbookManager::new();
+        let rx = manager.watch("token");
+        manager.acquire("token");
+        manager.acquire("token");
+        manager.update("token", make_book(0.40, 0.60));
+
+        // when
+        let first = manager.release("token");
+
+        // then
+        assert!(first.is_none());
+        assert!(manager.has_data("token"));
+
+        // when
+        let last = manager.release("token");
+
+        // then
+        assert_eq!(last.unwrap().best_bid(), Some(0.40));
+        assert!(rx.borrow().is_none());
+        assert!(rx.has_changed().is_ok());
+        manager.update("token", make_book(0.41, 0.60));
+        assert_eq!(rx.borrow().as_ref().unwrap().best_bid(), Some(0.41));
+    }
+
+    #[test]
+    fn test_remove_keeps_watchers() {
+        // given
+        let manager = OrderbookManager::new();
+        let rx = manager.watch("token");
+        let mut changes = manager.subscribe_threshold("token", 0.0);
+        manager.update("token", make_book(0.40, 0.60));
+        changes.try_recv().unwrap();
+
+        // when
+        manager.remove("token");
+        manager.update("token", make_book(0.42, 0.60));
+
+        // then
+        assert!(rx.has_changed().is_ok());
+        assert_eq!(rx.borrow().as_ref().unwrap().best_bid(), Some(0.42));
+        assert!(changes.try_recv().is_ok());
+    }
+}
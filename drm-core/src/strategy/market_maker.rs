@@ -0,0 +1,564 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::error::DrmError;
+use crate::exchange::Exchange;
+use crate::models::{calculate_delta, Order, OrderSide, Orderbook, OrderbookManager, Position};
+use crate::utils::{clamp_price, round_to_tick_size};
+
+use super::order_tracker::OrderEvent;
+use super::runtime::EventDrivenStrategy;
+use super::traits::{
+    BaseStrategy, MarketMakingConfig, Strategy, StrategyConfig, StrategyEvent, StrategyState,
+};
+
+/// A single resting order the market maker wants to have on the book.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    pub outcome: String,
+    pub token_id: Option<String>,
+    pub side: OrderSide,
+    pub price: f64,
+    pub size: f64,
+}
+
+/// Two-sided market maker for binary markets.
+///
+/// Every refresh it bids both outcomes around their mid, offers inventory it
+/// already holds, shifts quotes away from the heavier outcome in proportion to
+/// the `calculate_delta` imbalance, and replaces resting orders whose price
+/// or size no longer matches. Bids stop once positions reach
+/// `MarketMakingConfig::max_exposure`.
+pub struct MarketMaker<E: Exchange + 'static> {
+    pub base: BaseStrategy<E>,
+    pub mm_config: MarketMakingConfig,
+    orderbooks: OrderbookManager,
+    /// Books delivered through `on_orderbook`, for feeds that bypass the
+    /// manager (e.g. backtests).
+    last_books: HashMap<String, Arc<Orderbook>>,
+    inventory_skew: f64,
+}
+
+impl<E: Exchange + 'static> MarketMaker<E> {
+    pub fn new(
+        exchange: Arc<E>,
+        market_id: String,
+        config: StrategyConfig,
+        mm_config: MarketMakingConfig,
+    ) -> Self {
+        Self {
+            base: BaseStrategy::new(exchange, market_id, config),
+            mm_config,
+            orderbooks: OrderbookManager::new(),
+            last_books: HashMap::new(),
+            inventory_skew: 1.0,
+        }
+    }
+
+    /// Reads mids from `orderbooks` instead of the market's last prices.
+    pub fn with_orderbook_manager(mut self, orderbooks: OrderbookManager) -> Self {
+        self.orderbooks = orderbooks;
+        self
+    }
+
+    /// Fraction of the half spread to shift quotes by at full inventory
+    /// (`max_position_size`). `0.0` disables skewing.
+    pub fn with_inventory_skew(mut self, inventory_skew: f64) -> Self {
+        self.inventory_skew = inventory_skew.max(0.0);
+        self
+    }
+
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<StrategyEvent> {
+        self.base.subscribe()
+    }
+
+    fn spread_bps(&self) -> u32 {
+        self.base
+            .config
+            .spread_bps
+            .max(self.mm_config.min_spread_bps)
+    }
+
+    fn tick_size(&self) -> f64 {
+        match &self.base.market {
+            Some(m) if m.tick_size > 0.0 => m.tick_size,
+            _ => 0.01,
+        }
+    }
+
+    fn position_size(&self, outcome: &str) -> f64 {
+        self.base
+            .get_position(outcome)
+            .map(|p| p.size)
+            .unwrap_or(0.0)
+    }
+
+    /// Cost basis of every position held in this market.
+    pub fn current_exposure(&self) -> f64 {
+        self.base.positions.iter().map(|p| p.cost_basis()).sum()
+    }
+
+    fn mid_for(&self, outcome: &str, token_id: Option<&str>) -> Option<f64> {
+        token_id
+            .and_then(|id| {
+                self.orderbooks
+                    .get(id)
+                    .or_else(|| self.last_books.get(id).cloned())
+            })
+            .and_then(|ob| ob.mid_price())
+            .or_else(|| {
+                self.base
+                    .market
+                    .as_ref()
+                    .and_then(|m| m.prices.get(outcome).copied())
+            })
+            .filter(|&mid| mid > 0.0 && mid < 1.0)
+    }
+
+    /// Signed price shift for `outcome`: negative when it is the outcome we
+    /// are long, positive for the other one.
+    fn skew_for(&self, outcome: &str, half_spread: f64) -> f64 {
+        let sizes: HashMap<String, f64> = self
+            .base
+            .positions
+            .iter()
+            .map(|p| (p.outcome.clone(), p.size))
+            .collect();
+        let delta = calculate_delta(&sizes);
+
+        let max_position = self.base.config.max_position_size;
+        if delta.delta <= 0.0 || max_position <= 0.0 {
+            return 0.0;
+        }
+
+        let magnitude = half_spread * self.inventory_skew * (delta.delta / max_position).min(1.0);
+        match delta.max_outcome.as_deref() {
+            Some(heavy) if heavy == outcome => -magnitude,
+            _ => magnitude,
+        }
+    }
+
+    /// Quotes the strategy wants resting right now, given current positions
+    /// and mids. Empty until the market has been loaded.
+    pub fn desired_quotes(&self) -> Result<Vec<Quote>, DrmError> {
+        let market = match &self.base.market {
+            Some(m) if m.is_binary() => m,
+            Some(_) => {
+                return Err(DrmError::InvalidInput(
+                    "MarketMaker only supports binary markets".to_string(),
+                ))
+            }
+            None => return Ok(Vec::new()),
+        };
+
+        let tick = self.tick_size();
+        let spread_bps = self.spread_bps();
+        let mut budget = self.mm_config.max_exposure - self.current_exposure();
+        let mut quotes = Vec::new();
+
+        for token in market.get_outcome_tokens() {
+            let token_id = Some(token.token_id.clone()).filter(|id| !id.is_empty());
+            let mid = match self.mid_for(&token.outcome, token_id.as_deref()) {
+                Some(mid) => mid,
+                None => continue,
+            };
+
+            let (bid, ask) = self.base.calculate_spread_prices(mid, spread_bps);
+            let skew = self.skew_for(&token.outcome, (ask - bid) / 2.0);
+            let bid = clamp_price(bid + skew, tick, 1.0 - tick, tick)?;
+            let mut ask = clamp_price(ask + skew, tick, 1.0 - tick, tick)?;
+            if ask <= bid {
+                ask = round_to_tick_size(bid + tick, tick)?;
+            }
+
+            let held = self.position_size(&token.outcome);
+            let room = self.base.config.max_position_size - held;
+            if budget > 0.0 && room > 0.0 {
+                let size = self
+                    .base
+                    .calculate_order_size(bid, budget)
+                    .min(self.mm_config.max_order_size)
+                    .min(room);
+                if size > 0.0 {
+                    budget -= size * bid;
+                    quotes.push(Quote {
+                        outcome: token.outcome.clone(),
+                        token_id: token_id.clone(),
+                        side: OrderSide::Buy,
+                        price: bid,
+                        size,
+                    });
+                }
+            }
+
+            if held > 0.0 && ask < 1.0 {
+                quotes.push(Quote {
+                    outcome: token.outcome.clone(),
+                    token_id,
+                    side: OrderSide::Sell,
+                    price: ask,
+                    size: held.min(self.mm_config.max_order_size),
+                });
+            }
+        }
+
+        Ok(quotes)
+    }
+
+    fn matches(order: &Order, quote: &Quote, tick: f64) -> bool {
+        order.outcome == quote.outcome
+            && order.side == quote.side
+            && (order.price - quote.price).abs() < tick / 2.0
+            && order.remaining() >= quote.size / 2.0
+    }
+
+    /// Cancels stale quotes and places missing ones.
+    pub async fn update_quotes(&mut self) -> Result<(), DrmError> {
+        let tick = self.tick_size();
+        let mut wanted = self.desired_quotes()?;
+
+        let mut stale = Vec::new();
+        for order in &self.base.open_orders {
+            match wanted.iter().position(|q| Self::matches(order, q, tick)) {
+                Some(idx) => {
+                    wanted.remove(idx);
+                }
+                None => stale.push(order.id.clone()),
+            }
+        }
+
+        for order_id in stale {
+            self.base
+                .exchange
+                .cancel_order(&order_id, Some(&self.base.market_id))
+                .await?;
+            self.base.open_orders.retain(|o| o.id != order_id);
+            self.base.order_tracker().untrack_order(&order_id);
+            self.base.log(&format!("Cancelled stale quote {order_id}"));
+        }
+
+        for quote in wanted {
+            self.base
+                .place_order(
+                    &quote.outcome,
+                    quote.side,
+                    quote.price,
+                    quote.size,
+                    quote.token_id.as_deref(),
+                )
+                .await?;
+            self.base.log(&format!(
+                "Quoted {:?} {} {:.2} @ {:.4}",
+                quote.side, quote.outcome, quote.size, quote.price
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<E: Exchange + 'static> Strategy for MarketMaker<E> {
+    fn name(&self) -> &str {
+        "market_maker"
+    }
+
+    fn config(&self) -> &StrategyConfig {
+        &self.base.config
+    }
+
+    fn state(&self) -> StrategyState {
+        self.base.state
+    }
+
+    async fn on_tick(&mut self) -> Result<(), DrmError> {
+        if !self.base.is_running() {
+            return Ok(());
+        }
+        self.base.refresh_state().await?;
+        self.update_quotes().await
+    }
+
+    async fn start(&mut self) -> Result<(), DrmError> {
+        self.base.market = Some(
+            self.base
+                .exchange
+                .fetch_market(&self.base.market_id)
+                .await?,
+        );
+        self.base.state = StrategyState::Running;
+        let _ = self.base.event_tx.send(StrategyEvent::Started);
+        self.base.log("Market maker started");
+        Ok(())
+    }
+
+    async fn stop(&mut self) -> Result<(), DrmError> {
+        self.base.cancel_all_orders().await?;
+        self.base.state = StrategyState::Stopped;
+        let _ = self.base.event_tx.send(StrategyEvent::Stopped);
+        self.base.log("Market maker stopped");
+        Ok(())
+    }
+
+    fn pause(&mut self) {
+        self.base.pause();
+    }
+
+    fn resume(&mut self) {
+        self.base.resume();
+    }
+
+    fn events(&self) -> Option<tokio::sync::broadcast::Receiver<StrategyEvent>> {
+        Some(self.base.subscribe())
+    }
+}
+
+#[async_trait]
+impl<E: Exchange + 'static> EventDrivenStrategy for MarketMaker<E> {
+    fn name(&self) -> &str {
+        "market_maker"
+    }
+
+    async fn on_start(&mut self) -> Result<(), DrmError> {
+        Strategy::start(self).await?;
+        self.base.refresh_state().await?;
+        self.update_quotes().await
+    }
+
+    async fn on_stop(&mut self) -> Result<(), DrmError> {
+        Strategy::stop(self).await
+    }
+
+    async fn on_orderbook(
+        &mut self,
+        token_id: &str,
+        orderbook: Arc<Orderbook>,
+    ) -> Result<(), DrmError> {
+        self.last_books.insert(token_id.to_string(), orderbook);
+        if !self.base.is_running() {
+            return Ok(());
+        }
+        self.update_quotes().await
+    }
+
+    async fn on_fill(
+        &mut self,
+        _event: OrderEvent,
+        order: &Order,
+        fill_size: f64,
+    ) -> Result<(), DrmError> {
+        self.base.apply_fill(order, fill_size);
+        self.base.apply_order_update(order);
+        if !self.base.is_running() {
+            return Ok(());
+        }
+        self.update_quotes().await
+    }
+
+    async fn on_order_update(&mut self, _event: OrderEvent, order: &Order) -> Result<(), DrmError> {
+        self.base.apply_order_update(order);
+        Ok(())
+    }
+
+    async fn on_reconcile(
+        &mut self,
+        positions: &[Position],
+        open_orders: &[Order],
+    ) -> Result<(), DrmError> {
+        self.base.positions = positions.to_vec();
+        self.base.open_orders = open_orders.to_vec();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PriceLevel;
+    use crate::strategy::OrderSweeper;
+    use crate::testing::{make_market, MockExchange};
+
+    fn make_maker(exchange: Arc<MockExchange>) -> MarketMaker<MockExchange> {
+        let config = StrategyConfig {
+            spread_bps: 400,
+            max_position_size: 100.0,
+            ..Default::default()
+        };
+        let mm_config = MarketMakingConfig {
+            max_exposure: 100.0,
+            max_order_size: 10.0,
+            ..Default::default()
+        };
+        MarketMaker::new(exchange, "market-1".to_string(), config, mm_config)
+    }
+
+    fn make_position(outcome: &str, size: f64, average_price: f64) -> Position {
+        Position {
+            market_id: "market-1".to_string(),
+            outcome: outcome.to_string(),
+            size,
+            average_price,
+            current_price: average_price,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quotes_both_outcomes_on_tick() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let mut maker = make_maker(exchange.clone());
+        maker.start().await.unwrap();
+
+        // when
+        maker.on_tick().await.unwrap();
+
+        // then
+        let orders = exchange.open_orders();
+        assert_eq!(orders.len(), 2);
+        for order in &orders {
+            assert_eq!(order.side, OrderSide::Buy);
+            assert!((order.price - 0.49).abs() < 1e-9);
+            assert!((order.size - 10.0).abs() < 1e-9);
+        }
+        assert!(orders.iter().any(|o| o.outcome == "Yes"));
+        assert!(orders.iter().any(|o| o.outcome == "No"));
+    }
+
+    #[tokio::test]
+    async fn test_inventory_skews_quotes_and_offers_inventory() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        exchange
+            .positions
+            .lock()
+            .unwrap()
+            .push(make_position("Yes", 50.0, 0.5));
+        let mut maker = make_maker(exchange.clone()).with_inventory_skew(2.0);
+        maker.start().await.unwrap();
+        maker.base.refresh_state().await.unwrap();
+
+        // when
+        let quotes = maker.desired_quotes().unwrap();
+
+        // then
+        let find = |outcome: &str, side: OrderSide| {
+            quotes
+                .iter()
+                .find(|q| q.outcome == outcome && q.side == side)
+                .unwrap()
+                .price
+        };
+        assert!((find("Yes", OrderSide::Buy) - 0.48).abs() < 1e-9);
+        assert!((find("Yes", OrderSide::Sell) - 0.50).abs() < 1e-9);
+        assert!((find("No", OrderSide::Buy) - 0.50).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_replaces_stale_quotes_when_mid_moves() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let orderbooks = OrderbookManager::new();
+        let mut maker = make_maker(exchange.clone()).with_orderbook_manager(orderbooks.clone());
+        maker.start().await.unwrap();
+        maker.on_tick().await.unwrap();
+        let first_ids: Vec<String> = exchange.open_orders().into_iter().map(|o| o.id).collect();
+
+        // when
+        orderbooks.update(
+            "yes-token",
+            Orderbook {
+                market_id: "market-1".to_string(),
+                asset_id: "yes-token".to_string(),
+                bids: vec![PriceLevel::new(0.59, 10.0)],
+                asks: vec![PriceLevel::new(0.61, 10.0)],
+                last_update_id: None,
+                timestamp: None,
+            },
+        );
+        maker.on_tick().await.unwrap();
+
+        // then
+        let cancelled = exchange.cancelled.lock().unwrap().clone();
+        assert_eq!(cancelled.len(), 1);
+        assert!(first_ids.contains(&cancelled[0]));
+        let orders = exchange.open_orders();
+        assert_eq!(orders.len(), 2);
+        let yes = orders.iter().find(|o| o.outcome == "Yes").unwrap();
+        assert!((yes.price - 0.59).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_quotes_expire_after_order_ttl() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let config = StrategyConfig {
+            spread_bps: 400,
+            order_ttl_ms: Some(0),
+            ..Default::default()
+        };
+        let mut maker = MarketMaker::new(
+            exchange.clone(),
+            "market-1".to_string(),
+            config,
+            MarketMakingConfig::default(),
+        );
+        maker.start().await.unwrap();
+        maker.on_tick().await.unwrap();
+        let quoted = exchange.open_orders().len();
+        let sweeper = OrderSweeper::new(
+            exchange.clone(),
+            maker.base.order_tracker().clone(),
+            Default::default(),
+        );
+
+        // when
+        let events = sweeper.sweep_once().await;
+
+        // then
+        assert_eq!(quoted, 2);
+        assert_eq!(events.len(), quoted);
+        assert!(events.iter().all(|(_, e)| *e == OrderEvent::Expired));
+        assert!(exchange.open_orders().is_empty());
+        assert_eq!(maker.base.order_tracker().tracked_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_stops_bidding_at_exposure_cap() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        exchange
+            .positions
+            .lock()
+            .unwrap()
+            .push(make_position("Yes", 40.0, 0.5));
+        exchange
+            .positions
+            .lock()
+            .unwrap()
+            .push(make_position("No", 40.0, 0.5));
+        let mut maker = make_maker(exchange.clone());
+        maker.start().await.unwrap();
+
+        // when
+        maker.on_tick().await.unwrap();
+
+        // then
+        let orders = exchange.open_orders();
+        assert_eq!(orders.len(), 4);
+        let bids: Vec<&Order> = orders.iter().filter(|o| o.side == OrderSide::Buy).collect();
+        assert_eq!(bids.len(), 2);
+        let bid_notional: f64 = bids.iter().map(|o| o.price * o.size).sum();
+        assert!(bid_notional <= 60.0 + 1e-9);
+
+        // when
+        exchange.positions.lock().unwrap()[0].size = 160.0;
+        maker.on_tick().await.unwrap();
+
+        // then
+        assert!(exchange
+            .open_orders()
+            .iter()
+            .all(|o| o.side == OrderSide::Sell));
+    }
+}
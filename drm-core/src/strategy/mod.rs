@@ -1,5 +1,19 @@
+mod market_maker;
 mod order_tracker;
+mod persistence;
+mod portfolio;
+mod reconciler;
+mod runner;
+mod runtime;
+mod sweeper;
 mod traits;
 
+pub use market_maker::*;
 pub use order_tracker::*;
+pub use persistence::*;
+pub use portfolio::*;
+pub use reconciler::*;
+pub use runner::*;
+pub use runtime::*;
+pub use sweeper::*;
 pub use traits::*;
@@ -1,7 +1,9 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::models::{Order, OrderStatus};
 
@@ -15,11 +17,18 @@ pub enum OrderEvent {
     Expired,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackedOrder {
     pub order: Order,
     pub total_filled: f64,
     pub created_time: DateTime<Utc>,
+    /// Venue-side expiry of a GTD order.
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Local time-to-live; [`OrderSweeper`](super::OrderSweeper) cancels
+    /// the order once it is older than this.
+    #[serde(default)]
+    pub ttl_ms: Option<u64>,
 }
 
 impl TrackedOrder {
@@ -29,15 +38,31 @@ impl TrackedOrder {
             order,
             total_filled: filled,
             created_time: Utc::now(),
+            expires_at: None,
+            ttl_ms: None,
         }
     }
+
+    /// Whether the order has outlived its TTL at `now`.
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        self.ttl_ms.is_some_and(|ttl| {
+            now - self.created_time >= chrono::Duration::milliseconds(ttl as i64)
+        })
+    }
 }
 
 pub type OrderCallback = Arc<dyn Fn(OrderEvent, &Order, f64) + Send + Sync>;
 
+/// Handle returned by [`OrderTracker::register_callback`], used to remove
+/// the callback again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallbackId(u64);
+
 pub struct OrderTracker {
     tracked_orders: RwLock<HashMap<String, TrackedOrder>>,
-    callbacks: RwLock<Vec<OrderCallback>>,
+    callbacks: RwLock<Vec<(CallbackId, OrderCallback)>>,
+    next_callback_id: AtomicU64,
+    default_ttl_ms: Option<u64>,
     verbose: bool,
 }
 
@@ -46,20 +71,59 @@ impl OrderTracker {
         Self {
             tracked_orders: RwLock::new(HashMap::new()),
             callbacks: RwLock::new(Vec::new()),
+            next_callback_id: AtomicU64::new(0),
+            default_ttl_ms: None,
             verbose,
         }
     }
 
+    /// TTL applied to orders tracked without an explicit one, typically
+    /// the owning strategy's `StrategyConfig::order_ttl_ms`.
+    pub fn with_default_ttl(mut self, ttl_ms: Option<u64>) -> Self {
+        self.default_ttl_ms = ttl_ms;
+        self
+    }
+
     pub fn on_fill<F>(&self, callback: F) -> &Self
     where
         F: Fn(OrderEvent, &Order, f64) + Send + Sync + 'static,
     {
-        let mut callbacks = self.callbacks.write().unwrap();
-        callbacks.push(Arc::new(callback));
+        self.register_callback(callback);
         self
     }
 
+    /// Like [`on_fill`](Self::on_fill), for callbacks that should not live
+    /// as long as the tracker. Pass the id to
+    /// [`remove_callback`](Self::remove_callback) when done.
+    pub fn register_callback<F>(&self, callback: F) -> CallbackId
+    where
+        F: Fn(OrderEvent, &Order, f64) + Send + Sync + 'static,
+    {
+        let id = CallbackId(self.next_callback_id.fetch_add(1, Ordering::Relaxed));
+        let mut callbacks = self.callbacks.write().unwrap();
+        callbacks.push((id, Arc::new(callback)));
+        id
+    }
+
+    /// Returns whether a callback was registered under `id`.
+    pub fn remove_callback(&self, id: CallbackId) -> bool {
+        let mut callbacks = self.callbacks.write().unwrap();
+        let before = callbacks.len();
+        callbacks.retain(|(callback_id, _)| *callback_id != id);
+        callbacks.len() != before
+    }
+
+    pub fn callback_count(&self) -> usize {
+        self.callbacks.read().unwrap().len()
+    }
+
     pub fn track_order(&self, order: Order) {
+        self.track_order_with_expiry(order, None);
+    }
+
+    /// Tracks a GTD order. If the venue later reports it cancelled after
+    /// `expires_at`, the tracker emits `Expired` instead of `Cancelled`.
+    pub fn track_order_with_expiry(&self, order: Order, expires_at: Option<DateTime<Utc>>) {
         let order_id = order.id.clone();
         let mut tracked = self.tracked_orders.write().unwrap();
 
@@ -76,7 +140,19 @@ impl OrderTracker {
             println!("Tracking order {id_preview}...");
         }
 
-        tracked.insert(order_id, TrackedOrder::new(order));
+        let mut tracked_order = TrackedOrder::new(order);
+        tracked_order.expires_at = expires_at;
+        tracked_order.ttl_ms = self.default_ttl_ms;
+        tracked.insert(order_id, tracked_order);
+    }
+
+    /// Tracks an order with its own TTL instead of the tracker default.
+    pub fn track_order_with_ttl(&self, order: Order, ttl_ms: Option<u64>) {
+        let order_id = order.id.clone();
+        self.track_order(order);
+        if let Some(tracked_order) = self.tracked_orders.write().unwrap().get_mut(&order_id) {
+            tracked_order.ttl_ms = ttl_ms;
+        }
     }
 
     pub fn untrack_order(&self, order_id: &str) {
@@ -156,8 +232,150 @@ impl OrderTracker {
         }
     }
 
+    /// Applies a REST snapshot of a tracked order. Fill progress beyond
+    /// `total_filled` emits `PartialFill`/`Filled` with the difference;
+    /// terminal statuses emit `Cancelled`, `Expired` or `Rejected` and stop
+    /// tracking the order. Returns the emitted events.
+    pub fn handle_order_update(&self, order: &Order) -> Vec<OrderEvent> {
+        let now = Utc::now();
+        let mut events = Vec::new();
+
+        let (fill, terminal) = {
+            let mut tracked = self.tracked_orders.write().unwrap();
+            let tracked_order = match tracked.get_mut(&order.id) {
+                Some(t) => t,
+                None => return events,
+            };
+
+            let reported_filled = if order.status == OrderStatus::Filled {
+                order.filled.max(tracked_order.order.size)
+            } else {
+                order.filled
+            };
+            let fill_size = reported_filled - tracked_order.total_filled;
+
+            let mut updated_order = order.clone();
+            updated_order.size = tracked_order.order.size;
+            updated_order.filled = tracked_order.total_filled.max(reported_filled);
+            if updated_order.filled >= updated_order.size {
+                updated_order.status = OrderStatus::Filled;
+            }
+            tracked_order.total_filled = updated_order.filled;
+            tracked_order.order = updated_order.clone();
+
+            let fill = (fill_size > 1e-9).then(|| {
+                let event = if updated_order.status == OrderStatus::Filled {
+                    OrderEvent::Filled
+                } else {
+                    OrderEvent::PartialFill
+                };
+                (event, fill_size)
+            });
+
+            let terminal = match updated_order.status {
+                OrderStatus::Cancelled
+                    if tracked_order.expires_at.is_some_and(|expiry| expiry <= now) =>
+                {
+                    Some(OrderEvent::Expired)
+                }
+                OrderStatus::Cancelled => Some(OrderEvent::Cancelled),
+                OrderStatus::Rejected => Some(OrderEvent::Rejected),
+                _ => None,
+            };
+
+            (
+                fill.map(|f| (f, updated_order.clone())),
+                terminal.map(|t| (t, updated_order)),
+            )
+        };
+
+        if let Some(((event, fill_size), updated_order)) = fill {
+            self.emit(event, &updated_order, fill_size);
+            events.push(event);
+            if event == OrderEvent::Filled {
+                self.untrack_order(&order.id);
+            }
+        }
+
+        if let Some((event, updated_order)) = terminal {
+            self.emit(event, &updated_order, 0.0);
+            events.push(event);
+            self.untrack_order(&order.id);
+        }
+
+        events
+    }
+
+    /// Handles a tracked order the venue no longer returns. GTD orders past
+    /// their expiry emit `Expired`; anything else is left for the next pass.
+    pub fn handle_missing(&self, order_id: &str) -> Option<OrderEvent> {
+        let order = {
+            let tracked = self.tracked_orders.read().unwrap();
+            let tracked_order = tracked.get(order_id)?;
+            if tracked_order
+                .expires_at
+                .is_none_or(|expiry| expiry > Utc::now())
+            {
+                return None;
+            }
+            tracked_order.order.clone()
+        };
+
+        self.emit(OrderEvent::Expired, &order, 0.0);
+        self.untrack_order(order_id);
+        Some(OrderEvent::Expired)
+    }
+
+    /// Tracked orders that have outlived their TTL at `now`.
+    pub fn stale_orders(&self, now: DateTime<Utc>) -> Vec<TrackedOrder> {
+        self.tracked_orders
+            .read()
+            .unwrap()
+            .values()
+            .filter(|t| t.is_stale(now))
+            .cloned()
+            .collect()
+    }
+
+    /// Handles an order cancelled for exceeding its TTL. Fill progress in
+    /// `snapshot` (usually the cancel response) is emitted first; unless
+    /// that completes the order, `Expired` follows and tracking stops.
+    pub fn handle_expired(&self, order_id: &str, snapshot: Option<&Order>) -> Vec<OrderEvent> {
+        let mut events = Vec::new();
+        if let Some(snapshot) = snapshot {
+            let mut snapshot = snapshot.clone();
+            if snapshot.status != OrderStatus::Filled {
+                snapshot.status = OrderStatus::Open;
+            }
+            events.extend(self.handle_order_update(&snapshot));
+        }
+
+        let order = {
+            let tracked = self.tracked_orders.read().unwrap();
+            tracked.get(order_id).map(|t| Order {
+                status: OrderStatus::Cancelled,
+                updated_at: Some(Utc::now()),
+                ..t.order.clone()
+            })
+        };
+
+        if let Some(order) = order {
+            self.emit(OrderEvent::Expired, &order, 0.0);
+            self.untrack_order(order_id);
+            events.push(OrderEvent::Expired);
+        }
+        events
+    }
+
     fn emit(&self, event: OrderEvent, order: &Order, fill_size: f64) {
-        let callbacks = self.callbacks.read().unwrap();
+        // Snapshot first so a callback may register or remove callbacks.
+        let callbacks: Vec<OrderCallback> = self
+            .callbacks
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
         for callback in callbacks.iter() {
             callback(event, order, fill_size);
         }
@@ -167,6 +385,10 @@ impl OrderTracker {
         self.tracked_orders.read().unwrap().len()
     }
 
+    pub fn get_tracked(&self, order_id: &str) -> Option<TrackedOrder> {
+        self.tracked_orders.read().unwrap().get(order_id).cloned()
+    }
+
     pub fn get_tracked_orders(&self) -> Vec<Order> {
         self.tracked_orders
             .read()
@@ -176,6 +398,27 @@ impl OrderTracker {
             .collect()
     }
 
+    /// Copies every tracked order with its cumulative fill, for persistence.
+    pub fn snapshot(&self) -> Vec<TrackedOrder> {
+        self.tracked_orders
+            .read()
+            .unwrap()
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// Reinstates orders from [`snapshot`](Self::snapshot) without emitting
+    /// events. Orders already tracked are left alone.
+    pub fn restore(&self, orders: Vec<TrackedOrder>) {
+        let mut tracked = self.tracked_orders.write().unwrap();
+        for tracked_order in orders {
+            tracked
+                .entry(tracked_order.order.id.clone())
+                .or_insert(tracked_order);
+        }
+    }
+
     pub fn clear(&self) {
         self.tracked_orders.write().unwrap().clear();
     }
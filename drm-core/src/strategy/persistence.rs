@@ -0,0 +1,358 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::error::DrmError;
+use crate::exchange::{Exchange, FetchOrdersParams};
+use crate::models::{Order, Position};
+
+use super::order_tracker::{OrderEvent, OrderTracker, TrackedOrder};
+use super::reconciler::{OrderReconciler, ReconcilerConfig};
+use super::traits::StrategyState;
+
+pub const STATE_VERSION: u32 = 1;
+
+/// What a strategy needs to resume quoting after a restart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategySnapshot {
+    pub market_id: String,
+    pub state: StrategyState,
+    pub positions: Vec<Position>,
+    pub open_orders: Vec<Order>,
+    /// Strategy-specific state.
+    #[serde(default)]
+    pub extra: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistedState {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub tracked_orders: Vec<TrackedOrder>,
+    /// Keyed by strategy name.
+    #[serde(default)]
+    pub strategies: HashMap<String, StrategySnapshot>,
+    /// Sections saved by other components, keyed by owner.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl Default for PersistedState {
+    fn default() -> Self {
+        Self {
+            version: STATE_VERSION,
+            saved_at: None,
+            tracked_orders: Vec::new(),
+            strategies: HashMap::new(),
+            extra: HashMap::new(),
+        }
+    }
+}
+
+impl PersistedState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tracker(mut self, tracker: &OrderTracker) -> Self {
+        self.tracked_orders = tracker.snapshot();
+        self
+    }
+
+    pub fn with_strategy(mut self, name: impl Into<String>, snapshot: StrategySnapshot) -> Self {
+        self.strategies.insert(name.into(), snapshot);
+        self
+    }
+}
+
+/// Storage backend for [`PersistedState`].
+pub trait StateStore: Send + Sync {
+    /// `Ok(None)` when nothing has been saved yet.
+    fn load(&self) -> Result<Option<PersistedState>, DrmError>;
+
+    fn save(&self, state: &PersistedState) -> Result<(), DrmError>;
+}
+
+/// Stores state as one JSON document. Writes go to a temporary file that is
+/// renamed over the target, so a crash mid-write leaves the previous state.
+pub struct JsonFileStore {
+    path: PathBuf,
+}
+
+impl JsonFileStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl StateStore for JsonFileStore {
+    fn load(&self) -> Result<Option<PersistedState>, DrmError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let contents = std::fs::read_to_string(&self.path)
+            .map_err(|e| DrmError::InvalidInput(format!("cannot read state file: {e}")))?;
+        let state: PersistedState = serde_json::from_str(&contents)?;
+        if state.version > STATE_VERSION {
+            return Err(DrmError::InvalidInput(format!(
+                "state file version {} is newer than supported version {STATE_VERSION}",
+                state.version
+            )));
+        }
+        Ok(Some(state))
+    }
+
+    fn save(&self, state: &PersistedState) -> Result<(), DrmError> {
+        let mut state = state.clone();
+        state.saved_at = Some(Utc::now());
+        let contents = serde_json::to_string_pretty(&state)?;
+
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, contents)
+            .and_then(|_| std::fs::rename(&tmp, &self.path))
+            .map_err(|e| DrmError::Other(format!("cannot write state file: {e}")))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RecoveryReport {
+    /// Orders reinstated from the saved state.
+    pub restored: usize,
+    /// Events for fills, cancels and expiries that happened while down.
+    pub events: Vec<(String, OrderEvent)>,
+    /// Open orders on the exchange that the saved state did not know about.
+    pub adopted: Vec<String>,
+    /// Restored orders that are no longer working on the exchange and could
+    /// not be resolved to a fill or cancel, e.g. because the venue has
+    /// forgotten them. They are untracked without an event.
+    pub dropped: Vec<String>,
+}
+
+/// Rehydrates `tracker` from `state` and reconciles it with the exchange:
+/// missed fills and cancels are emitted through the tracker's callbacks, and
+/// resting orders placed after the last save are tracked. Afterwards the
+/// tracker holds exactly the exchange's working orders for `market_id`.
+pub async fn recover_orders<E: Exchange + ?Sized + 'static>(
+    exchange: Arc<E>,
+    tracker: Arc<OrderTracker>,
+    state: &PersistedState,
+    market_id: Option<&str>,
+) -> Result<RecoveryReport, DrmError> {
+    let restored = state.tracked_orders.len();
+    tracker.restore(state.tracked_orders.clone());
+
+    let config = ReconcilerConfig {
+        market_id: market_id.map(String::from),
+        ..Default::default()
+    };
+    let events = OrderReconciler::new(exchange.clone(), tracker.clone(), config)
+        .reconcile_once()
+        .await?;
+
+    let params = market_id.map(|id| FetchOrdersParams {
+        market_id: Some(id.to_string()),
+    });
+    let open = exchange.fetch_open_orders(params).await?;
+
+    let mut dropped = Vec::new();
+    for order in tracker.get_tracked_orders() {
+        let in_scope = market_id.is_none_or(|id| order.market_id == id);
+        if in_scope && !open.iter().any(|o| o.id == order.id) {
+            tracker.untrack_order(&order.id);
+            dropped.push(order.id);
+        }
+    }
+
+    let mut adopted = Vec::new();
+    for order in open {
+        if tracker.get_tracked(&order.id).is_none() {
+            adopted.push(order.id.clone());
+            tracker.track_order(order);
+        }
+    }
+
+    Ok(RecoveryReport {
+        restored,
+        events,
+        adopted,
+        dropped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{OrderSide, OrderStatus};
+    use crate::strategy::{BaseStrategy, StrategyConfig};
+    use crate::testing::{make_market, MockExchange};
+
+    #[tokio::test]
+    async fn test_restart_recovers_working_orders() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let mut strategy = BaseStrategy::new(
+            exchange.clone(),
+            "market-1".to_string(),
+            StrategyConfig::default(),
+        );
+        let tracker = OrderTracker::new(false);
+        let quote = strategy
+            .place_order("Yes", OrderSide::Buy, 0.45, 10.0, None)
+            .await
+            .unwrap();
+        tracker.track_order(quote.clone());
+
+        let path = std::env::temp_dir().join(format!("drm-state-{}.json", std::process::id()));
+        let store = JsonFileStore::new(&path);
+        store
+            .save(
+                &PersistedState::new()
+                    .with_tracker(&tracker)
+                    .with_strategy("quoter", strategy.snapshot()),
+            )
+            .unwrap();
+
+        // while down: the quote half fills and another order is placed
+        {
+            let mut orders = exchange.orders.lock().unwrap();
+            orders[0].filled = 5.0;
+            orders[0].status = OrderStatus::PartiallyFilled;
+        }
+        let orphan = exchange
+            .create_order("market-1", "No", OrderSide::Buy, 0.40, 5.0, HashMap::new())
+            .await
+            .unwrap();
+
+        // when
+        let state = store.load().unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let tracker = Arc::new(OrderTracker::new(false));
+        let report = recover_orders(exchange.clone(), tracker.clone(), &state, Some("market-1"))
+            .await
+            .unwrap();
+        let mut restarted = BaseStrategy::new(
+            exchange.clone(),
+            "market-1".to_string(),
+            StrategyConfig::default(),
+        );
+        restarted
+            .restore(&state.strategies["quoter"])
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!(report.restored, 1);
+        assert_eq!(
+            report.events,
+            vec![(quote.id.clone(), OrderEvent::PartialFill)]
+        );
+        assert_eq!(report.adopted, vec![orphan.id.clone()]);
+        assert!(report.dropped.is_empty());
+        assert_eq!(tracker.get_tracked(&quote.id).unwrap().total_filled, 5.0);
+        let mut working: Vec<String> = restarted.open_orders.iter().map(|o| o.id.clone()).collect();
+        working.sort();
+        assert_eq!(working, vec![quote.id, orphan.id]);
+    }
+
+    fn make_strategy(exchange: Arc<MockExchange>) -> BaseStrategy<MockExchange> {
+        BaseStrategy::new(exchange, "market-1".to_string(), StrategyConfig::default())
+    }
+
+    fn unknown_order() -> Order {
+        Order {
+            id: "gone".to_string(),
+            market_id: "market-1".to_string(),
+            outcome: "Yes".to_string(),
+            side: OrderSide::Buy,
+            price: 0.45,
+            size: 10.0,
+            filled: 0.0,
+            status: OrderStatus::Open,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recover_drops_orders_unknown_to_exchange() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let saved = OrderTracker::new(false);
+        saved.track_order(unknown_order());
+        let state = PersistedState::new().with_tracker(&saved);
+
+        // when
+        let tracker = Arc::new(OrderTracker::new(false));
+        let report = recover_orders(exchange, tracker.clone(), &state, Some("market-1"))
+            .await
+            .unwrap();
+
+        // then
+        assert_eq!(report.restored, 1);
+        assert!(report.events.is_empty());
+        assert_eq!(report.dropped, vec!["gone".to_string()]);
+        assert_eq!(tracker.tracked_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_recover_adopts_orders_never_stored() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let resting = exchange
+            .create_order(
+                "market-1",
+                "Yes",
+                OrderSide::Sell,
+                0.60,
+                5.0,
+                HashMap::new(),
+            )
+            .await
+            .unwrap();
+
+        // when
+        let tracker = Arc::new(OrderTracker::new(false));
+        let report = recover_orders(
+            exchange,
+            tracker.clone(),
+            &PersistedState::new(),
+            Some("market-1"),
+        )
+        .await
+        .unwrap();
+
+        // then
+        assert_eq!(report.restored, 0);
+        assert_eq!(report.adopted, vec![resting.id.clone()]);
+        assert!(tracker.get_tracked(&resting.id).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_strategy_restore_matches_exchange_orders() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let resting = exchange
+            .create_order("market-1", "No", OrderSide::Buy, 0.40, 5.0, HashMap::new())
+            .await
+            .unwrap();
+        let mut snapshot = make_strategy(exchange.clone()).snapshot();
+        snapshot.open_orders = vec![unknown_order()];
+
+        // when
+        let mut strategy = make_strategy(exchange);
+        strategy.restore(&snapshot).await.unwrap();
+
+        // then
+        let working: Vec<&str> = strategy.open_orders.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(working, vec![resting.id.as_str()]);
+    }
+}
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::error::DrmError;
+use crate::exchange::Exchange;
+use crate::models::{Market, Order, OrderSide, Position};
+
+use super::traits::{StrategyConfig, StrategyEvent, StrategyState};
+
+/// State for one market traded by a [`PortfolioStrategyBase`].
+#[derive(Debug, Clone)]
+pub struct MarketSlot {
+    pub exchange: String,
+    pub market_id: String,
+    pub market: Option<Market>,
+    pub positions: Vec<Position>,
+    pub open_orders: Vec<Order>,
+}
+
+impl MarketSlot {
+    pub fn exposure(&self) -> f64 {
+        self.positions.iter().map(|p| p.cost_basis()).sum()
+    }
+}
+
+/// Multi-exchange, multi-market counterpart of
+/// [`BaseStrategy`](super::BaseStrategy). Exchanges are registered under a
+/// name and markets are addressed as `(exchange, market_id)`.
+pub struct PortfolioStrategyBase {
+    exchanges: HashMap<String, Arc<dyn Exchange>>,
+    slots: Vec<MarketSlot>,
+    pub state: StrategyState,
+    pub config: StrategyConfig,
+    pub event_tx: broadcast::Sender<StrategyEvent>,
+}
+
+impl PortfolioStrategyBase {
+    pub fn new(config: StrategyConfig) -> Self {
+        let (event_tx, _) = broadcast::channel(100);
+
+        Self {
+            exchanges: HashMap::new(),
+            slots: Vec::new(),
+            state: StrategyState::Stopped,
+            config,
+            event_tx,
+        }
+    }
+
+    pub fn with_exchange(mut self, name: impl Into<String>, exchange: Arc<dyn Exchange>) -> Self {
+        self.exchanges.insert(name.into(), exchange);
+        self
+    }
+
+    /// Adds a market on a registered exchange. Adding it twice is a no-op.
+    pub fn add_market(&mut self, exchange: &str, market_id: &str) -> Result<(), DrmError> {
+        if !self.exchanges.contains_key(exchange) {
+            return Err(DrmError::InvalidInput(format!(
+                "unknown exchange: {exchange}"
+            )));
+        }
+        if self.slot(exchange, market_id).is_none() {
+            self.slots.push(MarketSlot {
+                exchange: exchange.to_string(),
+                market_id: market_id.to_string(),
+                market: None,
+                positions: Vec::new(),
+                open_orders: Vec::new(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn with_market(mut self, exchange: &str, market_id: &str) -> Result<Self, DrmError> {
+        self.add_market(exchange, market_id)?;
+        Ok(self)
+    }
+
+    pub fn exchange(&self, name: &str) -> Result<&Arc<dyn Exchange>, DrmError> {
+        self.exchanges
+            .get(name)
+            .ok_or_else(|| DrmError::InvalidInput(format!("unknown exchange: {name}")))
+    }
+
+    pub fn slots(&self) -> &[MarketSlot] {
+        &self.slots
+    }
+
+    pub fn slot(&self, exchange: &str, market_id: &str) -> Option<&MarketSlot> {
+        self.slots
+            .iter()
+            .find(|s| s.exchange == exchange && s.market_id == market_id)
+    }
+
+    fn slot_mut(&mut self, exchange: &str, market_id: &str) -> Option<&mut MarketSlot> {
+        self.slots
+            .iter_mut()
+            .find(|s| s.exchange == exchange && s.market_id == market_id)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<StrategyEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Fetches market metadata for every slot that has none yet.
+    pub async fn load_markets(&mut self) -> Result<(), DrmError> {
+        for i in 0..self.slots.len() {
+            if self.slots[i].market.is_some() {
+                continue;
+            }
+            let exchange = self.exchange(&self.slots[i].exchange)?.clone();
+            let market = exchange.fetch_market(&self.slots[i].market_id).await?;
+            self.slots[i].market = Some(market);
+        }
+        Ok(())
+    }
+
+    /// Refreshes positions and open orders with one request of each per
+    /// exchange and distributes the results to the slots.
+    pub async fn refresh_state(&mut self) -> Result<(), DrmError> {
+        let names: Vec<String> = self.exchanges.keys().cloned().collect();
+
+        for name in names {
+            if !self.slots.iter().any(|s| s.exchange == name) {
+                continue;
+            }
+            let exchange = self.exchanges[&name].clone();
+            let (positions, orders) = tokio::try_join!(
+                exchange.fetch_positions(None),
+                exchange.fetch_open_orders(None)
+            )?;
+
+            for slot in self.slots.iter_mut().filter(|s| s.exchange == name) {
+                slot.positions = positions
+                    .iter()
+                    .filter(|p| p.market_id == slot.market_id)
+                    .cloned()
+                    .collect();
+                slot.open_orders = orders
+                    .iter()
+                    .filter(|o| o.market_id == slot.market_id)
+                    .cloned()
+                    .collect();
+            }
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn place_order(
+        &mut self,
+        exchange: &str,
+        market_id: &str,
+        outcome: &str,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+        token_id: Option<&str>,
+    ) -> Result<Order, DrmError> {
+        if self.slot(exchange, market_id).is_none() {
+            return Err(DrmError::InvalidInput(format!(
+                "market {market_id} on {exchange} is not part of this strategy"
+            )));
+        }
+
+        let mut params = HashMap::new();
+        if let Some(tid) = token_id {
+            params.insert("token_id".to_string(), tid.to_string());
+        }
+
+        let order = self
+            .exchange(exchange)?
+            .create_order(market_id, outcome, side, price, size, params)
+            .await?;
+
+        if let Some(slot) = self.slot_mut(exchange, market_id) {
+            slot.open_orders.push(order.clone());
+        }
+        let _ = self.event_tx.send(StrategyEvent::Order(order.clone()));
+
+        Ok(order)
+    }
+
+    /// Applies an order event from `exchange` without a REST round trip.
+    pub fn apply_order_update(&mut self, exchange: &str, order: &Order) {
+        let slot = match self.slot_mut(exchange, &order.market_id) {
+            Some(slot) => slot,
+            None => return,
+        };
+
+        let existing = slot.open_orders.iter().position(|o| o.id == order.id);
+        match (existing, order.is_active()) {
+            (Some(idx), true) => slot.open_orders[idx] = order.clone(),
+            (Some(idx), false) => {
+                slot.open_orders.remove(idx);
+            }
+            (None, true) => slot.open_orders.push(order.clone()),
+            (None, false) => {}
+        }
+    }
+
+    /// Cancels every open order in every slot. Returns the first error after
+    /// attempting all cancels.
+    pub async fn cancel_all_orders(&mut self) -> Result<(), DrmError> {
+        let mut first_error = None;
+
+        for i in 0..self.slots.len() {
+            let exchange = self.exchange(&self.slots[i].exchange)?.clone();
+            let orders: Vec<Order> = self.slots[i].open_orders.drain(..).collect();
+            for order in orders {
+                if let Err(e) = exchange
+                    .cancel_order(&order.id, Some(&order.market_id))
+                    .await
+                {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Cost basis of all positions across exchanges.
+    pub fn total_exposure(&self) -> f64 {
+        self.slots.iter().map(|s| s.exposure()).sum()
+    }
+
+    pub fn log(&self, message: &str) {
+        if self.config.verbose {
+            println!("[portfolio] {message}");
+        }
+    }
+
+    pub fn pause(&mut self) {
+        if self.state == StrategyState::Running {
+            self.state = StrategyState::Paused;
+            let _ = self.event_tx.send(StrategyEvent::Paused);
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.state == StrategyState::Paused {
+            self.state = StrategyState::Running;
+            let _ = self.event_tx.send(StrategyEvent::Resumed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{make_market, MockExchange};
+
+    #[tokio::test]
+    async fn test_portfolio_routes_orders_per_exchange() {
+        // given
+        let polymarket = Arc::new(MockExchange::with_market(make_market("pm-1")));
+        polymarket.markets.lock().unwrap().push(make_market("pm-2"));
+        let kalshi = Arc::new(MockExchange::with_market(make_market("FED")));
+        let mut portfolio = PortfolioStrategyBase::new(StrategyConfig::default())
+            .with_exchange("polymarket", polymarket.clone())
+            .with_exchange("kalshi", kalshi.clone())
+            .with_market("polymarket", "pm-1")
+            .unwrap()
+            .with_market("polymarket", "pm-2")
+            .unwrap()
+            .with_market("kalshi", "FED")
+            .unwrap();
+
+        // when
+        portfolio.load_markets().await.unwrap();
+        portfolio
+            .place_order("polymarket", "pm-2", "Yes", OrderSide::Buy, 0.4, 10.0, None)
+            .await
+            .unwrap();
+        portfolio
+            .place_order("kalshi", "FED", "No", OrderSide::Buy, 0.6, 5.0, None)
+            .await
+            .unwrap();
+        portfolio.refresh_state().await.unwrap();
+
+        // then
+        let counts: Vec<usize> = portfolio
+            .slots()
+            .iter()
+            .map(|s| s.open_orders.len())
+            .collect();
+        assert_eq!(counts, vec![0, 1, 1]);
+        assert_eq!(polymarket.orders.lock().unwrap().len(), 1);
+        assert!(portfolio.add_market("opinion", "x").is_err());
+
+        portfolio.cancel_all_orders().await.unwrap();
+        assert_eq!(kalshi.cancelled.lock().unwrap().len(), 1);
+        assert_eq!(polymarket.cancelled.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+
+use crate::error::DrmError;
+use crate::exchange::{Exchange, FetchOrdersParams};
+
+use super::order_tracker::{OrderEvent, OrderTracker};
+
+#[derive(Debug, Clone)]
+pub struct ReconcilerConfig {
+    pub interval_ms: u64,
+    /// Scopes the open-orders request; tracked orders from other markets are
+    /// looked up one by one.
+    pub market_id: Option<String>,
+    pub verbose: bool,
+}
+
+impl Default for ReconcilerConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 5000,
+            market_id: None,
+            verbose: false,
+        }
+    }
+}
+
+/// Polls the exchange for tracked orders and feeds the snapshots to
+/// [`OrderTracker::handle_order_update`], so fills and cancels are detected
+/// on venues without a user websocket.
+pub struct OrderReconciler<E: Exchange + ?Sized> {
+    exchange: Arc<E>,
+    tracker: Arc<OrderTracker>,
+    config: ReconcilerConfig,
+}
+
+impl<E: Exchange + ?Sized + 'static> OrderReconciler<E> {
+    pub fn new(exchange: Arc<E>, tracker: Arc<OrderTracker>, config: ReconcilerConfig) -> Self {
+        Self {
+            exchange,
+            tracker,
+            config,
+        }
+    }
+
+    pub fn tracker(&self) -> &Arc<OrderTracker> {
+        &self.tracker
+    }
+
+    fn log(&self, message: &str) {
+        if self.config.verbose {
+            println!("[reconciler:{}] {}", self.exchange.id(), message);
+        }
+    }
+
+    /// One reconciliation pass. Open orders are fetched in a single request;
+    /// tracked orders missing from it are fetched individually to learn
+    /// whether they filled, were cancelled or expired.
+    pub async fn reconcile_once(&self) -> Result<Vec<(String, OrderEvent)>, DrmError> {
+        let mut tracked = self.tracker.get_tracked_orders();
+        if tracked.is_empty() {
+            return Ok(Vec::new());
+        }
+        tracked.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+
+        let params = self
+            .config
+            .market_id
+            .clone()
+            .map(|market_id| FetchOrdersParams {
+                market_id: Some(market_id),
+            });
+        let open: HashMap<String, _> = self
+            .exchange
+            .fetch_open_orders(params)
+            .await?
+            .into_iter()
+            .map(|o| (o.id.clone(), o))
+            .collect();
+
+        let mut events = Vec::new();
+        for order in tracked {
+            let snapshot = match open.get(&order.id) {
+                Some(snapshot) => Ok(snapshot.clone()),
+                None => {
+                    self.exchange
+                        .fetch_order(&order.id, Some(&order.market_id))
+                        .await
+                }
+            };
+
+            match snapshot {
+                Ok(snapshot) => {
+                    for event in self.tracker.handle_order_update(&snapshot) {
+                        events.push((order.id.clone(), event));
+                    }
+                }
+                Err(err) => {
+                    if let Some(event) = self.tracker.handle_missing(&order.id) {
+                        events.push((order.id.clone(), event));
+                    } else {
+                        self.log(&format!("fetch_order {} failed: {err}", order.id));
+                    }
+                }
+            }
+        }
+
+        for (order_id, event) in &events {
+            self.log(&format!("{order_id}: {event:?}"));
+        }
+        Ok(events)
+    }
+
+    /// Runs [`reconcile_once`](Self::reconcile_once) every `interval_ms`
+    /// until the returned handle is stopped. Errors are logged and retried
+    /// on the next interval.
+    pub fn spawn(self) -> ReconcilerHandle {
+        let (stop_tx, mut stop_rx) = watch::channel(false);
+        let period = Duration::from_millis(self.config.interval_ms.max(1));
+
+        let task = tokio::spawn(async move {
+            let mut ticker = interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        if let Err(err) = self.reconcile_once().await {
+                            self.log(&format!("reconcile failed: {err}"));
+                        }
+                    }
+                    changed = stop_rx.changed() => {
+                        if changed.is_err() || *stop_rx.borrow() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        ReconcilerHandle { stop_tx, task }
+    }
+}
+
+pub struct ReconcilerHandle {
+    stop_tx: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl ReconcilerHandle {
+    pub async fn stop(self) {
+        self.stop_tx.send_replace(true);
+        let _ = self.task.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Order, OrderSide, OrderStatus};
+    use crate::testing::{make_market, MockExchange};
+    use chrono::Utc;
+    use std::sync::Mutex;
+
+    async fn place(exchange: &MockExchange, size: f64) -> Order {
+        exchange
+            .create_order(
+                "market-1",
+                "Yes",
+                OrderSide::Buy,
+                0.5,
+                size,
+                Default::default(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_emits_fills_cancels_and_expiry() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let tracker = Arc::new(OrderTracker::new(false));
+        let partial = place(&exchange, 10.0).await;
+        let cancelled = place(&exchange, 10.0).await;
+        let expired = place(&exchange, 10.0).await;
+        tracker.track_order(partial.clone());
+        tracker.track_order(cancelled.clone());
+        tracker.track_order_with_expiry(expired.clone(), Some(Utc::now()));
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let seen_clone = seen.clone();
+        tracker.on_fill(move |event, order, fill_size| {
+            seen_clone
+                .lock()
+                .unwrap()
+                .push((order.id.clone(), event, fill_size));
+        });
+
+        {
+            let mut orders = exchange.orders.lock().unwrap();
+            for order in orders.iter_mut() {
+                if order.id == partial.id {
+                    order.filled = 4.0;
+                    order.status = OrderStatus::PartiallyFilled;
+                } else {
+                    order.status = OrderStatus::Cancelled;
+                }
+            }
+        }
+        let reconciler =
+            OrderReconciler::new(exchange.clone(), tracker.clone(), Default::default());
+
+        // when
+        reconciler.reconcile_once().await.unwrap();
+        exchange.orders.lock().unwrap()[0].filled = 10.0;
+        reconciler.reconcile_once().await.unwrap();
+
+        // then
+        let seen = seen.lock().unwrap();
+        assert_eq!(
+            *seen,
+            vec![
+                (partial.id.clone(), OrderEvent::PartialFill, 4.0),
+                (cancelled.id.clone(), OrderEvent::Cancelled, 0.0),
+                (expired.id.clone(), OrderEvent::Expired, 0.0),
+                (partial.id.clone(), OrderEvent::Filled, 6.0),
+            ]
+        );
+        assert_eq!(tracker.tracked_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_keeps_orders_when_lookup_fails() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let tracker = Arc::new(OrderTracker::new(false));
+        let mut unknown = place(&exchange, 10.0).await;
+        unknown.id = "unknown".to_string();
+        tracker.track_order(unknown);
+        let reconciler = OrderReconciler::new(exchange, tracker.clone(), Default::default());
+
+        // when
+        let events = reconciler.reconcile_once().await.unwrap();
+
+        // then
+        assert!(events.is_empty());
+        assert!(tracker.get_tracked("unknown").is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_spawn_reconciles_until_stopped() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let tracker = Arc::new(OrderTracker::new(false));
+        let order = place(&exchange, 10.0).await;
+        tracker.track_order(order.clone());
+        let config = ReconcilerConfig {
+            interval_ms: 100,
+            ..Default::default()
+        };
+        let handle = OrderReconciler::new(exchange.clone(), tracker.clone(), config).spawn();
+
+        // when
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        {
+            let mut orders = exchange.orders.lock().unwrap();
+            orders[0].filled = 10.0;
+            orders[0].status = OrderStatus::Filled;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        handle.stop().await;
+
+        // then
+        assert_eq!(tracker.tracked_count(), 0);
+    }
+}
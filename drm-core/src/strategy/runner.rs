@@ -0,0 +1,587 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep, Duration, Instant, MissedTickBehavior};
+
+use crate::error::DrmError;
+
+use super::traits::{Strategy, StrategyEvent, StrategyState};
+
+/// Builds a fresh strategy instance; called on launch and on every restart.
+pub type StrategyFactory = Arc<dyn Fn() -> Box<dyn Strategy> + Send + Sync>;
+
+#[derive(Debug, Clone)]
+pub struct RunnerConfig {
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Consecutive `on_tick` errors treated as a crash.
+    pub max_consecutive_errors: u32,
+    /// Give up after this many restarts. `None` restarts forever.
+    pub max_restarts: Option<u32>,
+    /// A run lasting this long resets the backoff.
+    pub healthy_after_ms: u64,
+    pub verbose: bool,
+}
+
+impl Default for RunnerConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff_ms: 1000,
+            max_backoff_ms: 60_000,
+            max_consecutive_errors: 5,
+            max_restarts: None,
+            healthy_after_ms: 300_000,
+            verbose: false,
+        }
+    }
+}
+
+/// A [`StrategyEvent`] tagged with the strategy that emitted it.
+#[derive(Debug, Clone)]
+pub struct RunnerEvent {
+    pub strategy: String,
+    pub event: StrategyEvent,
+}
+
+#[derive(Debug, Clone)]
+pub struct StrategyStatus {
+    pub state: StrategyState,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Run,
+    Pause,
+    Stop,
+}
+
+enum Exit {
+    Stopped,
+    Crashed(String),
+}
+
+struct Supervised {
+    control: watch::Sender<Control>,
+    status: Arc<RwLock<StrategyStatus>>,
+    task: JoinHandle<()>,
+}
+
+/// Runs many strategies, each on its own task, and restarts any that crash
+/// with exponential backoff. A crash is a failed `start`, a panic, or
+/// `max_consecutive_errors` failed ticks in a row.
+pub struct StrategyRunner {
+    config: RunnerConfig,
+    strategies: HashMap<String, Supervised>,
+    event_tx: broadcast::Sender<RunnerEvent>,
+}
+
+impl StrategyRunner {
+    pub fn new(config: RunnerConfig) -> Self {
+        let (event_tx, _) = broadcast::channel(1000);
+
+        Self {
+            config,
+            strategies: HashMap::new(),
+            event_tx,
+        }
+    }
+
+    /// Events from every supervised strategy: lifecycle changes and crash
+    /// reports from the runner, plus order and error events the strategies
+    /// emit through [`Strategy::events`].
+    pub fn subscribe(&self) -> broadcast::Receiver<RunnerEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Launches a strategy under `name`. Names must be unique.
+    pub fn spawn<F>(&mut self, name: impl Into<String>, factory: F) -> Result<(), DrmError>
+    where
+        F: Fn() -> Box<dyn Strategy> + Send + Sync + 'static,
+    {
+        let name = name.into();
+        if self.strategies.contains_key(&name) {
+            return Err(DrmError::InvalidInput(format!(
+                "strategy {name} is already running"
+            )));
+        }
+
+        let (control, control_rx) = watch::channel(Control::Run);
+        let status = Arc::new(RwLock::new(StrategyStatus {
+            state: StrategyState::Stopped,
+            restarts: 0,
+            last_error: None,
+        }));
+
+        let task = tokio::spawn(supervise(
+            name.clone(),
+            Arc::new(factory),
+            self.config.clone(),
+            control_rx,
+            status.clone(),
+            self.event_tx.clone(),
+        ));
+
+        self.strategies.insert(
+            name,
+            Supervised {
+                control,
+                status,
+                task,
+            },
+        );
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Result<&Supervised, DrmError> {
+        self.strategies
+            .get(name)
+            .ok_or_else(|| DrmError::InvalidInput(format!("unknown strategy: {name}")))
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.strategies.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    pub fn status(&self, name: &str) -> Option<StrategyStatus> {
+        self.strategies
+            .get(name)
+            .map(|s| s.status.read().unwrap().clone())
+    }
+
+    pub fn pause(&self, name: &str) -> Result<(), DrmError> {
+        self.get(name)?.control.send_replace(Control::Pause);
+        Ok(())
+    }
+
+    pub fn resume(&self, name: &str) -> Result<(), DrmError> {
+        self.get(name)?.control.send_replace(Control::Run);
+        Ok(())
+    }
+
+    /// Stops a strategy (calling its `stop`) and waits for its task.
+    pub async fn stop(&mut self, name: &str) -> Result<(), DrmError> {
+        self.get(name)?;
+        let supervised = self.strategies.remove(name).unwrap();
+        supervised.control.send_replace(Control::Stop);
+        let _ = supervised.task.await;
+        Ok(())
+    }
+
+    pub async fn stop_all(&mut self) {
+        for supervised in self.strategies.values() {
+            supervised.control.send_replace(Control::Stop);
+        }
+        for (_, supervised) in self.strategies.drain() {
+            let _ = supervised.task.await;
+        }
+    }
+}
+
+impl Default for StrategyRunner {
+    fn default() -> Self {
+        Self::new(RunnerConfig::default())
+    }
+}
+
+async fn supervise(
+    name: String,
+    factory: StrategyFactory,
+    config: RunnerConfig,
+    mut control: watch::Receiver<Control>,
+    status: Arc<RwLock<StrategyStatus>>,
+    event_tx: broadcast::Sender<RunnerEvent>,
+) {
+    let emit = |event: StrategyEvent| {
+        let _ = event_tx.send(RunnerEvent {
+            strategy: name.clone(),
+            event,
+        });
+    };
+    let mut backoff_ms = config.initial_backoff_ms;
+
+    loop {
+        if *control.borrow() == Control::Stop {
+            break;
+        }
+
+        let started = Instant::now();
+        let run = tokio::spawn(run_instance(
+            factory(),
+            config.max_consecutive_errors,
+            control.clone(),
+            status.clone(),
+            event_tx.clone(),
+            name.clone(),
+        ));
+
+        let reason = match run.await {
+            Ok(Exit::Stopped) => break,
+            Ok(Exit::Crashed(reason)) => reason,
+            Err(err) if err.is_panic() => "strategy panicked".to_string(),
+            Err(_) => break,
+        };
+
+        if started.elapsed() >= Duration::from_millis(config.healthy_after_ms) {
+            backoff_ms = config.initial_backoff_ms;
+        }
+
+        let restarts = {
+            let mut status = status.write().unwrap();
+            status.state = StrategyState::Stopped;
+            status.last_error = Some(reason.clone());
+            status.restarts
+        };
+        if config.max_restarts.is_some_and(|max| restarts >= max) {
+            emit(StrategyEvent::Error(format!(
+                "crashed: {reason}; giving up after {restarts} restarts"
+            )));
+            break;
+        }
+
+        emit(StrategyEvent::Error(format!(
+            "crashed: {reason}; restarting in {backoff_ms}ms"
+        )));
+        if config.verbose {
+            println!("[runner:{name}] crashed: {reason}; restarting in {backoff_ms}ms");
+        }
+
+        tokio::select! {
+            _ = sleep(Duration::from_millis(backoff_ms)) => {}
+            _ = control.wait_for(|c| *c == Control::Stop) => break,
+        }
+        backoff_ms = (backoff_ms * 2).min(config.max_backoff_ms);
+        status.write().unwrap().restarts += 1;
+    }
+
+    status.write().unwrap().state = StrategyState::Stopped;
+}
+
+async fn run_instance(
+    mut strategy: Box<dyn Strategy>,
+    max_consecutive_errors: u32,
+    mut control: watch::Receiver<Control>,
+    status: Arc<RwLock<StrategyStatus>>,
+    event_tx: broadcast::Sender<RunnerEvent>,
+    name: String,
+) -> Exit {
+    let emit = |event: StrategyEvent| {
+        let _ = event_tx.send(RunnerEvent {
+            strategy: name.clone(),
+            event,
+        });
+    };
+    let set_state = |state: StrategyState| status.write().unwrap().state = state;
+
+    let mut strategy_events = strategy.events();
+    if let Err(err) = strategy.start().await {
+        return Exit::Crashed(format!("start failed: {err}"));
+    }
+    set_state(StrategyState::Running);
+    emit(StrategyEvent::Started);
+
+    let mut ticker = interval(Duration::from_millis(
+        strategy.config().tick_interval_ms.max(1),
+    ));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut errors = 0;
+    let mut paused = false;
+
+    // Honour a pause requested while the previous instance was restarting.
+    control.mark_changed();
+
+    loop {
+        tokio::select! {
+            Some(event) = next_event(&mut strategy_events) => {
+                if forwarded(&event) {
+                    emit(event);
+                }
+            }
+            _ = ticker.tick(), if !paused => {
+                match strategy.on_tick().await {
+                    Ok(()) => {
+                        errors = 0;
+                        emit(StrategyEvent::Tick);
+                    }
+                    Err(err) => {
+                        errors += 1;
+                        status.write().unwrap().last_error = Some(err.to_string());
+                        emit(StrategyEvent::Error(err.to_string()));
+                        if errors >= max_consecutive_errors.max(1) {
+                            let _ = strategy.stop().await;
+                            drain_events(&mut strategy_events, emit);
+                            return Exit::Crashed(format!(
+                                "{errors} consecutive tick errors, last: {err}"
+                            ));
+                        }
+                    }
+                }
+            }
+            changed = control.changed() => {
+                let requested = if changed.is_err() {
+                    Control::Stop
+                } else {
+                    *control.borrow_and_update()
+                };
+                match requested {
+                    Control::Pause if !paused => {
+                        paused = true;
+                        strategy.pause();
+                        set_state(StrategyState::Paused);
+                        emit(StrategyEvent::Paused);
+                    }
+                    Control::Run if paused => {
+                        paused = false;
+                        strategy.resume();
+                        set_state(StrategyState::Running);
+                        emit(StrategyEvent::Resumed);
+                    }
+                    Control::Stop => {
+                        let _ = strategy.stop().await;
+                        drain_events(&mut strategy_events, emit);
+                        set_state(StrategyState::Stopped);
+                        emit(StrategyEvent::Stopped);
+                        return Exit::Stopped;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// The runner reports lifecycle and ticks itself; only the strategy's own
+/// order and error events are forwarded.
+fn forwarded(event: &StrategyEvent) -> bool {
+    matches!(event, StrategyEvent::Order(_) | StrategyEvent::Error(_))
+}
+
+/// Next event from a strategy's receiver, or pending forever once it has
+/// none. Lagged events are skipped.
+async fn next_event(
+    events: &mut Option<broadcast::Receiver<StrategyEvent>>,
+) -> Option<StrategyEvent> {
+    let Some(receiver) = events.as_mut() else {
+        return std::future::pending().await;
+    };
+    loop {
+        match receiver.recv().await {
+            Ok(event) => return Some(event),
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => {
+                *events = None;
+                return None;
+            }
+        }
+    }
+}
+
+/// Forwards events still queued when an instance exits.
+fn drain_events(
+    events: &mut Option<broadcast::Receiver<StrategyEvent>>,
+    emit: impl Fn(StrategyEvent),
+) {
+    let Some(receiver) = events.as_mut() else {
+        return;
+    };
+    while let Ok(event) = receiver.try_recv() {
+        if forwarded(&event) {
+            emit(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{MarketMaker, MarketMakingConfig, StrategyConfig};
+    use crate::testing::{make_market, MockExchange};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Flaky {
+        config: StrategyConfig,
+        state: StrategyState,
+        ticks: Arc<AtomicUsize>,
+        fail_start: bool,
+    }
+
+    #[async_trait]
+    impl Strategy for Flaky {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn config(&self) -> &StrategyConfig {
+            &self.config
+        }
+
+        fn state(&self) -> StrategyState {
+            self.state
+        }
+
+        async fn on_tick(&mut self) -> Result<(), DrmError> {
+            self.ticks.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn start(&mut self) -> Result<(), DrmError> {
+            if self.fail_start {
+                return Err(DrmError::Other("venue down".into()));
+            }
+            self.state = StrategyState::Running;
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<(), DrmError> {
+            self.state = StrategyState::Stopped;
+            Ok(())
+        }
+
+        fn pause(&mut self) {
+            self.state = StrategyState::Paused;
+        }
+
+        fn resume(&mut self) {
+            self.state = StrategyState::Running;
+        }
+    }
+
+    fn factory(
+        ticks: Arc<AtomicUsize>,
+        failures: usize,
+    ) -> impl Fn() -> Box<dyn Strategy> + Send + Sync {
+        let launches = AtomicUsize::new(0);
+        move || {
+            let launch = launches.fetch_add(1, Ordering::SeqCst);
+            Box::new(Flaky {
+                config: StrategyConfig {
+                    tick_interval_ms: 100,
+                    ..Default::default()
+                },
+                state: StrategyState::Stopped,
+                ticks: ticks.clone(),
+                fail_start: launch < failures,
+            })
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_runner_restarts_crashed_strategy_with_backoff() {
+        // given
+        let mut runner = StrategyRunner::default();
+        let mut events = runner.subscribe();
+        let ticks = Arc::new(AtomicUsize::new(0));
+
+        // when
+        runner.spawn("quoter", factory(ticks.clone(), 2)).unwrap();
+        sleep(Duration::from_millis(2500)).await;
+
+        // then
+        let status = runner.status("quoter").unwrap();
+        assert_eq!(status.restarts, 1);
+        assert_eq!(status.state, StrategyState::Stopped);
+        assert_eq!(ticks.load(Ordering::SeqCst), 0);
+
+        sleep(Duration::from_millis(1000)).await;
+        let status = runner.status("quoter").unwrap();
+        assert_eq!(status.restarts, 2);
+        assert_eq!(status.state, StrategyState::Running);
+        assert!(ticks.load(Ordering::SeqCst) > 0);
+
+        let mut crashes = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let StrategyEvent::Error(message) = event.event {
+                crashes.push(message);
+            }
+        }
+        assert_eq!(crashes.len(), 2);
+        assert!(crashes[1].contains("restarting in 2000ms"));
+        runner.stop_all().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_runner_forwards_strategy_order_events() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let mut runner = StrategyRunner::default();
+        let mut events = runner.subscribe();
+        let maker_exchange = exchange.clone();
+
+        // when
+        runner
+            .spawn("maker", move || {
+                Box::new(MarketMaker::new(
+                    maker_exchange.clone(),
+                    "market-1".to_string(),
+                    StrategyConfig {
+                        tick_interval_ms: 100,
+                        spread_bps: 400,
+                        ..Default::default()
+                    },
+                    MarketMakingConfig::default(),
+                ))
+            })
+            .unwrap();
+        sleep(Duration::from_millis(50)).await;
+        runner.stop_all().await;
+
+        // then
+        let mut orders = Vec::new();
+        let mut started = 0;
+        while let Ok(event) = events.try_recv() {
+            assert_eq!(event.strategy, "maker");
+            match event.event {
+                StrategyEvent::Order(order) => orders.push(order.id),
+                StrategyEvent::Started => started += 1,
+                _ => {}
+            }
+        }
+        let placed: Vec<String> = exchange
+            .orders
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|o| o.id.clone())
+            .collect();
+        assert!(!placed.is_empty());
+        assert_eq!(orders, placed);
+        assert_eq!(started, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_runner_pauses_resumes_and_stops_by_name() {
+        // given
+        let mut runner = StrategyRunner::default();
+        let a_ticks = Arc::new(AtomicUsize::new(0));
+        let b_ticks = Arc::new(AtomicUsize::new(0));
+        runner.spawn("a", factory(a_ticks.clone(), 0)).unwrap();
+        runner.spawn("b", factory(b_ticks.clone(), 0)).unwrap();
+        sleep(Duration::from_millis(250)).await;
+
+        // when
+        runner.pause("a").unwrap();
+        sleep(Duration::from_millis(50)).await;
+        let paused_at = a_ticks.load(Ordering::SeqCst);
+        sleep(Duration::from_millis(500)).await;
+
+        // then
+        assert_eq!(a_ticks.load(Ordering::SeqCst), paused_at);
+        assert_eq!(runner.status("a").unwrap().state, StrategyState::Paused);
+        assert!(b_ticks.load(Ordering::SeqCst) > paused_at);
+
+        runner.resume("a").unwrap();
+        sleep(Duration::from_millis(250)).await;
+        assert!(a_ticks.load(Ordering::SeqCst) > paused_at);
+
+        runner.stop("b").await.unwrap();
+        assert_eq!(runner.names(), vec!["a".to_string()]);
+        assert!(runner.pause("b").is_err());
+        runner.stop_all().await;
+    }
+}
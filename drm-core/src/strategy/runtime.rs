@@ -0,0 +1,555 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, Interval, MissedTickBehavior};
+
+use crate::error::DrmError;
+use crate::exchange::{Exchange, FetchOrdersParams};
+use crate::models::{Order, Orderbook, OrderbookManager, Position};
+
+use super::order_tracker::{OrderEvent, OrderTracker};
+use super::traits::StrategyEvent;
+
+/// Strategy driven by market and account events instead of a fixed tick.
+///
+/// Every hook has a no-op default, so implementors only override what they
+/// react to.
+#[async_trait]
+pub trait EventDrivenStrategy: Send {
+    fn name(&self) -> &str;
+
+    async fn on_start(&mut self) -> Result<(), DrmError> {
+        Ok(())
+    }
+
+    async fn on_stop(&mut self) -> Result<(), DrmError> {
+        Ok(())
+    }
+
+    /// A watched token's book changed.
+    async fn on_orderbook(
+        &mut self,
+        token_id: &str,
+        orderbook: Arc<Orderbook>,
+    ) -> Result<(), DrmError> {
+        let _ = (token_id, orderbook);
+        Ok(())
+    }
+
+    /// One of our orders traded. `fill_size` is the size of this fill only.
+    async fn on_fill(
+        &mut self,
+        event: OrderEvent,
+        order: &Order,
+        fill_size: f64,
+    ) -> Result<(), DrmError> {
+        let _ = (event, order, fill_size);
+        Ok(())
+    }
+
+    /// An order changed state without trading (created, cancelled, rejected,
+    /// expired, or observed during reconciliation).
+    async fn on_order_update(&mut self, event: OrderEvent, order: &Order) -> Result<(), DrmError> {
+        let _ = (event, order);
+        Ok(())
+    }
+
+    async fn on_timer(&mut self) -> Result<(), DrmError> {
+        Ok(())
+    }
+
+    /// Periodic REST snapshot of positions and open orders. Use it to repair
+    /// local state that missed an event, not to drive quoting.
+    async fn on_reconcile(
+        &mut self,
+        positions: &[Position],
+        open_orders: &[Order],
+    ) -> Result<(), DrmError> {
+        let _ = (positions, open_orders);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum StrategyInput {
+    Orderbook {
+        token_id: String,
+        orderbook: Arc<Orderbook>,
+    },
+    Fill {
+        event: OrderEvent,
+        order: Order,
+        fill_size: f64,
+    },
+    OrderUpdate {
+        event: OrderEvent,
+        order: Order,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct RuntimeConfig {
+    /// Market used to scope reconciliation requests.
+    pub market_id: Option<String>,
+    pub timer_interval_ms: Option<u64>,
+    pub reconcile_interval_ms: Option<u64>,
+    pub verbose: bool,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            market_id: None,
+            timer_interval_ms: Some(1000),
+            reconcile_interval_ms: Some(30_000),
+            verbose: false,
+        }
+    }
+}
+
+/// Cloneable handle for feeding a running [`StrategyRuntime`] and stopping it.
+#[derive(Clone)]
+pub struct RuntimeHandle {
+    input_tx: mpsc::UnboundedSender<StrategyInput>,
+    stop_tx: Arc<watch::Sender<bool>>,
+}
+
+impl RuntimeHandle {
+    pub fn send(&self, input: StrategyInput) -> Result<(), DrmError> {
+        self.input_tx
+            .send(input)
+            .map_err(|_| DrmError::Other("strategy runtime is not running".into()))
+    }
+
+    pub fn push_fill(
+        &self,
+        event: OrderEvent,
+        order: Order,
+        fill_size: f64,
+    ) -> Result<(), DrmError> {
+        self.send(StrategyInput::Fill {
+            event,
+            order,
+            fill_size,
+        })
+    }
+
+    pub fn push_order_update(&self, event: OrderEvent, order: Order) -> Result<(), DrmError> {
+        self.send(StrategyInput::OrderUpdate { event, order })
+    }
+
+    pub fn stop(&self) {
+        self.stop_tx.send_replace(true);
+    }
+}
+
+/// Feeds an [`EventDrivenStrategy`] from orderbook watch channels, fill and
+/// order events, a timer, and a slow REST reconciliation loop.
+pub struct StrategyRuntime<E: Exchange + 'static> {
+    exchange: Arc<E>,
+    orderbooks: OrderbookManager,
+    token_ids: Vec<String>,
+    config: RuntimeConfig,
+    input_tx: mpsc::UnboundedSender<StrategyInput>,
+    input_rx: mpsc::UnboundedReceiver<StrategyInput>,
+    stop_tx: Arc<watch::Sender<bool>>,
+    pub event_tx: broadcast::Sender<StrategyEvent>,
+}
+
+impl<E: Exchange + 'static> StrategyRuntime<E> {
+    pub fn new(exchange: Arc<E>, orderbooks: OrderbookManager, config: RuntimeConfig) -> Self {
+        let (input_tx, input_rx) = mpsc::unbounded_channel();
+        let (stop_tx, _) = watch::channel(false);
+        let (event_tx, _) = broadcast::channel(100);
+
+        Self {
+            exchange,
+            orderbooks,
+            token_ids: Vec::new(),
+            config,
+            input_tx,
+            input_rx,
+            stop_tx: Arc::new(stop_tx),
+            event_tx,
+        }
+    }
+
+    /// Delivers updates of `token_id` from the orderbook manager to
+    /// `on_orderbook`.
+    pub fn watch_token(&mut self, token_id: impl Into<String>) -> &mut Self {
+        let token_id = token_id.into();
+        if !self.token_ids.contains(&token_id) {
+            self.token_ids.push(token_id);
+        }
+        self
+    }
+
+    pub fn handle(&self) -> RuntimeHandle {
+        RuntimeHandle {
+            input_tx: self.input_tx.clone(),
+            stop_tx: self.stop_tx.clone(),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<StrategyEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Forwards every event of `tracker` into the runtime: fills go to
+    /// `on_fill`, everything else to `on_order_update`.
+    pub fn attach_order_tracker(&self, tracker: &OrderTracker) {
+        let tx = self.input_tx.clone();
+        tracker.on_fill(move |event, order, fill_size| {
+            let input = match event {
+                OrderEvent::PartialFill | OrderEvent::Filled => StrategyInput::Fill {
+                    event,
+                    order: order.clone(),
+                    fill_size,
+                },
+                _ => StrategyInput::OrderUpdate {
+                    event,
+                    order: order.clone(),
+                },
+            };
+            let _ = tx.send(input);
+        });
+    }
+
+    fn log(&self, name: &str, message: &str) {
+        if self.config.verbose {
+            println!("[{}:{}] {}", self.exchange.id(), name, message);
+        }
+    }
+
+    fn spawn_orderbook_forwarders(&self) -> Vec<JoinHandle<()>> {
+        self.token_ids
+            .iter()
+            .map(|token_id| {
+                let mut rx = self.orderbooks.watch(token_id);
+                let tx = self.input_tx.clone();
+                let token_id = token_id.clone();
+
+                tokio::spawn(async move {
+                    // Deliver a book that was already present before we started.
+                    rx.mark_changed();
+                    while rx.changed().await.is_ok() {
+                        let orderbook = rx.borrow_and_update().clone();
+                        if let Some(orderbook) = orderbook {
+                            let input = StrategyInput::Orderbook {
+                                token_id: token_id.clone(),
+                                orderbook,
+                            };
+                            if tx.send(input).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                })
+            })
+            .collect()
+    }
+
+    async fn dispatch<S: EventDrivenStrategy>(
+        strategy: &mut S,
+        input: StrategyInput,
+    ) -> Result<(), DrmError> {
+        match input {
+            StrategyInput::Orderbook {
+                token_id,
+                orderbook,
+            } => strategy.on_orderbook(&token_id, orderbook).await,
+            StrategyInput::Fill {
+                event,
+                order,
+                fill_size,
+            } => strategy.on_fill(event, &order, fill_size).await,
+            StrategyInput::OrderUpdate { event, order } => {
+                strategy.on_order_update(event, &order).await
+            }
+        }
+    }
+
+    async fn reconcile<S: EventDrivenStrategy>(&self, strategy: &mut S) -> Result<(), DrmError> {
+        let market_id = self.config.market_id.as_deref();
+        let params = FetchOrdersParams {
+            market_id: market_id.map(String::from),
+        };
+
+        let (positions, orders) = tokio::try_join!(
+            self.exchange.fetch_positions(market_id),
+            self.exchange.fetch_open_orders(Some(params)),
+        )?;
+
+        let orders: Vec<Order> = match market_id {
+            Some(id) => orders.into_iter().filter(|o| o.market_id == id).collect(),
+            None => orders,
+        };
+
+        strategy.on_reconcile(&positions, &orders).await
+    }
+
+    fn report(&self, name: &str, result: Result<(), DrmError>) {
+        if let Err(e) = result {
+            self.log(name, &format!("Handler error: {e}"));
+            let _ = self.event_tx.send(StrategyEvent::Error(e.to_string()));
+        }
+    }
+
+    /// Runs until [`RuntimeHandle::stop`] is called.
+    pub async fn run<S: EventDrivenStrategy>(&mut self, strategy: &mut S) -> Result<(), DrmError> {
+        let name = strategy.name().to_string();
+        self.stop_tx.send_replace(false);
+        let mut stop_rx = self.stop_tx.subscribe();
+
+        strategy.on_start().await?;
+        let forwarders = self.spawn_orderbook_forwarders();
+        let _ = self.event_tx.send(StrategyEvent::Started);
+        self.log(&name, "Strategy started");
+
+        let mut timer = self.config.timer_interval_ms.map(make_interval);
+        let mut reconcile = self.config.reconcile_interval_ms.map(make_interval);
+
+        loop {
+            tokio::select! {
+                biased;
+
+                _ = stop_rx.changed() => {
+                    if *stop_rx.borrow() {
+                        break;
+                    }
+                }
+                Some(input) = self.input_rx.recv() => {
+                    let result = Self::dispatch(strategy, input).await;
+                    self.report(&name, result);
+                }
+                _ = next_tick(&mut timer) => {
+                    let result = strategy.on_timer().await;
+                    if result.is_ok() {
+                        let _ = self.event_tx.send(StrategyEvent::Tick);
+                    }
+                    self.report(&name, result);
+                }
+                _ = next_tick(&mut reconcile) => {
+                    let result = self.reconcile(strategy).await;
+                    self.report(&name, result);
+                }
+            }
+        }
+
+        for handle in forwarders {
+            handle.abort();
+        }
+
+        let result = strategy.on_stop().await;
+        self.report(&name, result);
+        let _ = self.event_tx.send(StrategyEvent::Stopped);
+        self.log(&name, "Strategy stopped");
+
+        Ok(())
+    }
+}
+
+fn make_interval(ms: u64) -> Interval {
+    let mut interval = interval(Duration::from_millis(ms.max(1)));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval
+}
+
+async fn next_tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(i) => {
+            i.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{OrderSide, OrderStatus, PriceLevel};
+    use crate::strategy::StrategyConfig;
+    use crate::testing::{make_market, MockExchange};
+    use chrono::Utc;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder {
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl EventDrivenStrategy for Recorder {
+        fn name(&self) -> &str {
+            "recorder"
+        }
+
+        async fn on_orderbook(
+            &mut self,
+            token_id: &str,
+            orderbook: Arc<Orderbook>,
+        ) -> Result<(), DrmError> {
+            self.calls.lock().unwrap().push(format!(
+                "book:{token_id}:{}",
+                orderbook.best_bid().unwrap_or(0.0)
+            ));
+            Ok(())
+        }
+
+        async fn on_fill(
+            &mut self,
+            event: OrderEvent,
+            order: &Order,
+            fill_size: f64,
+        ) -> Result<(), DrmError> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("fill:{event:?}:{}:{fill_size}", order.id));
+            Ok(())
+        }
+
+        async fn on_order_update(
+            &mut self,
+            event: OrderEvent,
+            order: &Order,
+        ) -> Result<(), DrmError> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("update:{event:?}:{}", order.id));
+            Ok(())
+        }
+
+        async fn on_reconcile(
+            &mut self,
+            _positions: &[Position],
+            open_orders: &[Order],
+        ) -> Result<(), DrmError> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("reconcile:{}", open_orders.len()));
+            Ok(())
+        }
+    }
+
+    fn make_book(bid: f64) -> Orderbook {
+        Orderbook {
+            market_id: "market-1".to_string(),
+            asset_id: "yes-token".to_string(),
+            bids: vec![PriceLevel::new(bid, 10.0)],
+            asks: vec![PriceLevel::new(bid + 0.02, 10.0)],
+            last_update_id: None,
+            timestamp: None,
+        }
+    }
+
+    fn make_order(id: &str) -> Order {
+        Order {
+            id: id.to_string(),
+            market_id: "market-1".to_string(),
+            outcome: "Yes".to_string(),
+            side: OrderSide::Buy,
+            price: 0.50,
+            size: 10.0,
+            filled: 0.0,
+            status: OrderStatus::Open,
+            created_at: Utc::now(),
+            updated_at: None,
+        }
+    }
+
+    async fn wait_for_calls(calls: &Arc<Mutex<Vec<String>>>, count: usize) {
+        for _ in 0..200 {
+            if calls.lock().unwrap().len() >= count {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_runtime_dispatches_events() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let manager = OrderbookManager::new();
+        let strategy_config = StrategyConfig::default();
+        let tracker = OrderTracker::new(false).with_default_ttl(strategy_config.order_ttl_ms);
+        let config = RuntimeConfig {
+            market_id: Some("market-1".to_string()),
+            timer_interval_ms: None,
+            reconcile_interval_ms: None,
+            verbose: false,
+        };
+        let mut runtime = StrategyRuntime::new(exchange, manager.clone(), config);
+        runtime.watch_token("yes-token");
+        runtime.attach_order_tracker(&tracker);
+        let handle = runtime.handle();
+
+        let mut strategy = Recorder::default();
+        let calls = strategy.calls.clone();
+        let task = tokio::spawn(async move { runtime.run(&mut strategy).await });
+
+        // when
+        manager.update("yes-token", make_book(0.40));
+        wait_for_calls(&calls, 1).await;
+        tracker.track_order(make_order("order-1"));
+        tracker.handle_trade("order-1", 4.0, 0.50, None, None);
+        tracker.handle_cancel("order-1");
+        wait_for_calls(&calls, 3).await;
+        handle.stop();
+        task.await.unwrap().unwrap();
+
+        // then
+        let calls = calls.lock().unwrap().clone();
+        assert_eq!(
+            calls,
+            vec![
+                "book:yes-token:0.4",
+                "fill:PartialFill:order-1:4",
+                "update:Cancelled:order-1",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_runtime_reconciles_on_interval() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        exchange
+            .create_order(
+                "market-1",
+                "Yes",
+                OrderSide::Buy,
+                0.5,
+                1.0,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        let config = RuntimeConfig {
+            market_id: Some("market-1".to_string()),
+            timer_interval_ms: None,
+            reconcile_interval_ms: Some(10),
+            verbose: false,
+        };
+        let mut runtime = StrategyRuntime::new(exchange, OrderbookManager::new(), config);
+        let handle = runtime.handle();
+
+        let mut strategy = Recorder::default();
+        let calls = strategy.calls.clone();
+        let task = tokio::spawn(async move { runtime.run(&mut strategy).await });
+
+        // when
+        wait_for_calls(&calls, 2).await;
+        handle.stop();
+        task.await.unwrap().unwrap();
+
+        // then
+        let calls = calls.lock().unwrap().clone();
+        assert!(calls.len() >= 2);
+        assert!(calls.iter().all(|c| c == "reconcile:1"));
+    }
+}
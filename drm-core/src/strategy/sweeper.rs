@@ -0,0 +1,229 @@
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+
+use crate::exchange::Exchange;
+
+use super::order_tracker::{OrderEvent, OrderTracker};
+
+#[derive(Debug, Clone)]
+pub struct SweeperConfig {
+    pub interval_ms: u64,
+    pub verbose: bool,
+}
+
+impl Default for SweeperConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 1000,
+            verbose: false,
+        }
+    }
+}
+
+/// Cancels tracked orders that outlive their TTL and reports them as
+/// `Expired` through the tracker's callbacks.
+pub struct OrderSweeper<E: Exchange + ?Sized> {
+    exchange: Arc<E>,
+    tracker: Arc<OrderTracker>,
+    config: SweeperConfig,
+}
+
+impl<E: Exchange + ?Sized + 'static> OrderSweeper<E> {
+    pub fn new(exchange: Arc<E>, tracker: Arc<OrderTracker>, config: SweeperConfig) -> Self {
+        Self {
+            exchange,
+            tracker,
+            config,
+        }
+    }
+
+    fn log(&self, message: &str) {
+        if self.config.verbose {
+            println!("[sweeper:{}] {}", self.exchange.id(), message);
+        }
+    }
+
+    /// Cancels every stale order once. Orders whose cancel fails stay
+    /// tracked and are retried on the next sweep.
+    pub async fn sweep_once(&self) -> Vec<(String, OrderEvent)> {
+        let mut events = Vec::new();
+
+        for stale in self.tracker.stale_orders(Utc::now()) {
+            let order_id = stale.order.id.clone();
+            match self
+                .exchange
+                .cancel_order(&order_id, Some(&stale.order.market_id))
+                .await
+            {
+                Ok(cancelled) => {
+                    for event in self.tracker.handle_expired(&order_id, Some(&cancelled)) {
+                        events.push((order_id.clone(), event));
+                    }
+                    self.log(&format!("expired {order_id}"));
+                }
+                Err(err) => self.log(&format!("cancel {order_id} failed: {err}")),
+            }
+        }
+
+        events
+    }
+
+    /// Runs [`sweep_once`](Self::sweep_once) every `interval_ms` until the
+    /// returned handle is stopped.
+    pub fn spawn(self) -> SweeperHandle {
+        let (stop_tx, mut stop_rx) = watch::channel(false);
+        let period = Duration::from_millis(self.config.interval_ms.max(1));
+
+        let task = tokio::spawn(async move {
+            let mut ticker = interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {
+                        self.sweep_once().await;
+                    }
+                    changed = stop_rx.changed() => {
+                        if changed.is_err() || *stop_rx.borrow() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        SweeperHandle { stop_tx, task }
+    }
+}
+
+pub struct SweeperHandle {
+    stop_tx: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+impl SweeperHandle {
+    pub async fn stop(self) {
+        self.stop_tx.send_replace(true);
+        let _ = self.task.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OrderSide;
+    use crate::strategy::StrategyConfig;
+    use crate::testing::{make_market, MockExchange};
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_sweeper_cancels_orders_past_ttl() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let config = StrategyConfig {
+            order_ttl_ms: Some(60_000),
+            ..Default::default()
+        };
+        let tracker = Arc::new(OrderTracker::new(false).with_default_ttl(config.order_ttl_ms));
+        let mut orders = Vec::new();
+        for _ in 0..2 {
+            orders.push(
+                exchange
+                    .create_order("market-1", "Yes", OrderSide::Buy, 0.5, 10.0, HashMap::new())
+                    .await
+                    .unwrap(),
+            );
+        }
+        tracker.track_order(orders[0].clone());
+        tracker.track_order_with_ttl(orders[1].clone(), Some(0));
+
+        let expired = Arc::new(Mutex::new(Vec::new()));
+        let expired_clone = expired.clone();
+        tracker.on_fill(move |event, order, _| {
+            if event == OrderEvent::Expired {
+                expired_clone.lock().unwrap().push(order.id.clone());
+            }
+        });
+        let sweeper = OrderSweeper::new(exchange.clone(), tracker.clone(), Default::default());
+
+        // when
+        let events = sweeper.sweep_once().await;
+
+        // then
+        assert_eq!(events, vec![(orders[1].id.clone(), OrderEvent::Expired)]);
+        assert_eq!(*expired.lock().unwrap(), vec![orders[1].id.clone()]);
+        assert_eq!(
+            *exchange.cancelled.lock().unwrap(),
+            vec![orders[1].id.clone()]
+        );
+        assert_eq!(tracker.tracked_count(), 1);
+        assert!(tracker
+            .get_tracked(&orders[0].id)
+            .unwrap()
+            .is_stale(Utc::now() + chrono::Duration::minutes(1)));
+    }
+
+    #[tokio::test]
+    async fn test_sweeper_retries_failed_cancels() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let tracker = Arc::new(OrderTracker::new(false));
+        let mut unknown = exchange
+            .create_order("market-1", "Yes", OrderSide::Buy, 0.5, 10.0, HashMap::new())
+            .await
+            .unwrap();
+        unknown.id = "unknown".to_string();
+        tracker.track_order_with_ttl(unknown, Some(0));
+        let sweeper = OrderSweeper::new(exchange.clone(), tracker.clone(), Default::default());
+
+        // when
+        let first = sweeper.sweep_once().await;
+        let second = sweeper.sweep_once().await;
+
+        // then
+        assert!(first.is_empty() && second.is_empty());
+        assert_eq!(tracker.tracked_count(), 1);
+        assert_eq!(exchange.cancelled.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_sweeper_reports_fills_before_expiry() {
+        // given
+        let exchange = Arc::new(MockExchange::with_market(make_market("market-1")));
+        let tracker = Arc::new(OrderTracker::new(false));
+        let order = exchange
+            .create_order("market-1", "Yes", OrderSide::Buy, 0.5, 10.0, HashMap::new())
+            .await
+            .unwrap();
+        tracker.track_order_with_ttl(order.clone(), Some(0));
+        exchange.orders.lock().unwrap()[0].filled = 4.0;
+
+        let fills = Arc::new(Mutex::new(Vec::new()));
+        let fills_clone = fills.clone();
+        tracker.on_fill(move |event, _, fill_size| {
+            fills_clone.lock().unwrap().push((event, fill_size));
+        });
+        let sweeper = OrderSweeper::new(exchange, tracker.clone(), Default::default());
+
+        // when
+        let events = sweeper.sweep_once().await;
+
+        // then
+        assert_eq!(
+            events,
+            vec![
+                (order.id.clone(), OrderEvent::PartialFill),
+                (order.id.clone(), OrderEvent::Expired),
+            ]
+        );
+        assert_eq!(
+            *fills.lock().unwrap(),
+            vec![(OrderEvent::PartialFill, 4.0), (OrderEvent::Expired, 0.0)]
+        );
+        assert_eq!(tracker.tracked_count(), 0);
+    }
+}
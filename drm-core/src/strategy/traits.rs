@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{broadcast, Mutex};
@@ -8,7 +9,11 @@ use crate::error::DrmError;
 use crate::exchange::Exchange;
 use crate::models::{Market, Order, OrderSide, Position};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use super::order_tracker::OrderTracker;
+use super::persistence::StrategySnapshot;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum StrategyState {
     Stopped,
     Running,
@@ -31,6 +36,8 @@ pub struct StrategyConfig {
     pub tick_interval_ms: u64,
     pub max_position_size: f64,
     pub spread_bps: u32,
+    /// Default time-to-live for this strategy's tracked orders.
+    pub order_ttl_ms: Option<u64>,
     pub verbose: bool,
 }
 
@@ -40,6 +47,7 @@ impl Default for StrategyConfig {
             tick_interval_ms: 1000,
             max_position_size: 100.0,
             spread_bps: 100,
+            order_ttl_ms: None,
             verbose: false,
         }
     }
@@ -84,6 +92,12 @@ pub trait Strategy: Send + Sync {
     async fn stop(&mut self) -> Result<(), DrmError>;
     fn pause(&mut self);
     fn resume(&mut self);
+
+    /// Events the strategy emits itself, such as placed orders. A
+    /// `StrategyRunner` forwards them to its subscribers.
+    fn events(&self) -> Option<broadcast::Receiver<StrategyEvent>> {
+        None
+    }
 }
 
 pub struct BaseStrategy<E: Exchange + 'static> {
@@ -95,6 +109,7 @@ pub struct BaseStrategy<E: Exchange + 'static> {
     pub positions: Vec<Position>,
     pub open_orders: Vec<Order>,
     pub event_tx: broadcast::Sender<StrategyEvent>,
+    order_tracker: Arc<OrderTracker>,
     tick_handle: Option<tokio::task::JoinHandle<()>>,
     stop_signal: Arc<Mutex<bool>>,
 }
@@ -102,6 +117,8 @@ pub struct BaseStrategy<E: Exchange + 'static> {
 impl<E: Exchange + 'static> BaseStrategy<E> {
     pub fn new(exchange: Arc<E>, market_id: String, config: StrategyConfig) -> Self {
         let (event_tx, _) = broadcast::channel(100);
+        let order_tracker =
+            Arc::new(OrderTracker::new(config.verbose).with_default_ttl(config.order_ttl_ms));
 
         Self {
             exchange,
@@ -112,6 +129,7 @@ impl<E: Exchange + 'static> BaseStrategy<E> {
             positions: Vec::new(),
             open_orders: Vec::new(),
             event_tx,
+            order_tracker,
             tick_handle: None,
             stop_signal: Arc::new(Mutex::new(false)),
         }
@@ -121,6 +139,13 @@ impl<E: Exchange + 'static> BaseStrategy<E> {
         self.event_tx.subscribe()
     }
 
+    /// Tracks every order placed through [`place_order`](Self::place_order)
+    /// with `config.order_ttl_ms` as its TTL. Hand it to an `OrderSweeper`,
+    /// `OrderReconciler` or `StrategyRuntime` to act on it.
+    pub fn order_tracker(&self) -> &Arc<OrderTracker> {
+        &self.order_tracker
+    }
+
     pub async fn refresh_state(&mut self) -> Result<(), DrmError> {
         let (positions, orders) = tokio::try_join!(
             self.exchange.fetch_positions(Some(&self.market_id)),
@@ -136,12 +161,99 @@ impl<E: Exchange + 'static> BaseStrategy<E> {
         Ok(())
     }
 
+    pub fn snapshot(&self) -> StrategySnapshot {
+        StrategySnapshot {
+            market_id: self.market_id.clone(),
+            state: self.state,
+            positions: self.positions.clone(),
+            open_orders: self.open_orders.clone(),
+            extra: serde_json::Value::Null,
+        }
+    }
+
+    /// Loads a snapshot taken before a restart, then refreshes from the
+    /// exchange so `open_orders` matches what is actually resting. If the
+    /// refresh fails the snapshot values stay in place and the error is
+    /// returned.
+    pub async fn restore(&mut self, snapshot: &StrategySnapshot) -> Result<(), DrmError> {
+        if snapshot.market_id != self.market_id {
+            return Err(DrmError::InvalidInput(format!(
+                "snapshot is for market {}, strategy trades {}",
+                snapshot.market_id, self.market_id
+            )));
+        }
+
+        self.positions = snapshot.positions.clone();
+        self.open_orders = snapshot.open_orders.clone();
+        self.refresh_state().await
+    }
+
+    /// Applies an order event to `open_orders` without a REST round trip.
+    pub fn apply_order_update(&mut self, order: &Order) {
+        let existing = self.open_orders.iter().position(|o| o.id == order.id);
+
+        match (existing, order.is_active()) {
+            (Some(idx), true) => self.open_orders[idx] = order.clone(),
+            (Some(idx), false) => {
+                self.open_orders.remove(idx);
+            }
+            (None, true) if order.market_id == self.market_id => {
+                self.open_orders.push(order.clone())
+            }
+            (None, _) => {}
+        }
+    }
+
+    /// Applies a fill of `fill_size` at `order.price` to `positions`.
+    pub fn apply_fill(&mut self, order: &Order, fill_size: f64) {
+        if fill_size <= 0.0 {
+            return;
+        }
+
+        let idx = match self
+            .positions
+            .iter()
+            .position(|p| p.outcome == order.outcome)
+        {
+            Some(idx) => idx,
+            None => {
+                self.positions.push(Position {
+                    market_id: self.market_id.clone(),
+                    outcome: order.outcome.clone(),
+                    size: 0.0,
+                    average_price: 0.0,
+                    current_price: order.price,
+                });
+                self.positions.len() - 1
+            }
+        };
+
+        let position = &mut self.positions[idx];
+        match order.side {
+            OrderSide::Buy => {
+                let new_size = position.size + fill_size;
+                position.average_price =
+                    (position.size * position.average_price + fill_size * order.price) / new_size;
+                position.size = new_size;
+            }
+            OrderSide::Sell => {
+                position.size = (position.size - fill_size).max(0.0);
+            }
+        }
+        position.current_price = order.price;
+
+        if position.size <= 0.0 {
+            self.positions.remove(idx);
+        }
+    }
+
     pub async fn cancel_all_orders(&mut self) -> Result<(), DrmError> {
         for order in self.open_orders.drain(..) {
             let _ = self
                 .exchange
                 .cancel_order(&order.id, Some(&self.market_id))
                 .await;
+            self.order_tracker.untrack_order(&order.id);
         }
         Ok(())
     }
@@ -188,6 +300,7 @@ impl<E: Exchange + 'static> BaseStrategy<E> {
             .await?;
 
         self.open_orders.push(order.clone());
+        self.order_tracker.track_order(order.clone());
         let _ = self.event_tx.send(StrategyEvent::Order(order.clone()));
 
         Ok(order)
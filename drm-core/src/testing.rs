@@ -0,0 +1,166 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::error::{DrmError, ExchangeError};
+use crate::exchange::{Exchange, FetchMarketsParams, FetchOrdersParams};
+use crate::models::{Market, Order, OrderSide, OrderStatus, Position};
+
+pub(crate) fn make_market(id: &str) -> Market {
+    Market {
+        id: id.to_string(),
+        question: "Will it rain tomorrow?".to_string(),
+        outcomes: vec!["Yes".to_string(), "No".to_string()],
+        close_time: None,
+        volume: 1000.0,
+        liquidity: 5000.0,
+        prices: HashMap::from([("Yes".to_string(), 0.5), ("No".to_string(), 0.5)]),
+        metadata: serde_json::json!({ "clobTokenIds": ["yes-token", "no-token"] }),
+        tick_size: 0.01,
+        description: String::new(),
+    }
+}
+
+/// In-memory exchange that accepts every order and records what it saw.
+#[derive(Default)]
+pub(crate) struct MockExchange {
+    pub markets: Mutex<Vec<Market>>,
+    pub orders: Mutex<Vec<Order>>,
+    pub cancelled: Mutex<Vec<String>>,
+    /// `create_order` params, in call order.
+    pub params: Mutex<Vec<HashMap<String, String>>>,
+    pub positions: Mutex<Vec<Position>>,
+    pub balance: Mutex<HashMap<String, f64>>,
+    next_id: AtomicUsize,
+}
+
+impl MockExchange {
+    pub fn with_market(market: Market) -> Self {
+        let exchange = Self::default();
+        exchange.markets.lock().unwrap().push(market);
+        exchange
+            .balance
+            .lock()
+            .unwrap()
+            .insert("USDC".to_string(), 1000.0);
+        exchange
+    }
+
+    pub fn open_orders(&self) -> Vec<Order> {
+        self.orders
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|o| o.is_active())
+            .cloned()
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Exchange for MockExchange {
+    fn id(&self) -> &'static str {
+        "mock"
+    }
+
+    fn name(&self) -> &'static str {
+        "Mock"
+    }
+
+    async fn fetch_markets(
+        &self,
+        _params: Option<FetchMarketsParams>,
+    ) -> Result<Vec<Market>, DrmError> {
+        Ok(self.markets.lock().unwrap().clone())
+    }
+
+    async fn fetch_market(&self, market_id: &str) -> Result<Market, DrmError> {
+        self.markets
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|m| m.id == market_id)
+            .cloned()
+            .ok_or_else(|| ExchangeError::MarketNotFound(market_id.to_string()).into())
+    }
+
+    async fn create_order(
+        &self,
+        market_id: &str,
+        outcome: &str,
+        side: OrderSide,
+        price: f64,
+        size: f64,
+        params: HashMap<String, String>,
+    ) -> Result<Order, DrmError> {
+        self.params.lock().unwrap().push(params);
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let order = Order {
+            id: format!("order-{id}"),
+            market_id: market_id.to_string(),
+            outcome: outcome.to_string(),
+            side,
+            price,
+            size,
+            filled: 0.0,
+            status: OrderStatus::Open,
+            created_at: Utc::now(),
+            updated_at: None,
+        };
+        self.orders.lock().unwrap().push(order.clone());
+        Ok(order)
+    }
+
+    async fn cancel_order(
+        &self,
+        order_id: &str,
+        _market_id: Option<&str>,
+    ) -> Result<Order, DrmError> {
+        self.cancelled.lock().unwrap().push(order_id.to_string());
+        let mut orders = self.orders.lock().unwrap();
+        let order = orders
+            .iter_mut()
+            .find(|o| o.id == order_id)
+            .ok_or_else(|| ExchangeError::InvalidOrder(order_id.to_string()))?;
+        order.status = OrderStatus::Cancelled;
+        Ok(order.clone())
+    }
+
+    async fn fetch_order(
+        &self,
+        order_id: &str,
+        _market_id: Option<&str>,
+    ) -> Result<Order, DrmError> {
+        self.orders
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|o| o.id == order_id)
+            .cloned()
+            .ok_or_else(|| ExchangeError::InvalidOrder(order_id.to_string()).into())
+    }
+
+    async fn fetch_open_orders(
+        &self,
+        _params: Option<FetchOrdersParams>,
+    ) -> Result<Vec<Order>, DrmError> {
+        Ok(self.open_orders())
+    }
+
+    async fn fetch_positions(&self, market_id: Option<&str>) -> Result<Vec<Position>, DrmError> {
+        Ok(self
+            .positions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| market_id.is_none_or(|id| p.market_id == id))
+            .cloned()
+            .collect())
+    }
+
+    async fn fetch_balance(&self) -> Result<HashMap<String, f64>, DrmError> {
+        Ok(self.balance.lock().unwrap().clone())
+    }
+}
@@ -4,8 +4,9 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use drm_core::{
-    DrmError, Exchange, ExchangeInfo, FetchMarketsParams, FetchOrdersParams, Market, Order,
-    OrderSide, OrderStatus, Orderbook, Position, PriceLevel, RateLimiter,
+    consolidate_binary_books, DrmError, Exchange, ExchangeInfo, FetchMarketsParams,
+    FetchOrdersParams, Market, Order, OrderSide, OrderStatus, Orderbook, OrderbookSource, Position,
+    PriceLevel, RateLimiter,
 };
 
 use crate::auth::KalshiAuth;
@@ -392,56 +393,36 @@ impl Kalshi {
         let path = format!("/markets/{ticker}/orderbook");
         let resp: OrderbookResponse = self.get(&path).await?;
 
-        // Convert yes/no orderbook to bids/asks
-        // Bids = buying Yes (or selling No)
-        // Asks = selling Yes (or buying No)
-        let mut bids = Vec::new();
-        let mut asks = Vec::new();
-
-        if let Some(yes_levels) = resp.orderbook.yes {
-            for level in yes_levels {
-                if level.len() >= 2 {
-                    let price = level[0] / 100.0; // Convert cents to decimal
-                    let size = level[1];
-                    bids.push(PriceLevel { price, size });
-                }
-            }
-        }
-
-        if let Some(no_levels) = resp.orderbook.no {
-            for level in no_levels {
-                if level.len() >= 2 {
-                    let price = level[0] / 100.0;
-                    let size = level[1];
-                    // No orders are inverse - convert to Yes-equivalent asks
-                    asks.push(PriceLevel {
-                        price: 1.0 - price,
-                        size,
-                    });
-                }
-            }
-        }
+        // Kalshi only lists bids on each side; a No bid is a Yes ask at 1 - p.
+        let parse_bids = |levels: Option<Vec<Vec<f64>>>| -> Vec<PriceLevel> {
+            levels
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|level| level.len() >= 2)
+                .map(|level| PriceLevel {
+                    price: level[0] / 100.0, // Convert cents to decimal
+                    size: level[1],
+                })
+                .collect()
+        };
 
-        // Sort: bids descending, asks ascending
-        bids.sort_by(|a, b| {
-            b.price
-                .partial_cmp(&a.price)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-        asks.sort_by(|a, b| {
-            a.price
-                .partial_cmp(&b.price)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        Ok(Orderbook {
+        let yes_book = Orderbook {
             market_id: ticker.to_string(),
             asset_id: ticker.to_string(),
-            bids,
-            asks,
-            last_update_id: None,
-            timestamp: Some(chrono::Utc::now()),
-        })
+            bids: parse_bids(resp.orderbook.yes),
+            ..Default::default()
+        };
+        let no_book = Orderbook {
+            market_id: ticker.to_string(),
+            asset_id: ticker.to_string(),
+            bids: parse_bids(resp.orderbook.no),
+            ..Default::default()
+        };
+
+        let mut orderbook = consolidate_binary_books(&yes_book, &no_book).to_orderbook();
+        orderbook.timestamp = Some(chrono::Utc::now());
+
+        Ok(orderbook)
     }
 }
 
@@ -728,3 +709,12 @@ impl Exchange for Kalshi {
         }
     }
 }
+
+#[async_trait]
+impl OrderbookSource for Kalshi {
+    async fn fetch_book(&self, ticker: &str) -> Result<Orderbook, DrmError> {
+        self.fetch_orderbook(ticker)
+            .await
+            .map_err(|e| DrmError::Exchange(e.into()))
+    }
+}
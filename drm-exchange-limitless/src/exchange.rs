@@ -5,7 +5,8 @@ use tokio::sync::Mutex;
 
 use drm_core::{
     DrmError, Exchange, ExchangeInfo, FetchMarketsParams, FetchOrdersParams, Market, Nav, Order,
-    OrderSide, OrderStatus, Position, PriceHistoryInterval, PricePoint, RateLimiter,
+    OrderSide, OrderStatus, Orderbook, OrderbookSource, Position, PriceHistoryInterval, PricePoint,
+    RateLimiter,
 };
 
 use crate::clob::{LimitlessClobClient, LimitlessOrderType, LimitlessSide};
@@ -421,7 +422,7 @@ impl Limitless {
             }
         }
 
-        points.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        points.sort_by_key(|p| p.timestamp);
         Ok(points)
     }
 
@@ -971,3 +972,12 @@ impl Exchange for Limitless {
         }
     }
 }
+
+#[async_trait]
+impl OrderbookSource for Limitless {
+    async fn fetch_book(&self, slug_or_token: &str) -> Result<Orderbook, DrmError> {
+        self.get_orderbook(slug_or_token)
+            .await
+            .map_err(|e| DrmError::Exchange(e.into()))
+    }
+}
@@ -10,7 +10,8 @@ use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
 
 use drm_core::{
-    OrderBookWebSocket, Orderbook, OrderbookStream, PriceLevel, WebSocketError, WebSocketState,
+    L2Book, OrderBookWebSocket, Orderbook, OrderbookManager, OrderbookStream, PriceLevel,
+    WebSocketError, WebSocketState,
 };
 
 const WS_URL: &str = "wss://ws.limitless.exchange";
@@ -71,7 +72,6 @@ struct SharedState {
     subscribed_slugs: Vec<String>,
     subscribed_addresses: Vec<String>,
     orderbook_senders: HashMap<String, OrderbookSender>,
-    orderbooks: HashMap<String, Orderbook>,
 }
 
 impl SharedState {
@@ -81,7 +81,6 @@ impl SharedState {
             subscribed_slugs: Vec::new(),
             subscribed_addresses: Vec::new(),
             orderbook_senders: HashMap::new(),
-            orderbooks: HashMap::new(),
         }
     }
 }
@@ -89,6 +88,7 @@ impl SharedState {
 pub struct LimitlessWebSocket {
     shared: Arc<RwLock<SharedState>>,
     client: Arc<RwLock<Option<Client>>>,
+    orderbook_manager: OrderbookManager,
     #[allow(dead_code)]
     auto_reconnect: bool,
 }
@@ -102,10 +102,22 @@ impl LimitlessWebSocket {
         Self {
             shared: Arc::new(RwLock::new(SharedState::new())),
             client: Arc::new(RwLock::new(None)),
+            orderbook_manager: OrderbookManager::new(),
             auto_reconnect,
         }
     }
 
+    /// Publishes every book into `manager` instead of a private one, so
+    /// several clients can share a single store.
+    pub fn with_orderbook_manager(mut self, manager: OrderbookManager) -> Self {
+        self.orderbook_manager = manager;
+        self
+    }
+
+    pub fn orderbook_manager(&self) -> OrderbookManager {
+        self.orderbook_manager.clone()
+    }
+
     async fn set_state(&self, new_state: WebSocketState) {
         let mut shared = self.shared.write().await;
         shared.ws_state = new_state;
@@ -158,7 +170,11 @@ impl LimitlessWebSocket {
         }
     }
 
-    async fn handle_orderbook_update(shared: Arc<RwLock<SharedState>>, data: OrderbookUpdateData) {
+    async fn handle_orderbook_update(
+        shared: Arc<RwLock<SharedState>>,
+        manager: &OrderbookManager,
+        data: OrderbookUpdateData,
+    ) {
         let market_slug = match data.market_slug {
             Some(s) => s,
             None => return,
@@ -170,46 +186,26 @@ impl LimitlessWebSocket {
             (data.bids.unwrap_or_default(), data.asks.unwrap_or_default())
         };
 
-        let mut bids: Vec<PriceLevel> = raw_bids
-            .iter()
-            .filter_map(Self::parse_price_level)
-            .collect();
-        let mut asks: Vec<PriceLevel> = raw_asks
-            .iter()
-            .filter_map(Self::parse_price_level)
-            .collect();
-
-        bids.sort_by(|a, b| {
-            b.price
-                .partial_cmp(&a.price)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-        asks.sort_by(|a, b| {
-            a.price
-                .partial_cmp(&b.price)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        let orderbook = Orderbook {
-            market_id: market_slug.clone(),
-            asset_id: market_slug.clone(),
-            bids,
-            asks,
-            last_update_id: None,
-            timestamp: Some(chrono::Utc::now()),
-        };
+        let mut book = L2Book::new(market_slug.clone()).with_market_id(market_slug.clone());
+        book.apply_snapshot(
+            raw_bids.iter().filter_map(Self::parse_price_level),
+            raw_asks.iter().filter_map(Self::parse_price_level),
+        );
+        let orderbook = book.to_orderbook();
 
-        let mut shared = shared.write().await;
-        shared
-            .orderbooks
-            .insert(market_slug.clone(), orderbook.clone());
+        manager.update(market_slug.clone(), orderbook.clone());
 
+        let shared = shared.read().await;
         if let Some(sender) = shared.orderbook_senders.get(&market_slug) {
             let _ = sender.send(Ok(orderbook));
         }
     }
 
-    async fn handle_price_update(shared: Arc<RwLock<SharedState>>, data: PriceUpdateData) {
+    async fn handle_price_update(
+        shared: Arc<RwLock<SharedState>>,
+        manager: &OrderbookManager,
+        data: PriceUpdateData,
+    ) {
         let market_address = match data.market_address {
             Some(a) => a,
             None => return,
@@ -237,20 +233,13 @@ impl LimitlessWebSocket {
             asks.push(PriceLevel::new(1.0 - no_price, 1.0));
         }
 
-        let orderbook = Orderbook {
-            market_id: market_address.clone(),
-            asset_id: market_address.clone(),
-            bids,
-            asks,
-            last_update_id: None,
-            timestamp: Some(chrono::Utc::now()),
-        };
+        let mut book = L2Book::new(market_address.clone()).with_market_id(market_address.clone());
+        book.apply_snapshot(bids, asks);
+        let orderbook = book.to_orderbook();
 
-        let mut shared = shared.write().await;
-        shared
-            .orderbooks
-            .insert(market_address.clone(), orderbook.clone());
+        manager.update(market_address.clone(), orderbook.clone());
 
+        let shared = shared.read().await;
         if let Some(sender) = shared.orderbook_senders.get(&market_address) {
             let _ = sender.send(Ok(orderbook));
         }
@@ -273,6 +262,8 @@ impl OrderBookWebSocket for LimitlessWebSocket {
         let shared_price = self.shared.clone();
         let shared_connect = self.shared.clone();
         let shared_disconnect = self.shared.clone();
+        let manager_orderbook = self.orderbook_manager.clone();
+        let manager_price = self.orderbook_manager.clone();
 
         let client = ClientBuilder::new(WS_URL)
             .namespace(NAMESPACE)
@@ -296,11 +287,12 @@ impl OrderBookWebSocket for LimitlessWebSocket {
             })
             .on("orderbookUpdate", move |payload, _| {
                 let shared = shared_orderbook.clone();
+                let manager = manager_orderbook.clone();
                 async move {
                     if let Payload::Text(values) = payload {
                         for value in values {
                             if let Ok(data) = serde_json::from_value::<OrderbookUpdateData>(value) {
-                                Self::handle_orderbook_update(shared.clone(), data).await;
+                                Self::handle_orderbook_update(shared.clone(), &manager, data).await;
                             }
                         }
                     }
@@ -309,11 +301,12 @@ impl OrderBookWebSocket for LimitlessWebSocket {
             })
             .on("newPriceData", move |payload, _| {
                 let shared = shared_price.clone();
+                let manager = manager_price.clone();
                 async move {
                     if let Payload::Text(values) = payload {
                         for value in values {
                             if let Ok(data) = serde_json::from_value::<PriceUpdateData>(value) {
-                                Self::handle_price_update(shared.clone(), data).await;
+                                Self::handle_price_update(shared.clone(), &manager, data).await;
                             }
                         }
                     }
@@ -364,6 +357,7 @@ impl OrderBookWebSocket for LimitlessWebSocket {
             let mut shared = self.shared.write().await;
             if !shared.subscribed_slugs.contains(&market_id.to_string()) {
                 shared.subscribed_slugs.push(market_id.to_string());
+                self.orderbook_manager.acquire(market_id);
             }
             if !shared.orderbook_senders.contains_key(market_id) {
                 let (tx, _) = broadcast::channel(100);
@@ -384,12 +378,16 @@ impl OrderBookWebSocket for LimitlessWebSocket {
     }
 
     async fn unsubscribe(&mut self, market_id: &str) -> Result<(), WebSocketError> {
-        {
+        let was_subscribed = {
             let mut shared = self.shared.write().await;
+            let before = shared.subscribed_slugs.len();
             shared.subscribed_slugs.retain(|s| s != market_id);
             shared.subscribed_addresses.retain(|s| s != market_id);
             shared.orderbook_senders.remove(market_id);
-            shared.orderbooks.remove(market_id);
+            shared.subscribed_slugs.len() != before
+        };
+        if was_subscribed {
+            self.orderbook_manager.release(market_id);
         }
 
         let state = {
@@ -463,7 +461,8 @@ impl LimitlessWebSocket {
     }
 
     pub async fn get_orderbook(&self, market_id: &str) -> Option<Orderbook> {
-        let shared = self.shared.read().await;
-        shared.orderbooks.get(market_id).cloned()
+        self.orderbook_manager
+            .get(market_id)
+            .map(|ob| Orderbook::clone(&ob))
     }
 }
@@ -2,6 +2,8 @@ use drm_core::ExchangeConfig;
 
 pub const BASE_URL: &str = "https://proxy.opinion.trade:8443";
 pub const CHAIN_ID: u64 = 56;
+pub const BSC_RPC_URL: &str = "https://bsc-dataseed.bnbchain.org";
+pub const CONDITIONAL_TOKENS_ADDR: &str = "0xAD1a38cEc043e70E83a3eC30443dB285ED10D774";
 
 #[derive(Debug, Clone)]
 pub struct OpinionConfig {
@@ -11,6 +13,8 @@ pub struct OpinionConfig {
     pub private_key: Option<String>,
     pub multi_sig_addr: Option<String>,
     pub chain_id: u64,
+    pub rpc_url: String,
+    pub conditional_tokens_addr: String,
 }
 
 impl Default for OpinionConfig {
@@ -22,6 +26,8 @@ impl Default for OpinionConfig {
             private_key: None,
             multi_sig_addr: None,
             chain_id: CHAIN_ID,
+            rpc_url: BSC_RPC_URL.into(),
+            conditional_tokens_addr: CONDITIONAL_TOKENS_ADDR.into(),
         }
     }
 }
@@ -51,6 +57,16 @@ impl OpinionConfig {
         self
     }
 
+    pub fn with_rpc_url(mut self, url: impl Into<String>) -> Self {
+        self.rpc_url = url.into();
+        self
+    }
+
+    pub fn with_conditional_tokens(mut self, addr: impl Into<String>) -> Self {
+        self.conditional_tokens_addr = addr.into();
+        self
+    }
+
     pub fn with_verbose(mut self, verbose: bool) -> Self {
         self.base = self.base.with_verbose(verbose);
         self
@@ -19,6 +19,15 @@ pub enum OpinionError {
 
     #[error("not supported: {0}")]
     NotSupported(String),
+
+    #[error("config error: {0}")]
+    Config(String),
+
+    #[error("signing error: {0}")]
+    Signing(String),
+
+    #[error("transaction error: {0}")]
+    Transaction(String),
 }
 
 impl From<OpinionError> for drm_core::ExchangeError {
@@ -32,3 +41,12 @@ impl From<OpinionError> for drm_core::ExchangeError {
         }
     }
 }
+
+impl From<drm_core::CtfError> for OpinionError {
+    fn from(err: drm_core::CtfError) -> Self {
+        match err {
+            drm_core::CtfError::Transaction(msg) => OpinionError::Transaction(msg),
+            drm_core::CtfError::Signing(msg) => OpinionError::Signing(msg),
+        }
+    }
+}
@@ -1,16 +1,18 @@
 use async_trait::async_trait;
+use ethers::types::{Address, H256, U256};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use drm_core::{
     DrmError, Exchange, ExchangeInfo, FetchMarketsParams, FetchOrdersParams, Market, Nav, Order,
-    OrderSide, OrderStatus, Orderbook, Position, PriceHistoryInterval, PriceLevel, PricePoint,
-    RateLimiter,
+    OrderSide, OrderStatus, Orderbook, OrderbookSource, Position, PriceHistoryInterval, PriceLevel,
+    PricePoint, RateLimiter,
 };
 
 use crate::config::OpinionConfig;
 use crate::error::OpinionError;
+use crate::onchain::{OnchainClient, OnchainResult};
 
 #[derive(Debug, serde::Deserialize)]
 struct ApiResponse<T> {
@@ -29,6 +31,7 @@ pub struct Opinion {
     config: OpinionConfig,
     client: reqwest::Client,
     rate_limiter: Arc<Mutex<RateLimiter>>,
+    onchain: Option<Arc<OnchainClient>>,
 }
 
 impl Opinion {
@@ -41,10 +44,22 @@ impl Opinion {
             config.base.rate_limit_per_second,
         )));
 
+        let onchain = match config.private_key {
+            Some(ref private_key) => Some(Arc::new(OnchainClient::new(
+                &config.rpc_url,
+                private_key,
+                config.multi_sig_addr.as_deref(),
+                config.chain_id,
+                &config.conditional_tokens_addr,
+            )?)),
+            None => None,
+        };
+
         Ok(Self {
             config,
             client,
             rate_limiter,
+            onchain,
         })
     }
 
@@ -597,7 +612,7 @@ impl Opinion {
             }
         }
 
-        points.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        points.sort_by_key(|p| p.timestamp);
         Ok(points)
     }
 
@@ -652,39 +667,97 @@ impl Opinion {
         Ok(filtered)
     }
 
+    fn onchain(&self) -> Result<&OnchainClient, OpinionError> {
+        self.onchain.as_deref().ok_or(OpinionError::AuthRequired)
+    }
+
+    /// Condition id and collateral token of a market, as needed on chain.
+    pub async fn fetch_condition(&self, market_id: &str) -> Result<(H256, Address), OpinionError> {
+        let market = self
+            .fetch_market(market_id)
+            .await
+            .map_err(|e| OpinionError::Api(format!("{e}")))?;
+
+        let field = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|k| market.metadata.get(*k).and_then(|v| v.as_str()))
+                .map(String::from)
+        };
+
+        let condition_id = field(&["condition_id", "conditionId"])
+            .and_then(|s| s.parse::<H256>().ok())
+            .ok_or_else(|| OpinionError::Api(format!("market {market_id} has no condition id")))?;
+        let collateral = field(&["quote_token", "quoteToken", "collateral"])
+            .and_then(|s| s.parse::<Address>().ok())
+            .ok_or_else(|| {
+                OpinionError::Api(format!("market {market_id} has no collateral token"))
+            })?;
+
+        Ok((condition_id, collateral))
+    }
+
+    fn reject_approval_check(operation: &str, check_approval: bool) -> Result<(), OpinionError> {
+        if check_approval {
+            return Err(OpinionError::NotSupported(format!(
+                "{operation} burns the holder's own tokens and needs no approval; \
+                 pass check_approval = false"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Locks `amount_wei` collateral into Yes and No tokens. With
+    /// `check_approval`, first approves the ConditionalTokens contract if
+    /// its collateral allowance is too low.
     pub async fn split(
         &self,
         market_id: &str,
         amount_wei: u128,
-        _check_approval: bool,
-    ) -> Result<HashMap<String, String>, OpinionError> {
-        let _ = (market_id, amount_wei);
-        Err(OpinionError::NotSupported(
-            "split requires on-chain transaction; use Python opinion_clob_sdk".into(),
-        ))
+        check_approval: bool,
+    ) -> Result<OnchainResult, OpinionError> {
+        let onchain = self.onchain()?;
+        let (condition_id, collateral) = self.fetch_condition(market_id).await?;
+
+        onchain
+            .split(
+                collateral,
+                condition_id,
+                U256::from(amount_wei),
+                check_approval,
+            )
+            .await
     }
 
+    /// Burns `amount_wei` Yes and No tokens back into collateral. The
+    /// holder burns its own tokens, so there is no approval to check and
+    /// `check_approval` must be false.
     pub async fn merge(
         &self,
         market_id: &str,
         amount_wei: u128,
-        _check_approval: bool,
-    ) -> Result<HashMap<String, String>, OpinionError> {
-        let _ = (market_id, amount_wei);
-        Err(OpinionError::NotSupported(
-            "merge requires on-chain transaction; use Python opinion_clob_sdk".into(),
-        ))
+        check_approval: bool,
+    ) -> Result<OnchainResult, OpinionError> {
+        Self::reject_approval_check("merge", check_approval)?;
+        let onchain = self.onchain()?;
+        let (condition_id, collateral) = self.fetch_condition(market_id).await?;
+
+        onchain
+            .merge(collateral, condition_id, U256::from(amount_wei))
+            .await
     }
 
+    /// Redeems winning tokens of a resolved market. As with `merge`,
+    /// `check_approval` must be false.
     pub async fn redeem(
         &self,
         market_id: &str,
-        _check_approval: bool,
-    ) -> Result<HashMap<String, String>, OpinionError> {
-        let _ = market_id;
-        Err(OpinionError::NotSupported(
-            "redeem requires on-chain transaction; use Python opinion_clob_sdk".into(),
-        ))
+        check_approval: bool,
+    ) -> Result<OnchainResult, OpinionError> {
+        Self::reject_approval_check("redeem", check_approval)?;
+        let onchain = self.onchain()?;
+        let (condition_id, collateral) = self.fetch_condition(market_id).await?;
+
+        onchain.redeem(collateral, condition_id).await
     }
 
     pub async fn fetch_positions_for_market(
@@ -1056,3 +1129,12 @@ impl Exchange for Opinion {
         }
     }
 }
+
+#[async_trait]
+impl OrderbookSource for Opinion {
+    async fn fetch_book(&self, token_id: &str) -> Result<Orderbook, DrmError> {
+        self.get_orderbook(token_id)
+            .await
+            .map_err(|e| DrmError::Exchange(e.into()))
+    }
+}
@@ -1,7 +1,9 @@
 mod config;
 mod error;
 mod exchange;
+mod onchain;
 
 pub use config::*;
 pub use error::*;
 pub use exchange::*;
+pub use onchain::*;
@@ -0,0 +1,189 @@
+use drm_core::ctf::{
+    call_uint, encode_call, merge_positions_call, redeem_positions_call, safe_call,
+    send_and_confirm, split_position_call, CtfCall, SignerClient,
+};
+use ethers::abi::{parse_abi, Abi, Token};
+use ethers::prelude::*;
+use std::sync::{Arc, OnceLock};
+
+use crate::error::OpinionError;
+
+fn erc20_abi() -> &'static Abi {
+    static ABI: OnceLock<Abi> = OnceLock::new();
+    ABI.get_or_init(|| {
+        parse_abi(&[
+            "function allowance(address owner, address spender) view returns (uint256)",
+            "function approve(address spender, uint256 amount) returns (bool)",
+        ])
+        .expect("valid ERC20 abi")
+    })
+}
+
+/// Outcome of an on-chain operation.
+#[derive(Debug, Clone)]
+pub struct OnchainResult {
+    pub tx_hash: H256,
+    pub receipt: TransactionReceipt,
+    /// Approval transactions sent before the operation, if any were needed.
+    pub approvals: Vec<TransactionReceipt>,
+}
+
+/// Sends ConditionalTokens transactions on BNB chain. With a multisig the
+/// Safe holds the funds and the key signs `execTransaction` as its owner;
+/// otherwise the key's own address holds them.
+pub struct OnchainClient {
+    client: Arc<SignerClient>,
+    multi_sig: Option<Address>,
+    chain_id: u64,
+    conditional_tokens: Address,
+}
+
+impl OnchainClient {
+    pub fn new(
+        rpc_url: &str,
+        private_key: &str,
+        multi_sig: Option<&str>,
+        chain_id: u64,
+        conditional_tokens: &str,
+    ) -> Result<Self, OpinionError> {
+        let wallet: LocalWallet = private_key
+            .parse()
+            .map_err(|e| OpinionError::Config(format!("invalid private key: {e}")))?;
+
+        let multi_sig = multi_sig
+            .map(|a| a.parse::<Address>())
+            .transpose()
+            .map_err(|e| OpinionError::Config(format!("invalid multisig address: {e}")))?;
+
+        let conditional_tokens = conditional_tokens.parse().map_err(|e| {
+            OpinionError::Config(format!("invalid conditional tokens address: {e}"))
+        })?;
+
+        let provider = Provider::<Http>::try_from(rpc_url)
+            .map_err(|e| OpinionError::Config(format!("invalid rpc url: {e}")))?;
+
+        Ok(Self {
+            client: Arc::new(SignerMiddleware::new(
+                provider,
+                wallet.with_chain_id(chain_id),
+            )),
+            multi_sig,
+            chain_id,
+            conditional_tokens,
+        })
+    }
+
+    /// Wallet holding collateral and outcome tokens.
+    pub fn holder(&self) -> Address {
+        self.multi_sig.unwrap_or_else(|| self.client.address())
+    }
+
+    pub async fn split(
+        &self,
+        collateral: Address,
+        condition_id: H256,
+        amount: U256,
+        check_approval: bool,
+    ) -> Result<OnchainResult, OpinionError> {
+        let mut approvals = Vec::new();
+        if check_approval {
+            approvals.extend(self.ensure_allowance(collateral, amount).await?);
+        }
+
+        let call = split_position_call(self.conditional_tokens, collateral, condition_id, amount);
+        let receipt = self.execute(&call).await?;
+
+        Ok(OnchainResult {
+            tx_hash: receipt.transaction_hash,
+            receipt,
+            approvals,
+        })
+    }
+
+    /// Merging burns the holder's own outcome tokens, so no approval is
+    /// needed.
+    pub async fn merge(
+        &self,
+        collateral: Address,
+        condition_id: H256,
+        amount: U256,
+    ) -> Result<OnchainResult, OpinionError> {
+        let call = merge_positions_call(self.conditional_tokens, collateral, condition_id, amount);
+        let receipt = self.execute(&call).await?;
+
+        Ok(OnchainResult {
+            tx_hash: receipt.transaction_hash,
+            receipt,
+            approvals: Vec::new(),
+        })
+    }
+
+    /// Like merging, redeeming burns the holder's own tokens.
+    pub async fn redeem(
+        &self,
+        collateral: Address,
+        condition_id: H256,
+    ) -> Result<OnchainResult, OpinionError> {
+        let call = redeem_positions_call(self.conditional_tokens, collateral, condition_id);
+        let receipt = self.execute(&call).await?;
+
+        Ok(OnchainResult {
+            tx_hash: receipt.transaction_hash,
+            receipt,
+            approvals: Vec::new(),
+        })
+    }
+
+    /// Approves the ConditionalTokens contract to pull `amount` collateral
+    /// from the holder, if the current allowance falls short.
+    pub async fn ensure_allowance(
+        &self,
+        collateral: Address,
+        amount: U256,
+    ) -> Result<Option<TransactionReceipt>, OpinionError> {
+        let allowance_call = CtfCall {
+            to: collateral,
+            data: encode_call(
+                erc20_abi(),
+                "allowance",
+                &[
+                    Token::Address(self.holder()),
+                    Token::Address(self.conditional_tokens),
+                ],
+            ),
+        };
+        let allowance = call_uint(&self.client, &allowance_call).await?;
+        if allowance >= amount {
+            return Ok(None);
+        }
+
+        let approve = CtfCall {
+            to: collateral,
+            data: encode_call(
+                erc20_abi(),
+                "approve",
+                &[
+                    Token::Address(self.conditional_tokens),
+                    Token::Uint(U256::MAX),
+                ],
+            ),
+        };
+        self.execute(&approve).await.map(Some)
+    }
+
+    /// Sends `call` from the holder and waits for the receipt. Reverted
+    /// transactions are reported as errors.
+    pub async fn execute(&self, call: &CtfCall) -> Result<TransactionReceipt, OpinionError> {
+        let call = match self.multi_sig {
+            Some(safe) => safe_call(&self.client, safe, self.chain_id, call).await?,
+            None => call.clone(),
+        };
+
+        let tx = TransactionRequest::new()
+            .to(call.to)
+            .data(call.data)
+            .from(self.client.address());
+
+        Ok(send_and_confirm(&self.client, tx.into()).await?)
+    }
+}
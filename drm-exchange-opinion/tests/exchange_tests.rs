@@ -1,5 +1,11 @@
+use drm_core::ctf::{
+    merge_positions_call, redeem_positions_call, safe_exec_call, safe_tx_hash, split_position_call,
+};
 use drm_core::Exchange;
-use drm_exchange_opinion::{Opinion, OpinionConfig};
+use drm_exchange_opinion::{Opinion, OpinionConfig, OpinionError, CONDITIONAL_TOKENS_ADDR};
+use ethers::abi::{parse_abi, Token};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::{Address, Signature, H256, U256};
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -227,3 +233,161 @@ async fn test_parse_token_ids_from_market() {
     assert_eq!(token_ids[0], "token_yes_789");
     assert_eq!(token_ids[1], "token_no_789");
 }
+
+const TEST_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const CONDITION_ID: &str = "0x5e5c9b3b5bd63ab94e0d1b1e3d4f11d0b23c2c6b1f0b46c9e0e0df7c36d3d2a1";
+const USDT: &str = "0x55d398326f99059fF775485246999027B3197955";
+
+fn onchain_market_response() -> serde_json::Value {
+    serde_json::json!({
+        "errno": 0,
+        "errmsg": null,
+        "result": {
+            "data": {
+                "market_id": "789",
+                "market_title": "Test market question",
+                "yes_token_id": "token_yes_789",
+                "no_token_id": "token_no_789",
+                "condition_id": CONDITION_ID,
+                "quote_token": USDT
+            }
+        }
+    })
+}
+
+#[test]
+fn test_conditional_tokens_calldata() {
+    // given
+    let ct: Address = CONDITIONAL_TOKENS_ADDR.parse().unwrap();
+    let collateral: Address = USDT.parse().unwrap();
+    let condition_id: H256 = CONDITION_ID.parse().unwrap();
+
+    // when
+    let split = split_position_call(
+        ct,
+        collateral,
+        condition_id,
+        U256::from(10u64).pow(18.into()),
+    );
+    let merge = merge_positions_call(ct, collateral, condition_id, U256::from(5));
+    let redeem = redeem_positions_call(ct, collateral, condition_id);
+
+    // then
+    assert_eq!(split.data[..4], [0x72, 0xce, 0x42, 0x75]);
+    assert_eq!(merge.data[..4], [0x9e, 0x72, 0x12, 0xad]);
+    assert_eq!(redeem.data[..4], [0x01, 0xb7, 0x03, 0x7c]);
+    assert_eq!(split.to, ct);
+
+    let abi = parse_abi(&[
+        "function splitPosition(address collateralToken, bytes32 parentCollectionId, bytes32 conditionId, uint256[] partition, uint256 amount)",
+    ])
+    .unwrap();
+    let tokens = abi
+        .function("splitPosition")
+        .unwrap()
+        .decode_input(&split.data[4..])
+        .unwrap();
+    assert_eq!(tokens[0], Token::Address(collateral));
+    assert_eq!(tokens[2], Token::FixedBytes(condition_id.0.to_vec()));
+    assert_eq!(
+        tokens[3],
+        Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())])
+    );
+}
+
+#[test]
+fn test_safe_exec_call_signed_by_owner() {
+    // given
+    let wallet: LocalWallet = TEST_KEY.parse().unwrap();
+    let safe: Address = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+        .parse()
+        .unwrap();
+    let call = redeem_positions_call(
+        CONDITIONAL_TOKENS_ADDR.parse().unwrap(),
+        USDT.parse().unwrap(),
+        CONDITION_ID.parse().unwrap(),
+    );
+    let hash = safe_tx_hash(safe, 56, &call, U256::from(3));
+    let signature = wallet.sign_hash(H256::from(hash)).unwrap();
+
+    // when
+    let exec = safe_exec_call(safe, &call, &signature);
+
+    // then
+    assert_eq!(exec.to, safe);
+    assert_ne!(hash, safe_tx_hash(safe, 56, &call, U256::from(4)));
+    assert_ne!(hash, safe_tx_hash(safe, 97, &call, U256::from(3)));
+    // signatures is the trailing dynamic arg: 65 bytes padded to 96
+    let recovered = Signature::try_from(&exec.data[exec.data.len() - 96..exec.data.len() - 31])
+        .unwrap()
+        .recover(H256::from(hash))
+        .unwrap();
+    assert_eq!(recovered, wallet.address());
+}
+
+#[tokio::test]
+async fn test_fetch_condition_reads_market_metadata() {
+    // given
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/markets/789"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(onchain_market_response()))
+        .mount(&mock_server)
+        .await;
+
+    let config = OpinionConfig::new()
+        .with_api_url(mock_server.uri())
+        .with_verbose(false);
+    let exchange = Opinion::new(config).unwrap();
+
+    // when
+    let (condition_id, collateral) = exchange.fetch_condition("789").await.unwrap();
+
+    // then
+    assert_eq!(condition_id, CONDITION_ID.parse::<H256>().unwrap());
+    assert_eq!(collateral, USDT.parse::<Address>().unwrap());
+}
+
+#[tokio::test]
+async fn test_split_requires_private_key() {
+    // given
+    let exchange = Opinion::with_default_config().unwrap();
+
+    // when
+    let result = exchange.split("789", 1_000, true).await;
+
+    // then
+    assert!(matches!(result, Err(OpinionError::AuthRequired)));
+}
+
+#[tokio::test]
+async fn test_merge_and_redeem_reject_approval_check() {
+    // given
+    let exchange = Opinion::with_default_config().unwrap();
+
+    // when
+    let merge_checked = exchange.merge("789", 1_000, true).await;
+    let redeem_checked = exchange.redeem("789", true).await;
+    let merge_unchecked = exchange.merge("789", 1_000, false).await;
+    let redeem_unchecked = exchange.redeem("789", false).await;
+
+    // then
+    assert!(matches!(merge_checked, Err(OpinionError::NotSupported(_))));
+    assert!(matches!(redeem_checked, Err(OpinionError::NotSupported(_))));
+    assert!(matches!(merge_unchecked, Err(OpinionError::AuthRequired)));
+    assert!(matches!(redeem_unchecked, Err(OpinionError::AuthRequired)));
+}
+
+#[test]
+fn test_new_rejects_invalid_multisig() {
+    // given
+    let config = OpinionConfig::new()
+        .with_private_key(TEST_KEY)
+        .with_multi_sig("not-an-address");
+
+    // when
+    let result = Opinion::new(config);
+
+    // then
+    assert!(matches!(result, Err(OpinionError::Config(_))));
+}
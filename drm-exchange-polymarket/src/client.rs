@@ -8,6 +8,7 @@ pub struct HttpClient {
     client: Client,
     gamma_url: String,
     clob_url: String,
+    data_url: String,
     verbose: bool,
 }
 
@@ -19,6 +20,7 @@ impl HttpClient {
             client,
             gamma_url: config.gamma_url.clone(),
             clob_url: config.clob_url.clone(),
+            data_url: config.data_url.clone(),
             verbose: config.base.verbose,
         })
     }
@@ -39,6 +41,14 @@ impl HttpClient {
         self.get(&url).await
     }
 
+    pub async fn get_data<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+    ) -> Result<T, PolymarketError> {
+        let url = format!("{}{}", self.data_url, endpoint);
+        self.get(&url).await
+    }
+
     async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T, PolymarketError> {
         if self.verbose {
             tracing::debug!("GET {}", url);
@@ -1,12 +1,30 @@
 use ethers::prelude::*;
 use ethers::utils::keccak256;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use crate::error::PolymarketError;
 
 pub const CLOB_URL: &str = "https://clob.polymarket.com";
 const CHAIN_ID: u64 = 137;
-const CTF_EXCHANGE: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+pub const CTF_EXCHANGE: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+pub const NEG_RISK_CTF_EXCHANGE: &str = "0xC5d563A36AE78145C45a50134d48A1215220f80a";
+/// How long a fetched market fee rate is reused before it is fetched again.
+const FEE_RATE_TTL: Duration = Duration::from_secs(300);
+
+/// Verifying contract orders are signed for. Neg-risk (multi-outcome)
+/// markets settle through a separate exchange.
+pub fn exchange_address(neg_risk: bool) -> &'static str {
+    if neg_risk {
+        NEG_RISK_CTF_EXCHANGE
+    } else {
+        CTF_EXCHANGE
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
@@ -19,16 +37,131 @@ pub enum ClobOrderSide {
 #[serde(rename_all = "UPPERCASE")]
 pub enum ClobOrderType {
     Gtc,
+    /// Good-til-date; the order must carry a non-zero expiration.
+    Gtd,
     Fok,
     Ioc,
 }
 
+impl ClobOrderType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gtc => "GTC",
+            Self::Gtd => "GTD",
+            Self::Fok => "FOK",
+            Self::Ioc => "IOC",
+        }
+    }
+}
+
+impl FromStr for ClobOrderType {
+    type Err = PolymarketError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "GTC" => Ok(Self::Gtc),
+            "GTD" => Ok(Self::Gtd),
+            "FOK" => Ok(Self::Fok),
+            "IOC" => Ok(Self::Ioc),
+            other => Err(PolymarketError::Config(format!(
+                "invalid order type: {other}"
+            ))),
+        }
+    }
+}
+
+/// Wallet type the order signature is produced for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SignatureType {
+    /// Plain EOA: the signing key also holds the funds.
+    Eoa,
+    /// Magic/email proxy wallet owned by the signing key.
+    PolyProxy,
+    /// Gnosis Safe owned by the signing key.
+    #[default]
+    PolyGnosisSafe,
+}
+
+impl SignatureType {
+    pub fn as_u8(self) -> u8 {
+        match self {
+            Self::Eoa => 0,
+            Self::PolyProxy => 1,
+            Self::PolyGnosisSafe => 2,
+        }
+    }
+
+    /// Address holding the funds for `signer`. EOAs act for themselves;
+    /// proxy and Safe wallets require the `funder` address.
+    pub fn resolve_maker(
+        self,
+        signer: Address,
+        funder: Option<&str>,
+    ) -> Result<Address, PolymarketError> {
+        let funder = funder
+            .map(|f| f.parse::<Address>())
+            .transpose()
+            .map_err(|e| PolymarketError::Config(format!("invalid funder address: {e}")))?;
+
+        match (self, funder) {
+            (Self::Eoa, None) => Ok(signer),
+            (Self::Eoa, Some(funder)) if funder == signer => Ok(signer),
+            (Self::Eoa, Some(funder)) => Err(PolymarketError::Config(format!(
+                "EOA signature type cannot trade for funder {funder:?}; use a proxy or Safe signature type"
+            ))),
+            (_, Some(funder)) => Ok(funder),
+            (_, None) => Err(PolymarketError::Config(format!(
+                "{self:?} signature type requires a funder address"
+            ))),
+        }
+    }
+}
+
+impl TryFrom<u8> for SignatureType {
+    type Error = PolymarketError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Eoa),
+            1 => Ok(Self::PolyProxy),
+            2 => Ok(Self::PolyGnosisSafe),
+            other => Err(PolymarketError::Config(format!(
+                "invalid signature type: {other}"
+            ))),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct OrderArgs {
     pub token_id: String,
     pub price: f64,
     pub size: f64,
     pub side: ClobOrderSide,
+    pub neg_risk: bool,
+    /// Unix seconds after which the order expires; 0 for none (GTD only).
+    pub expiration: u64,
+    /// Exchange nonce; bumping it on-chain invalidates all lower nonces.
+    pub nonce: u64,
+    /// Fee rate to sign. `None` fetches the market's current rate.
+    pub fee_rate_bps: Option<u64>,
+}
+
+static LAST_SALT: AtomicU64 = AtomicU64::new(0);
+
+/// Millisecond timestamp with random low digits, kept strictly increasing
+/// within the process so orders signed in the same millisecond never share
+/// a salt.
+fn next_salt() -> u64 {
+    let now = chrono::Utc::now().timestamp_millis() as u64 * 1000;
+    let base = now + ethers::core::rand::random::<u64>() % 1000;
+    let prev = LAST_SALT
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| {
+            Some(base.max(last + 1))
+        })
+        .unwrap_or_default();
+    base.max(prev + 1)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,16 +235,31 @@ pub struct TokenBalance {
     pub balance: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct FeeRateResponse {
+    base_fee: u64,
+}
+
 pub struct ClobClient {
     http: reqwest::Client,
+    base_url: String,
     wallet: LocalWallet,
     address: Address,
-    funder: Option<Address>,
+    maker: Address,
+    signature_type: SignatureType,
     api_creds: Option<ApiCredentials>,
+    fee_rates: Mutex<HashMap<String, (u64, Instant)>>,
 }
 
 impl ClobClient {
-    pub fn new(private_key: &str, funder: Option<&str>) -> Result<Self, PolymarketError> {
+    /// EOA orders are made and signed by the key's own address. Proxy and
+    /// Safe orders are signed by the key on behalf of the `funder` wallet,
+    /// which must be given.
+    pub fn new(
+        private_key: &str,
+        funder: Option<&str>,
+        signature_type: SignatureType,
+    ) -> Result<Self, PolymarketError> {
         let wallet: LocalWallet = private_key
             .parse()
             .map_err(|e| PolymarketError::Config(format!("invalid private key: {e}")))?;
@@ -119,24 +267,38 @@ impl ClobClient {
         let wallet = wallet.with_chain_id(CHAIN_ID);
         let address = wallet.address();
 
-        let funder = funder
-            .map(|f| f.parse::<Address>())
-            .transpose()
-            .map_err(|e| PolymarketError::Config(format!("invalid funder address: {e}")))?;
+        let maker = signature_type.resolve_maker(address, funder)?;
 
         Ok(Self {
             http: reqwest::Client::new(),
+            base_url: CLOB_URL.into(),
             wallet,
             address,
-            funder,
+            maker,
+            signature_type,
             api_creds: None,
+            fee_rates: Mutex::new(HashMap::new()),
         })
     }
 
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into();
+        self
+    }
+
     pub fn address(&self) -> Address {
         self.address
     }
 
+    /// Address that holds the funds and appears as `maker` on orders.
+    pub fn maker(&self) -> Address {
+        self.maker
+    }
+
+    pub fn signature_type(&self) -> SignatureType {
+        self.signature_type
+    }
+
     pub async fn derive_api_credentials(&mut self) -> Result<ApiCredentials, PolymarketError> {
         let nonce = chrono::Utc::now().timestamp_millis();
         let message = format!("I am signing this nonce: {nonce}");
@@ -146,7 +308,7 @@ impl ClobClient {
             .await
             .map_err(|e| PolymarketError::Signing(format!("signing failed: {e}")))?;
 
-        let url = format!("{CLOB_URL}/auth/derive-api-key");
+        let url = format!("{}/auth/derive-api-key", self.base_url);
         let response = self
             .http
             .get(&url)
@@ -182,9 +344,55 @@ impl ClobClient {
         self.api_creds = Some(creds);
     }
 
+    /// Fee rate the market charges takers, in basis points. Cached per token
+    /// for a few minutes, and dropped when the CLOB rejects an order over
+    /// its fee rate.
+    pub async fn get_fee_rate_bps(&self, token_id: &str) -> Result<u64, PolymarketError> {
+        if let Some(&(rate, fetched_at)) = self.fee_rates.lock().unwrap().get(token_id) {
+            if fetched_at.elapsed() < FEE_RATE_TTL {
+                return Ok(rate);
+            }
+        }
+
+        let url = format!("{}/fee-rate?token_id={token_id}", self.base_url);
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| PolymarketError::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(PolymarketError::Api(format!(
+                "get fee rate failed: {status} - {text}"
+            )));
+        }
+
+        let fee: FeeRateResponse = response
+            .json()
+            .await
+            .map_err(|e| PolymarketError::Api(format!("parse fee rate failed: {e}")))?;
+
+        self.fee_rates
+            .lock()
+            .unwrap()
+            .insert(token_id.to_string(), (fee.base_fee, Instant::now()));
+        Ok(fee.base_fee)
+    }
+
+    pub fn invalidate_fee_rate(&self, token_id: &str) {
+        self.fee_rates.lock().unwrap().remove(token_id);
+    }
+
     pub async fn create_order(&self, args: OrderArgs) -> Result<SignedOrder, PolymarketError> {
-        let salt = chrono::Utc::now().timestamp_millis() as u128 * 1000;
-        let maker = format!("{:?}", self.funder.unwrap_or(self.address));
+        let salt = next_salt();
+        let fee_rate_bps = match args.fee_rate_bps {
+            Some(rate) => rate,
+            None => self.get_fee_rate_bps(&args.token_id).await?,
+        };
+        let maker = format!("{:?}", self.maker);
         let signer = format!("{:?}", self.address);
 
         let side_int: u8 = match args.side {
@@ -219,11 +427,12 @@ impl ClobClient {
             token_id,
             U256::from(maker_amount),
             U256::from(taker_amount),
-            U256::zero(),
-            U256::zero(),
-            U256::zero(),
+            U256::from(args.expiration),
+            U256::from(args.nonce),
+            U256::from(fee_rate_bps),
             side_int,
-            2u8,
+            self.signature_type.as_u8(),
+            args.neg_risk,
         );
 
         let signature = self
@@ -239,11 +448,11 @@ impl ClobClient {
             token_id: args.token_id,
             maker_amount: maker_amount.to_string(),
             taker_amount: taker_amount.to_string(),
-            expiration: "0".into(),
-            nonce: "0".into(),
-            fee_rate_bps: "0".into(),
+            expiration: args.expiration.to_string(),
+            nonce: args.nonce.to_string(),
+            fee_rate_bps: fee_rate_bps.to_string(),
             side: side_int,
-            signature_type: 2,
+            signature_type: self.signature_type.as_u8(),
             signature: format!("0x{}", hex::encode(signature.to_vec())),
         })
     }
@@ -263,12 +472,13 @@ impl ClobClient {
         fee_rate_bps: U256,
         side: u8,
         signature_type: u8,
+        neg_risk: bool,
     ) -> [u8; 32] {
         let order_type_hash = keccak256(
             b"Order(uint256 salt,address maker,address signer,address taker,uint256 tokenId,uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,uint256 feeRateBps,uint8 side,uint8 signatureType)"
         );
 
-        let domain_separator = self.compute_domain_separator();
+        let domain_separator = self.compute_domain_separator(neg_risk);
 
         let struct_hash = keccak256(ethers::abi::encode(&[
             ethers::abi::Token::FixedBytes(order_type_hash.to_vec()),
@@ -293,14 +503,14 @@ impl ClobClient {
         keccak256(&payload)
     }
 
-    fn compute_domain_separator(&self) -> [u8; 32] {
+    fn compute_domain_separator(&self, neg_risk: bool) -> [u8; 32] {
         let domain_type_hash = keccak256(
             b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
         );
 
         let name_hash = keccak256(b"Polymarket CTF Exchange");
         let version_hash = keccak256(b"1");
-        let contract: Address = CTF_EXCHANGE.parse().unwrap();
+        let contract: Address = exchange_address(neg_risk).parse().unwrap();
 
         keccak256(ethers::abi::encode(&[
             ethers::abi::Token::FixedBytes(domain_type_hash.to_vec()),
@@ -321,27 +531,22 @@ impl ClobClient {
             .as_ref()
             .ok_or_else(|| PolymarketError::Auth("API credentials not set".into()))?;
 
-        let owner = format!("{:?}", self.funder.unwrap_or(self.address));
-        let order_type_str = match order_type {
-            ClobOrderType::Gtc => "GTC",
-            ClobOrderType::Fok => "FOK",
-            ClobOrderType::Ioc => "IOC",
-        };
-
+        let owner = format!("{:?}", self.maker);
         let request = PostOrderRequest {
             order,
             owner,
-            order_type: order_type_str.into(),
+            order_type: order_type.as_str().into(),
         };
 
         let timestamp = chrono::Utc::now().timestamp_millis().to_string();
         let body = serde_json::to_string(&request)
             .map_err(|e| PolymarketError::Api(format!("serialize failed: {e}")))?;
 
+        let token_id = request.order.token_id.clone();
         let sig_payload = format!("POST\n/order\n{timestamp}\n{body}");
         let hmac_sig = self.sign_hmac(&sig_payload, &creds.secret)?;
 
-        let url = format!("{CLOB_URL}/order");
+        let url = format!("{}/order", self.base_url);
         let response = self
             .http
             .post(&url)
@@ -359,6 +564,9 @@ impl ClobClient {
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
+            if text.to_lowercase().contains("fee") {
+                self.invalidate_fee_rate(&token_id);
+            }
             return Err(PolymarketError::Api(format!(
                 "post order failed: {status} - {text}"
             )));
@@ -380,7 +588,7 @@ impl ClobClient {
         let sig_payload = format!("DELETE\n/order/{order_id}\n{timestamp}\n");
         let hmac_sig = self.sign_hmac(&sig_payload, &creds.secret)?;
 
-        let url = format!("{CLOB_URL}/order/{order_id}");
+        let url = format!("{}/order/{order_id}", self.base_url);
         let response = self
             .http
             .delete(&url)
@@ -414,7 +622,7 @@ impl ClobClient {
         let sig_payload = format!("GET\n/order/{order_id}\n{timestamp}\n");
         let hmac_sig = self.sign_hmac(&sig_payload, &creds.secret)?;
 
-        let url = format!("{CLOB_URL}/order/{order_id}");
+        let url = format!("{}/order/{order_id}", self.base_url);
         let response = self
             .http
             .get(&url)
@@ -451,7 +659,7 @@ impl ClobClient {
         let sig_payload = format!("GET\n/orders\n{timestamp}\n");
         let hmac_sig = self.sign_hmac(&sig_payload, &creds.secret)?;
 
-        let url = format!("{CLOB_URL}/orders");
+        let url = format!("{}/orders", self.base_url);
         let response = self
             .http
             .get(&url)
@@ -488,7 +696,7 @@ impl ClobClient {
         let sig_payload = format!("GET\n/balance-allowance?asset_type=COLLATERAL\n{timestamp}\n");
         let hmac_sig = self.sign_hmac(&sig_payload, &creds.secret)?;
 
-        let url = format!("{CLOB_URL}/balance-allowance?asset_type=COLLATERAL");
+        let url = format!("{}/balance-allowance?asset_type=COLLATERAL", self.base_url);
         let response = self
             .http
             .get(&url)
@@ -526,7 +734,7 @@ impl ClobClient {
         let sig_payload = format!("GET\n/balance-allowance?{query}\n{timestamp}\n");
         let hmac_sig = self.sign_hmac(&sig_payload, &creds.secret)?;
 
-        let url = format!("{CLOB_URL}/balance-allowance?{query}");
+        let url = format!("{}/balance-allowance?{query}", self.base_url);
         let response = self
             .http
             .get(&url)
@@ -574,3 +782,307 @@ impl ClobClient {
         Ok(STANDARD.encode(result.into_bytes()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::transaction::eip712::{EIP712Domain, Eip712, TypedData};
+
+    const TEST_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const TEST_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const TOKEN_ID: &str =
+        "71321045679252212594626385532706912750332728571942532289631379312455583992563";
+
+    fn client() -> ClobClient {
+        ClobClient::new(TEST_KEY, None, SignatureType::Eoa).unwrap()
+    }
+
+    fn domain(contract: &str) -> EIP712Domain {
+        EIP712Domain {
+            name: Some("Polymarket CTF Exchange".into()),
+            version: Some("1".into()),
+            chain_id: Some(U256::from(CHAIN_ID)),
+            verifying_contract: Some(contract.parse().unwrap()),
+            salt: None,
+        }
+    }
+
+    fn typed_order(contract: &str) -> TypedData {
+        serde_json::from_value(serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Order": [
+                    { "name": "salt", "type": "uint256" },
+                    { "name": "maker", "type": "address" },
+                    { "name": "signer", "type": "address" },
+                    { "name": "taker", "type": "address" },
+                    { "name": "tokenId", "type": "uint256" },
+                    { "name": "makerAmount", "type": "uint256" },
+                    { "name": "takerAmount", "type": "uint256" },
+                    { "name": "expiration", "type": "uint256" },
+                    { "name": "nonce", "type": "uint256" },
+                    { "name": "feeRateBps", "type": "uint256" },
+                    { "name": "side", "type": "uint8" },
+                    { "name": "signatureType", "type": "uint8" }
+                ]
+            },
+            "primaryType": "Order",
+            "domain": {
+                "name": "Polymarket CTF Exchange",
+                "version": "1",
+                "chainId": CHAIN_ID,
+                "verifyingContract": contract
+            },
+            "message": {
+                "salt": "479249096354",
+                "maker": TEST_ADDRESS,
+                "signer": TEST_ADDRESS,
+                "taker": "0x0000000000000000000000000000000000000000",
+                "tokenId": TOKEN_ID,
+                "makerAmount": "50000000",
+                "takerAmount": "100000000",
+                "expiration": "0",
+                "nonce": "0",
+                "feeRateBps": "0",
+                "side": 0,
+                "signatureType": 0
+            }
+        }))
+        .unwrap()
+    }
+
+    fn order_hash(client: &ClobClient, neg_risk: bool) -> [u8; 32] {
+        let address: Address = TEST_ADDRESS.parse().unwrap();
+        client.compute_order_hash(
+            U256::from(479249096354u64),
+            address,
+            address,
+            Address::zero(),
+            U256::from_dec_str(TOKEN_ID).unwrap(),
+            U256::from(50_000_000u64),
+            U256::from(100_000_000u64),
+            U256::zero(),
+            U256::zero(),
+            U256::zero(),
+            0,
+            0,
+            neg_risk,
+        )
+    }
+
+    #[test]
+    fn test_exchange_address_by_neg_risk() {
+        // given
+        let (standard, neg_risk) = (false, true);
+
+        // when
+        let standard_exchange = exchange_address(standard);
+        let neg_risk_exchange = exchange_address(neg_risk);
+
+        // then
+        assert_eq!(standard_exchange, CTF_EXCHANGE);
+        assert_eq!(neg_risk_exchange, NEG_RISK_CTF_EXCHANGE);
+    }
+
+    #[test]
+    fn test_domain_separator_vectors() {
+        // given
+        let client = client();
+
+        // when
+        let standard = client.compute_domain_separator(false);
+        let neg_risk = client.compute_domain_separator(true);
+
+        // then
+        assert_eq!(
+            hex::encode(standard),
+            "1a573e3617c78403b5b4b892827992f027b03d4eaf570048b8ee8cdd84d151be"
+        );
+        assert_eq!(
+            hex::encode(neg_risk),
+            "82cb6aa85babb812f4b521a12b10f0cbc68d2b44be7bc02c047004f544adb49f"
+        );
+        assert_eq!(standard, domain(CTF_EXCHANGE).separator());
+        assert_eq!(neg_risk, domain(NEG_RISK_CTF_EXCHANGE).separator());
+    }
+
+    #[test]
+    fn test_order_hash_matches_typed_data() {
+        // given
+        let client = client();
+
+        // when
+        let standard = order_hash(&client, false);
+        let neg_risk = order_hash(&client, true);
+
+        // then
+        assert_eq!(
+            hex::encode(standard),
+            "2d4e37d43ce67ac26fd34fbded7ac34fdcba1b2aff632aac52b36483f1d5eeb8"
+        );
+        assert_eq!(
+            hex::encode(neg_risk),
+            "b1d77fd3871ea5e3fff30e907e82b1d19fe9e1bd189db182016c35fa9585d1e5"
+        );
+        assert_eq!(standard, typed_order(CTF_EXCHANGE).encode_eip712().unwrap());
+        assert_eq!(
+            neg_risk,
+            typed_order(NEG_RISK_CTF_EXCHANGE).encode_eip712().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_neg_risk_order_signature_recovers_signer() {
+        // given
+        let client = client();
+        let args = OrderArgs {
+            token_id: TOKEN_ID.into(),
+            price: 0.5,
+            size: 100.0,
+            side: ClobOrderSide::Buy,
+            neg_risk: true,
+            expiration: 1_900_000_000,
+            nonce: 7,
+            fee_rate_bps: Some(0),
+        };
+
+        // when
+        let order = client.create_order(args).await.unwrap();
+
+        // then
+        let hash = client.compute_order_hash(
+            U256::from_dec_str(&order.salt).unwrap(),
+            order.maker.parse().unwrap(),
+            order.signer.parse().unwrap(),
+            Address::zero(),
+            U256::from_dec_str(&order.token_id).unwrap(),
+            U256::from_dec_str(&order.maker_amount).unwrap(),
+            U256::from_dec_str(&order.taker_amount).unwrap(),
+            U256::from_dec_str(&order.expiration).unwrap(),
+            U256::from_dec_str(&order.nonce).unwrap(),
+            U256::from_dec_str(&order.fee_rate_bps).unwrap(),
+            order.side,
+            order.signature_type,
+            true,
+        );
+        assert_eq!(order.expiration, "1900000000");
+        assert_eq!(order.nonce, "7");
+        let signature: Signature = order.signature.parse().unwrap();
+        let recovered = signature.recover(H256::from(hash)).unwrap();
+        assert_eq!(recovered, TEST_ADDRESS.parse::<Address>().unwrap());
+    }
+
+    #[test]
+    fn test_signature_type_requires_funder_for_proxy_and_safe() {
+        // given
+        let types = [SignatureType::PolyProxy, SignatureType::PolyGnosisSafe];
+
+        // when
+        let results: Vec<_> = types
+            .into_iter()
+            .map(|signature_type| ClobClient::new(TEST_KEY, None, signature_type))
+            .collect();
+
+        // then
+        assert!(results
+            .iter()
+            .all(|result| matches!(result, Err(PolymarketError::Config(_)))));
+    }
+
+    #[test]
+    fn test_eoa_rejects_foreign_funder() {
+        // given
+        let funder = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+        // when
+        let foreign = ClobClient::new(TEST_KEY, Some(funder), SignatureType::Eoa);
+        let own = ClobClient::new(TEST_KEY, Some(TEST_ADDRESS), SignatureType::Eoa);
+
+        // then
+        assert!(matches!(foreign, Err(PolymarketError::Config(_))));
+        assert_eq!(
+            own.unwrap().maker(),
+            TEST_ADDRESS.parse::<Address>().unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_proxy_order_maker_is_funder() {
+        // given
+        let funder = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+        let client = ClobClient::new(TEST_KEY, Some(funder), SignatureType::PolyProxy).unwrap();
+        let args = OrderArgs {
+            token_id: TOKEN_ID.into(),
+            price: 0.5,
+            size: 10.0,
+            side: ClobOrderSide::Sell,
+            neg_risk: false,
+            expiration: 0,
+            nonce: 0,
+            fee_rate_bps: Some(0),
+        };
+
+        // when
+        let order = client.create_order(args).await.unwrap();
+
+        // then
+        assert_eq!(
+            order.maker.parse::<Address>().unwrap(),
+            funder.parse::<Address>().unwrap()
+        );
+        assert_eq!(
+            order.signer.parse::<Address>().unwrap(),
+            TEST_ADDRESS.parse::<Address>().unwrap()
+        );
+        assert_eq!(order.signature_type, 1);
+    }
+
+    #[test]
+    fn test_signature_type_from_u8() {
+        // given
+        let codes = [0u8, 1, 2, 3];
+
+        // when
+        let parsed: Vec<_> = codes.into_iter().map(SignatureType::try_from).collect();
+
+        // then
+        assert_eq!(parsed[0].as_ref().unwrap(), &SignatureType::Eoa);
+        assert_eq!(parsed[1].as_ref().unwrap(), &SignatureType::PolyProxy);
+        assert_eq!(parsed[2].as_ref().unwrap(), &SignatureType::PolyGnosisSafe);
+        assert!(parsed[3].is_err());
+    }
+
+    #[test]
+    fn test_salts_unique_within_same_millisecond() {
+        // given
+        let count = 10_000;
+
+        // when
+        let salts: std::collections::HashSet<u64> = (0..count).map(|_| next_salt()).collect();
+
+        // then
+        assert_eq!(salts.len(), count);
+    }
+
+    #[test]
+    fn test_order_type_from_str() {
+        // given
+        let names = ["gtd", "FOK", "GTX"];
+
+        // when
+        let parsed: Vec<_> = names
+            .iter()
+            .map(|name| name.parse::<ClobOrderType>())
+            .collect();
+
+        // then
+        assert!(matches!(parsed[0], Ok(ClobOrderType::Gtd)));
+        assert!(matches!(parsed[1], Ok(ClobOrderType::Fok)));
+        assert!(parsed[2].is_err());
+    }
+}
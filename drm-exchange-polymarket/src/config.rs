@@ -1,15 +1,22 @@
 use drm_core::ExchangeConfig;
 
+use crate::clob::SignatureType;
+
 pub const GAMMA_API_URL: &str = "https://gamma-api.polymarket.com";
 pub const CLOB_API_URL: &str = "https://clob.polymarket.com";
+pub const DATA_API_URL: &str = "https://data-api.polymarket.com";
+pub const POLYGON_RPC_URL: &str = "https://polygon-rpc.com";
 
 #[derive(Debug, Clone)]
 pub struct PolymarketConfig {
     pub base: ExchangeConfig,
     pub gamma_url: String,
     pub clob_url: String,
+    pub data_url: String,
+    pub rpc_url: String,
     pub private_key: Option<String>,
     pub funder: Option<String>,
+    pub signature_type: SignatureType,
     pub chain_id: u64,
 }
 
@@ -19,8 +26,11 @@ impl Default for PolymarketConfig {
             base: ExchangeConfig::default(),
             gamma_url: GAMMA_API_URL.into(),
             clob_url: CLOB_API_URL.into(),
+            data_url: DATA_API_URL.into(),
+            rpc_url: POLYGON_RPC_URL.into(),
             private_key: None,
             funder: None,
+            signature_type: SignatureType::default(),
             chain_id: 137,
         }
     }
@@ -41,6 +51,11 @@ impl PolymarketConfig {
         self
     }
 
+    pub fn with_signature_type(mut self, signature_type: SignatureType) -> Self {
+        self.signature_type = signature_type;
+        self
+    }
+
     pub fn with_verbose(mut self, verbose: bool) -> Self {
         self.base = self.base.with_verbose(verbose);
         self
@@ -56,6 +71,16 @@ impl PolymarketConfig {
         self
     }
 
+    pub fn with_data_url(mut self, url: impl Into<String>) -> Self {
+        self.data_url = url.into();
+        self
+    }
+
+    pub fn with_rpc_url(mut self, url: impl Into<String>) -> Self {
+        self.rpc_url = url.into();
+        self
+    }
+
     pub fn is_authenticated(&self) -> bool {
         self.private_key.is_some()
     }
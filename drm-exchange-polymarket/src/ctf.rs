@@ -0,0 +1,426 @@
+use drm_core::ctf::{
+    encode_call, merge_positions_call, redeem_positions_call, safe_call, send_and_confirm,
+    split_position_call, SignerClient,
+};
+use ethers::abi::{parse_abi, Abi, Token};
+use ethers::prelude::*;
+use std::sync::{Arc, OnceLock};
+
+use crate::clob::SignatureType;
+use crate::error::PolymarketError;
+
+pub const CONDITIONAL_TOKENS: &str = "0x4D97DCd97eC945f40cF65F87097ACe5EA0476045";
+pub const USDC_E: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
+pub const NEG_RISK_ADAPTER: &str = "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296";
+pub const PROXY_WALLET_FACTORY: &str = "0xaB45c5A4B0c941a2F231C04C3f49182e1A254052";
+
+/// Collateral and outcome tokens use 6 decimals.
+pub const CTF_DECIMALS: u32 = 6;
+
+pub use drm_core::ctf::{safe_exec_call, safe_tx_hash, CtfCall};
+
+fn neg_risk_adapter_abi() -> &'static Abi {
+    static ABI: OnceLock<Abi> = OnceLock::new();
+    ABI.get_or_init(|| {
+        parse_abi(&[
+            "function splitPosition(bytes32 conditionId, uint256 amount)",
+            "function mergePositions(bytes32 conditionId, uint256 amount)",
+            "function redeemPositions(bytes32 conditionId, uint256[] amounts)",
+            "function convertPositions(bytes32 marketId, uint256 indexSet, uint256 amount)",
+        ])
+        .expect("valid NegRiskAdapter abi")
+    })
+}
+
+fn proxy_factory_abi() -> &'static Abi {
+    static ABI: OnceLock<Abi> = OnceLock::new();
+    ABI.get_or_init(|| {
+        parse_abi(&[
+            "struct ProxyCall { uint8 typeCode; address to; uint256 value; bytes data; }",
+            "function proxy(ProxyCall[] calls) payable returns (bytes[])",
+        ])
+        .expect("valid ProxyWalletFactory abi")
+    })
+}
+
+/// Converts a share or collateral amount to base units.
+pub fn to_base_units(amount: f64) -> U256 {
+    U256::from((amount * 10f64.powi(CTF_DECIMALS as i32)).round() as u128)
+}
+
+/// Addresses of the contracts CTF operations go through.
+#[derive(Debug, Clone)]
+pub struct CtfContracts {
+    pub conditional_tokens: Address,
+    pub collateral: Address,
+    pub neg_risk_adapter: Address,
+    pub proxy_factory: Address,
+}
+
+impl Default for CtfContracts {
+    fn default() -> Self {
+        Self {
+            conditional_tokens: CONDITIONAL_TOKENS.parse().unwrap(),
+            collateral: USDC_E.parse().unwrap(),
+            neg_risk_adapter: NEG_RISK_ADAPTER.parse().unwrap(),
+            proxy_factory: PROXY_WALLET_FACTORY.parse().unwrap(),
+        }
+    }
+}
+
+impl CtfContracts {
+    /// Locks `amount` collateral into one Yes and one No share per unit.
+    /// Neg-risk markets split through the adapter.
+    pub fn split_position(&self, condition_id: H256, amount: U256, neg_risk: bool) -> CtfCall {
+        if neg_risk {
+            return self.neg_risk_call("splitPosition", condition_id, amount);
+        }
+        split_position_call(
+            self.conditional_tokens,
+            self.collateral,
+            condition_id,
+            amount,
+        )
+    }
+
+    /// Burns `amount` Yes and No shares back into collateral.
+    pub fn merge_positions(&self, condition_id: H256, amount: U256, neg_risk: bool) -> CtfCall {
+        if neg_risk {
+            return self.neg_risk_call("mergePositions", condition_id, amount);
+        }
+        merge_positions_call(
+            self.conditional_tokens,
+            self.collateral,
+            condition_id,
+            amount,
+        )
+    }
+
+    fn neg_risk_call(&self, name: &str, condition_id: H256, amount: U256) -> CtfCall {
+        CtfCall {
+            to: self.neg_risk_adapter,
+            data: encode_call(
+                neg_risk_adapter_abi(),
+                name,
+                &[
+                    Token::FixedBytes(condition_id.0.to_vec()),
+                    Token::Uint(amount),
+                ],
+            ),
+        }
+    }
+
+    /// Redeems every winning share of a resolved condition.
+    pub fn redeem_positions(&self, condition_id: H256) -> CtfCall {
+        redeem_positions_call(self.conditional_tokens, self.collateral, condition_id)
+    }
+
+    /// Redeems neg-risk shares; `amounts` are the Yes and No balances.
+    pub fn redeem_neg_risk_positions(&self, condition_id: H256, amounts: [U256; 2]) -> CtfCall {
+        CtfCall {
+            to: self.neg_risk_adapter,
+            data: encode_call(
+                neg_risk_adapter_abi(),
+                "redeemPositions",
+                &[
+                    Token::FixedBytes(condition_id.0.to_vec()),
+                    Token::Array(amounts.iter().map(|a| Token::Uint(*a)).collect()),
+                ],
+            ),
+        }
+    }
+
+    /// Converts No shares of the questions in `index_set` into collateral
+    /// plus Yes shares of the remaining questions of a neg-risk market.
+    pub fn convert_positions(&self, market_id: H256, index_set: U256, amount: U256) -> CtfCall {
+        CtfCall {
+            to: self.neg_risk_adapter,
+            data: encode_call(
+                neg_risk_adapter_abi(),
+                "convertPositions",
+                &[
+                    Token::FixedBytes(market_id.0.to_vec()),
+                    Token::Uint(index_set),
+                    Token::Uint(amount),
+                ],
+            ),
+        }
+    }
+
+    /// Routes `call` through the caller's Polymarket proxy wallet.
+    pub fn proxy_call(&self, call: &CtfCall) -> CtfCall {
+        CtfCall {
+            to: self.proxy_factory,
+            data: encode_call(
+                proxy_factory_abi(),
+                "proxy",
+                &[Token::Array(vec![Token::Tuple(vec![
+                    Token::Uint(U256::one()),
+                    Token::Address(call.to),
+                    Token::Uint(U256::zero()),
+                    Token::Bytes(call.data.to_vec()),
+                ])])],
+            ),
+        }
+    }
+}
+
+/// Sends CTF transactions for the funder wallet. EOA funders call the
+/// contracts directly; proxy funders go through the proxy wallet factory
+/// and Safe funders through `execTransaction` signed by the key (a 1-of-1
+/// owner, as Polymarket deploys them).
+pub struct CtfClient {
+    client: Arc<SignerClient>,
+    funder: Address,
+    signature_type: SignatureType,
+    chain_id: u64,
+    contracts: CtfContracts,
+}
+
+impl CtfClient {
+    pub fn new(
+        rpc_url: &str,
+        private_key: &str,
+        funder: Option<&str>,
+        signature_type: SignatureType,
+        chain_id: u64,
+    ) -> Result<Self, PolymarketError> {
+        let wallet: LocalWallet = private_key
+            .parse()
+            .map_err(|e| PolymarketError::Config(format!("invalid private key: {e}")))?;
+        let wallet = wallet.with_chain_id(chain_id);
+        let funder = signature_type.resolve_maker(wallet.address(), funder)?;
+
+        let provider = Provider::<Http>::try_from(rpc_url)
+            .map_err(|e| PolymarketError::Config(format!("invalid rpc url: {e}")))?;
+
+        Ok(Self {
+            client: Arc::new(SignerMiddleware::new(provider, wallet)),
+            funder,
+            signature_type,
+            chain_id,
+            contracts: CtfContracts::default(),
+        })
+    }
+
+    /// Overrides contract addresses, e.g. mocks deployed on a local chain.
+    pub fn with_contracts(mut self, contracts: CtfContracts) -> Self {
+        self.contracts = contracts;
+        self
+    }
+
+    pub fn funder(&self) -> Address {
+        self.funder
+    }
+
+    pub fn contracts(&self) -> &CtfContracts {
+        &self.contracts
+    }
+
+    pub async fn split(
+        &self,
+        condition_id: H256,
+        amount: U256,
+        neg_risk: bool,
+    ) -> Result<TransactionReceipt, PolymarketError> {
+        let call = self
+            .contracts
+            .split_position(condition_id, amount, neg_risk);
+        self.execute(&call).await
+    }
+
+    pub async fn merge(
+        &self,
+        condition_id: H256,
+        amount: U256,
+        neg_risk: bool,
+    ) -> Result<TransactionReceipt, PolymarketError> {
+        let call = self
+            .contracts
+            .merge_positions(condition_id, amount, neg_risk);
+        self.execute(&call).await
+    }
+
+    pub async fn redeem(&self, condition_id: H256) -> Result<TransactionReceipt, PolymarketError> {
+        let call = self.contracts.redeem_positions(condition_id);
+        self.execute(&call).await
+    }
+
+    pub async fn redeem_neg_risk(
+        &self,
+        condition_id: H256,
+        amounts: [U256; 2],
+    ) -> Result<TransactionReceipt, PolymarketError> {
+        let call = self
+            .contracts
+            .redeem_neg_risk_positions(condition_id, amounts);
+        self.execute(&call).await
+    }
+
+    pub async fn convert(
+        &self,
+        market_id: H256,
+        index_set: U256,
+        amount: U256,
+    ) -> Result<TransactionReceipt, PolymarketError> {
+        let call = self
+            .contracts
+            .convert_positions(market_id, index_set, amount);
+        self.execute(&call).await
+    }
+
+    /// Sends `call` on behalf of the funder and waits for the receipt.
+    /// Reverted transactions are reported as errors.
+    pub async fn execute(&self, call: &CtfCall) -> Result<TransactionReceipt, PolymarketError> {
+        let call = match self.signature_type {
+            SignatureType::Eoa => call.clone(),
+            SignatureType::PolyProxy => self.contracts.proxy_call(call),
+            SignatureType::PolyGnosisSafe => {
+                safe_call(&self.client, self.funder, self.chain_id, call).await?
+            }
+        };
+
+        let tx = Eip1559TransactionRequest::new()
+            .to(call.to)
+            .data(call.data)
+            .from(self.client.address());
+
+        Ok(send_and_confirm(&self.client, tx.into()).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use drm_core::ctf::{binary_partition, conditional_tokens_abi};
+
+    const TEST_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const CONDITION_ID: &str = "0x5e5c9b3b5bd63ab94e0d1b1e3d4f11d0b23c2c6b1f0b46c9e0e0df7c36d3d2a1";
+
+    fn condition_id() -> H256 {
+        CONDITION_ID.parse().unwrap()
+    }
+
+    fn selector(call: &CtfCall) -> String {
+        hex::encode(&call.data[..4])
+    }
+
+    #[test]
+    fn test_conditional_tokens_selectors() {
+        // given
+        let contracts = CtfContracts::default();
+
+        // when
+        let split = contracts.split_position(condition_id(), U256::from(1_000_000), false);
+        let merge = contracts.merge_positions(condition_id(), U256::from(1_000_000), false);
+        let redeem = contracts.redeem_positions(condition_id());
+
+        // then
+        assert_eq!(selector(&split), "72ce4275");
+        assert_eq!(selector(&merge), "9e7212ad");
+        assert_eq!(selector(&redeem), "01b7037c");
+        assert_eq!(split.to, CONDITIONAL_TOKENS.parse::<Address>().unwrap());
+    }
+
+    #[test]
+    fn test_split_encodes_binary_partition() {
+        // given
+        let contracts = CtfContracts::default();
+
+        // when
+        let call = contracts.split_position(condition_id(), to_base_units(2.5), false);
+
+        // then
+        let tokens = conditional_tokens_abi()
+            .function("splitPosition")
+            .unwrap()
+            .decode_input(&call.data[4..])
+            .unwrap();
+        assert_eq!(tokens[0], Token::Address(USDC_E.parse().unwrap()));
+        assert_eq!(tokens[1], Token::FixedBytes(vec![0; 32]));
+        assert_eq!(tokens[2], Token::FixedBytes(condition_id().0.to_vec()));
+        assert_eq!(tokens[3], binary_partition());
+        assert_eq!(tokens[4], Token::Uint(U256::from(2_500_000)));
+    }
+
+    #[test]
+    fn test_neg_risk_operations_go_through_adapter() {
+        // given
+        let contracts = CtfContracts::default();
+        let adapter: Address = NEG_RISK_ADAPTER.parse().unwrap();
+
+        // when
+        let split = contracts.split_position(condition_id(), U256::from(5), true);
+        let redeem =
+            contracts.redeem_neg_risk_positions(condition_id(), [U256::from(3), U256::zero()]);
+        let convert = contracts.convert_positions(condition_id(), U256::from(0b101), U256::from(7));
+
+        // then
+        assert_eq!(split.to, adapter);
+        assert_eq!(redeem.to, adapter);
+        assert_eq!(convert.to, adapter);
+        let tokens = neg_risk_adapter_abi()
+            .function("convertPositions")
+            .unwrap()
+            .decode_input(&convert.data[4..])
+            .unwrap();
+        assert_eq!(tokens[1], Token::Uint(U256::from(5)));
+        assert_eq!(tokens[2], Token::Uint(U256::from(7)));
+    }
+
+    #[test]
+    fn test_proxy_call_wraps_inner_call() {
+        // given
+        let contracts = CtfContracts::default();
+        let inner = contracts.merge_positions(condition_id(), U256::from(10), false);
+
+        // when
+        let call = contracts.proxy_call(&inner);
+
+        // then
+        assert_eq!(call.to, PROXY_WALLET_FACTORY.parse::<Address>().unwrap());
+        let tokens = proxy_factory_abi()
+            .function("proxy")
+            .unwrap()
+            .decode_input(&call.data[4..])
+            .unwrap();
+        assert_eq!(
+            tokens[0],
+            Token::Array(vec![Token::Tuple(vec![
+                Token::Uint(U256::one()),
+                Token::Address(inner.to),
+                Token::Uint(U256::zero()),
+                Token::Bytes(inner.data.to_vec()),
+            ])])
+        );
+    }
+
+    #[test]
+    fn test_client_requires_funder_for_safe() {
+        // given / when
+        let result = CtfClient::new(
+            "http://localhost:8545",
+            TEST_KEY,
+            None,
+            SignatureType::PolyGnosisSafe,
+            137,
+        );
+
+        // then
+        assert!(matches!(result, Err(PolymarketError::Config(_))));
+    }
+
+    #[test]
+    fn test_to_base_units_rounds_to_six_decimals() {
+        // given
+        let (whole, fraction) = (1.0, 0.1234567);
+
+        // when
+        let whole_units = to_base_units(whole);
+        let fraction_units = to_base_units(fraction);
+
+        // then
+        assert_eq!(whole_units, U256::from(1_000_000));
+        assert_eq!(fraction_units, U256::from(123_457));
+    }
+}
@@ -0,0 +1,44 @@
+use drm_core::Position;
+use serde::{Deserialize, Serialize};
+
+/// A wallet position as reported by the Polymarket data API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PolymarketPosition {
+    pub proxy_wallet: String,
+    pub asset: String,
+    pub condition_id: String,
+    pub size: f64,
+    pub avg_price: f64,
+    pub initial_value: f64,
+    pub current_value: f64,
+    pub cash_pnl: f64,
+    pub percent_pnl: f64,
+    pub total_bought: f64,
+    pub realized_pnl: f64,
+    pub percent_realized_pnl: f64,
+    pub cur_price: f64,
+    pub redeemable: bool,
+    pub mergeable: bool,
+    pub title: String,
+    pub slug: String,
+    pub event_slug: String,
+    pub outcome: String,
+    pub outcome_index: u32,
+    pub opposite_outcome: String,
+    pub opposite_asset: String,
+    pub end_date: Option<String>,
+    pub negative_risk: bool,
+}
+
+impl PolymarketPosition {
+    pub fn to_position(&self, market_id: impl Into<String>) -> Position {
+        Position {
+            market_id: market_id.into(),
+            outcome: self.outcome.clone(),
+            size: self.size,
+            average_price: self.avg_price,
+            current_price: self.cur_price,
+        }
+    }
+}
@@ -31,6 +31,9 @@ pub enum PolymarketError {
 
     #[error("signing error: {0}")]
     Signing(String),
+
+    #[error("transaction error: {0}")]
+    Transaction(String),
 }
 
 impl From<PolymarketError> for drm_core::ExchangeError {
@@ -45,3 +48,12 @@ impl From<PolymarketError> for drm_core::ExchangeError {
         }
     }
 }
+
+impl From<drm_core::CtfError> for PolymarketError {
+    fn from(err: drm_core::CtfError) -> Self {
+        match err {
+            drm_core::CtfError::Transaction(msg) => PolymarketError::Transaction(msg),
+            drm_core::CtfError::Signing(msg) => PolymarketError::Signing(msg),
+        }
+    }
+}
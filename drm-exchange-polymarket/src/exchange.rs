@@ -1,21 +1,23 @@
 use async_trait::async_trait;
+use ethers::types::H256;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tokio::sync::Mutex;
 
 use drm_core::{
-    normalize_token_symbol, CryptoHourlyMarket, CryptoMarketType, DrmError, Exchange, ExchangeInfo,
-    FetchMarketsParams, FetchOrdersParams, Market, MarketDirection, Nav, Order, OrderSide,
-    OrderStatus, Orderbook, Position, PriceHistoryInterval, PriceLevel, PricePoint, PublicTrade,
-    RateLimiter,
+    normalize_token_symbol, CompleteSetConverter, CryptoHourlyMarket, DrmError, Exchange,
+    ExchangeInfo, FetchMarketsParams, FetchOrdersParams, Market, Nav, Order, OrderSide,
+    OrderStatus, Orderbook, OrderbookSource, Position, PriceHistoryInterval, PriceLevel,
+    PricePoint, PublicTrade, RateLimiter,
 };
-use regex::Regex;
 
 use crate::client::HttpClient;
 use crate::clob::{
     ApiCredentials, ClobClient, ClobOrderData, ClobOrderSide, ClobOrderType, OrderArgs,
 };
 use crate::config::PolymarketConfig;
+use crate::ctf::{to_base_units, CtfClient};
+use crate::data::PolymarketPosition;
 use crate::error::PolymarketError;
 use crate::websocket::PolymarketWebSocket;
 
@@ -24,6 +26,11 @@ pub struct Polymarket {
     client: HttpClient,
     rate_limiter: Arc<Mutex<RateLimiter>>,
     clob_client: Option<Arc<Mutex<ClobClient>>>,
+    ctf_client: Option<Arc<CtfClient>>,
+    /// negRisk flags seen in market metadata, keyed by market id and token id.
+    neg_risk: RwLock<HashMap<String, bool>>,
+    /// Gamma market ids seen in market metadata, keyed by condition id.
+    market_ids: RwLock<HashMap<String, String>>,
 }
 
 impl Polymarket {
@@ -34,17 +41,33 @@ impl Polymarket {
         )));
 
         let clob_client = if let Some(ref private_key) = config.private_key {
-            let clob = ClobClient::new(private_key, config.funder.as_deref())?;
+            let clob =
+                ClobClient::new(private_key, config.funder.as_deref(), config.signature_type)?
+                    .with_base_url(&config.clob_url);
             Some(Arc::new(Mutex::new(clob)))
         } else {
             None
         };
 
+        let ctf_client = match config.private_key {
+            Some(ref private_key) => Some(Arc::new(CtfClient::new(
+                &config.rpc_url,
+                private_key,
+                config.funder.as_deref(),
+                config.signature_type,
+                config.chain_id,
+            )?)),
+            None => None,
+        };
+
         Ok(Self {
             config,
             client,
             rate_limiter,
             clob_client,
+            ctf_client,
+            neg_risk: RwLock::new(HashMap::new()),
+            market_ids: RwLock::new(HashMap::new()),
         })
     }
 
@@ -73,6 +96,30 @@ impl Polymarket {
         Ok(())
     }
 
+    /// On-chain split/merge/redeem/convert client for the funder wallet.
+    pub fn ctf(&self) -> Result<&CtfClient, PolymarketError> {
+        self.ctf_client
+            .as_deref()
+            .ok_or_else(|| PolymarketError::Auth("private key required for CTF operations".into()))
+    }
+
+    /// Condition id and neg-risk flag of a market, as needed on chain.
+    pub async fn fetch_condition(&self, market_id: &str) -> Result<(H256, bool), DrmError> {
+        let market = self.fetch_market(market_id).await?;
+        let condition_id = market
+            .metadata
+            .get("conditionId")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<H256>().ok())
+            .ok_or_else(|| {
+                DrmError::Exchange(drm_core::ExchangeError::Api(format!(
+                    "market {market_id} has no condition id"
+                )))
+            })?;
+
+        Ok((condition_id, Self::is_neg_risk(&market)))
+    }
+
     async fn rate_limit(&self) {
         self.rate_limiter.lock().await.wait().await;
     }
@@ -355,7 +402,7 @@ impl Polymarket {
             }
         }
 
-        points.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        points.sort_by_key(|p| p.timestamp);
 
         Ok(points)
     }
@@ -422,7 +469,6 @@ impl Polymarket {
     ) -> Result<Vec<PublicTrade>, PolymarketError> {
         self.rate_limit().await;
 
-        const DATA_API_URL: &str = "https://data-api.polymarket.com";
         const PAGE_SIZE: usize = 500;
 
         let total_limit = limit.unwrap_or(100);
@@ -439,7 +485,8 @@ impl Polymarket {
             }
 
             let mut url = format!(
-                "{DATA_API_URL}/trades?limit={page_limit}&offset={current_offset}&takerOnly={taker}"
+                "{}/trades?limit={page_limit}&offset={current_offset}&takerOnly={taker}",
+                self.config.data_url
             );
 
             if let Some(m) = market {
@@ -603,6 +650,52 @@ impl Polymarket {
         })
     }
 
+    /// Wallet whose positions are reported: the configured funder, or the
+    /// order maker derived from the private key.
+    async fn positions_user(&self) -> Result<String, PolymarketError> {
+        if let Some(funder) = &self.config.funder {
+            return Ok(funder.clone());
+        }
+        match &self.clob_client {
+            Some(clob) => Ok(format!("{:?}", clob.lock().await.maker())),
+            None => Err(PolymarketError::AuthRequired),
+        }
+    }
+
+    /// Positions held by the funder wallet, from the data API. Filters to
+    /// the given condition ids when non-empty; otherwise lists every market.
+    pub async fn fetch_data_positions(
+        &self,
+        condition_ids: &[String],
+    ) -> Result<Vec<PolymarketPosition>, PolymarketError> {
+        const PAGE_SIZE: usize = 500;
+
+        let user = self.positions_user().await?;
+        let mut positions = Vec::new();
+        let mut offset = 0usize;
+
+        loop {
+            self.rate_limit().await;
+
+            let mut endpoint =
+                format!("/positions?user={user}&sizeThreshold=0&limit={PAGE_SIZE}&offset={offset}");
+            if !condition_ids.is_empty() {
+                endpoint.push_str(&format!("&market={}", condition_ids.join(",")));
+            }
+
+            let page: Vec<PolymarketPosition> = self.client.get_data(&endpoint).await?;
+            let count = page.len();
+            positions.extend(page.into_iter().filter(|p| p.size > 0.0));
+
+            if count < PAGE_SIZE {
+                break;
+            }
+            offset += count;
+        }
+
+        Ok(positions)
+    }
+
     pub async fn fetch_positions_for_market(
         &self,
         market: &Market,
@@ -681,14 +774,6 @@ impl Polymarket {
             }
         }
 
-        let up_down_pattern =
-            Regex::new(r"(?i)(?P<token>Bitcoin|Ethereum|Solana|BTC|ETH|SOL|XRP)\s+Up or Down")
-                .unwrap();
-
-        let strike_pattern = Regex::new(
-            r"(?i)(?:(?P<token1>BTC|ETH|SOL|BITCOIN|ETHEREUM|SOLANA)\s+.*?(?P<direction>above|below|over|under|reach)\s+[\$]?(?P<price1>[\d,]+(?:\.\d+)?))|(?:[\$]?(?P<price2>[\d,]+(?:\.\d+)?)\s+.*?(?P<token2>BTC|ETH|SOL|BITCOIN|ETHEREUM|SOLANA))"
-        ).unwrap();
-
         let now = chrono::Utc::now();
 
         for market in all_markets {
@@ -716,74 +801,22 @@ impl Polymarket {
                 }
             }
 
-            if let Some(caps) = up_down_pattern.captures(&market.question) {
-                let parsed_token = normalize_token_symbol(caps.name("token").unwrap().as_str());
-
-                if let Some(filter) = token_symbol {
-                    if parsed_token != normalize_token_symbol(filter) {
-                        continue;
-                    }
-                }
-
-                let expiry = market
-                    .close_time
-                    .unwrap_or_else(|| now + chrono::Duration::hours(1));
-
-                let crypto_market = CryptoHourlyMarket {
-                    token_symbol: parsed_token,
-                    expiry_time: expiry,
-                    strike_price: None,
-                    direction: None,
-                    market_type: Some(CryptoMarketType::UpDown),
-                };
-
-                return Ok(Some((market, crypto_market)));
-            }
-
-            if let Some(caps) = strike_pattern.captures(&market.question) {
-                let token_str = caps
-                    .name("token1")
-                    .or_else(|| caps.name("token2"))
-                    .map(|m| m.as_str())
-                    .unwrap_or("");
-
-                let parsed_token = normalize_token_symbol(token_str);
+            let expiry = market
+                .close_time
+                .unwrap_or_else(|| now + chrono::Duration::hours(1));
 
-                let price_str = caps
-                    .name("price1")
-                    .or_else(|| caps.name("price2"))
-                    .map(|m| m.as_str())
-                    .unwrap_or("0");
-
-                let parsed_price: f64 = price_str.replace(',', "").parse().unwrap_or(0.0);
+            let crypto_market = match CryptoHourlyMarket::parse(&market.question, expiry) {
+                Some(crypto_market) => crypto_market,
+                None => continue,
+            };
 
-                if let Some(filter) = token_symbol {
-                    if parsed_token != normalize_token_symbol(filter) {
-                        continue;
-                    }
+            if let Some(filter) = token_symbol {
+                if crypto_market.token_symbol != normalize_token_symbol(filter) {
+                    continue;
                 }
-
-                let expiry = market
-                    .close_time
-                    .unwrap_or_else(|| now + chrono::Duration::hours(1));
-
-                let direction =
-                    caps.name("direction")
-                        .map(|m| match m.as_str().to_lowercase().as_str() {
-                            "above" | "over" | "reach" => MarketDirection::Up,
-                            _ => MarketDirection::Down,
-                        });
-
-                let crypto_market = CryptoHourlyMarket {
-                    token_symbol: parsed_token,
-                    expiry_time: expiry,
-                    strike_price: Some(parsed_price),
-                    direction,
-                    market_type: Some(CryptoMarketType::StrikePrice),
-                };
-
-                return Ok(Some((market, crypto_market)));
             }
+
+            return Ok(Some((market, crypto_market)));
         }
 
         Ok(None)
@@ -844,6 +877,153 @@ impl Polymarket {
             .map_err(|e| PolymarketError::Api(e.to_string()))
     }
 
+    /// Whether the market settles through the Neg Risk CTF Exchange.
+    pub fn is_neg_risk(market: &Market) -> bool {
+        match market.metadata.get("negRisk") {
+            Some(serde_json::Value::Bool(b)) => *b,
+            Some(serde_json::Value::String(s)) => s.eq_ignore_ascii_case("true"),
+            _ => false,
+        }
+    }
+
+    /// Resolves the neg-risk flag for an order: explicit `neg_risk` param,
+    /// then cached market metadata, then a market lookup. Errors if the
+    /// lookup fails rather than guessing.
+    async fn resolve_neg_risk(
+        &self,
+        market_id: &str,
+        token_id: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<bool, DrmError> {
+        if let Some(flag) = params.get("neg_risk") {
+            return Ok(flag.eq_ignore_ascii_case("true"));
+        }
+
+        let cached = self.neg_risk.read().ok().and_then(|cache| {
+            cache
+                .get(token_id)
+                .or_else(|| cache.get(market_id))
+                .copied()
+        });
+        if let Some(flag) = cached {
+            return Ok(flag);
+        }
+
+        // Signing against the wrong exchange contract gets the order
+        // rejected, so refuse to guess.
+        let market = self.fetch_market(market_id).await.map_err(|e| {
+            DrmError::Exchange(drm_core::ExchangeError::InvalidOrder(format!(
+                "cannot resolve negRisk for {market_id}, pass the neg_risk param: {e}"
+            )))
+        })?;
+        Ok(Self::is_neg_risk(&market))
+    }
+
+    fn u64_param(params: &HashMap<String, String>, key: &str) -> Result<Option<u64>, DrmError> {
+        params
+            .get(key)
+            .map(|v| {
+                v.parse().map_err(|_| {
+                    DrmError::Exchange(drm_core::ExchangeError::InvalidOrder(format!(
+                        "invalid {key}: {v}"
+                    )))
+                })
+            })
+            .transpose()
+    }
+
+    /// GTD orders need an expiration at least a minute out (the CLOB's
+    /// security threshold); other order types must not carry one.
+    fn validate_expiration(order_type: &ClobOrderType, expiration: u64) -> Result<(), DrmError> {
+        let invalid = |msg: String| DrmError::Exchange(drm_core::ExchangeError::InvalidOrder(msg));
+
+        match order_type {
+            ClobOrderType::Gtd => {
+                let min = chrono::Utc::now().timestamp() as u64 + 60;
+                if expiration < min {
+                    return Err(invalid(format!(
+                        "GTD expiration must be at least {min}, got {expiration}"
+                    )));
+                }
+            }
+            _ if expiration != 0 => {
+                return Err(invalid(format!(
+                    "expiration is only supported for GTD orders, got {}",
+                    order_type.as_str()
+                )));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn cache_neg_risk(&self, market: &Market) {
+        let flag = Self::is_neg_risk(market);
+        if let Ok(mut cache) = self.neg_risk.write() {
+            cache.insert(market.id.clone(), flag);
+            for token_id in market.get_token_ids() {
+                cache.insert(token_id, flag);
+            }
+        }
+    }
+
+    fn cache_market_id(&self, market: &Market) {
+        let Some(condition_id) = market.metadata.get("conditionId").and_then(|v| v.as_str()) else {
+            return;
+        };
+        if let Ok(mut cache) = self.market_ids.write() {
+            cache.insert(condition_id.to_string(), market.id.clone());
+        }
+    }
+
+    /// Maps condition ids to Gamma market ids, looking up the ones not seen
+    /// yet. Condition ids Gamma does not know map to themselves.
+    async fn resolve_market_ids(
+        &self,
+        condition_ids: &[String],
+    ) -> Result<HashMap<String, String>, DrmError> {
+        const CHUNK_SIZE: usize = 50;
+
+        let missing: Vec<&String> = {
+            let cache = self.market_ids.read().unwrap();
+            let mut missing: Vec<&String> = condition_ids
+                .iter()
+                .filter(|id| !cache.contains_key(*id))
+                .collect();
+            missing.sort();
+            missing.dedup();
+            missing
+        };
+
+        for chunk in missing.chunks(CHUNK_SIZE) {
+            self.rate_limit().await;
+            let query: Vec<String> = chunk
+                .iter()
+                .map(|id| format!("condition_ids={id}"))
+                .collect();
+            let endpoint = format!("/markets?limit={}&{}", chunk.len(), query.join("&"));
+            let data: Vec<serde_json::Value> = self
+                .client
+                .get_gamma(&endpoint)
+                .await
+                .map_err(|e| DrmError::Exchange(e.into()))?;
+            for value in data {
+                self.parse_market(value);
+            }
+        }
+
+        let cache = self.market_ids.read().unwrap();
+        Ok(condition_ids
+            .iter()
+            .map(|id| {
+                (
+                    id.clone(),
+                    cache.get(id).cloned().unwrap_or_else(|| id.clone()),
+                )
+            })
+            .collect())
+    }
+
     fn parse_market(&self, data: serde_json::Value) -> Option<Market> {
         let obj = data.as_object()?;
 
@@ -926,7 +1106,7 @@ impl Polymarket {
             .unwrap_or("")
             .to_string();
 
-        Some(Market {
+        let market = Market {
             id,
             question,
             outcomes,
@@ -937,7 +1117,11 @@ impl Polymarket {
             metadata: data,
             tick_size,
             description,
-        })
+        };
+        self.cache_neg_risk(&market);
+        self.cache_market_id(&market);
+
+        Some(market)
     }
 }
 
@@ -1068,26 +1252,36 @@ impl Exchange for Polymarket {
             .cloned()
             .unwrap_or_else(|| format!("{market_id}:{outcome}"));
 
-        let order_type_str = params
+        let order_type: ClobOrderType = params
             .get("order_type")
             .map(|s| s.as_str())
-            .unwrap_or("GTC");
-        let order_type = match order_type_str.to_uppercase().as_str() {
-            "FOK" => ClobOrderType::Fok,
-            "IOC" => ClobOrderType::Ioc,
-            _ => ClobOrderType::Gtc,
-        };
+            .unwrap_or("GTC")
+            .parse()
+            .map_err(|e: PolymarketError| {
+                DrmError::Exchange(drm_core::ExchangeError::InvalidOrder(e.to_string()))
+            })?;
+
+        let expiration = Self::u64_param(&params, "expiration")?.unwrap_or(0);
+        Self::validate_expiration(&order_type, expiration)?;
+        let nonce = Self::u64_param(&params, "nonce")?.unwrap_or(0);
+        let fee_rate_bps = Self::u64_param(&params, "fee_rate_bps")?;
 
         let clob_side = match side {
             OrderSide::Buy => ClobOrderSide::Buy,
             OrderSide::Sell => ClobOrderSide::Sell,
         };
 
+        let neg_risk = self.resolve_neg_risk(market_id, &token_id, &params).await?;
+
         let args = OrderArgs {
             token_id: token_id.clone(),
             price,
             size,
             side: clob_side,
+            neg_risk,
+            expiration,
+            nonce,
+            fee_rate_bps,
         };
 
         let clob = clob.lock().await;
@@ -1192,70 +1386,36 @@ impl Exchange for Polymarket {
         Ok(orders.iter().map(|o| self.parse_clob_order(o)).collect())
     }
 
+    /// Positions for one market, or across all markets (keyed by condition
+    /// id) when `market_id` is `None`.
+    /// Positions carry the Gamma market id, as used by `fetch_market`, on
+    /// both the all-markets and the single-market path.
     async fn fetch_positions(&self, market_id: Option<&str>) -> Result<Vec<Position>, DrmError> {
-        let clob = self.clob_client.as_ref().ok_or_else(|| {
-            DrmError::Exchange(drm_core::ExchangeError::Authentication(
-                "private key required".into(),
-            ))
-        })?;
-
-        let market_id = match market_id {
-            Some(id) => id,
-            None => return Ok(vec![]),
+        let Some(market_id) = market_id else {
+            let positions = self
+                .fetch_data_positions(&[])
+                .await
+                .map_err(|e| DrmError::Exchange(e.into()))?;
+            let condition_ids: Vec<String> =
+                positions.iter().map(|p| p.condition_id.clone()).collect();
+            let market_ids = self.resolve_market_ids(&condition_ids).await?;
+            return Ok(positions
+                .iter()
+                .map(|p| p.to_position(market_ids[&p.condition_id].clone()))
+                .collect());
         };
 
         let market = self.fetch_market(market_id).await?;
-
-        let token_ids: Vec<String> = market
-            .metadata
-            .get("clobTokenIds")
-            .and_then(|v| {
-                if let Some(arr) = v.as_array() {
-                    Some(
-                        arr.iter()
-                            .filter_map(|x| x.as_str().map(String::from))
-                            .collect(),
-                    )
-                } else if let Some(s) = v.as_str() {
-                    serde_json::from_str(s).ok()
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_default();
-
-        if token_ids.is_empty() {
+        let Some(condition_id) = market.metadata.get("conditionId").and_then(|v| v.as_str()) else {
             return Ok(vec![]);
-        }
-
-        let mut positions = Vec::new();
-        let clob = clob.lock().await;
-
-        for (i, token_id) in token_ids.iter().enumerate() {
-            let balance = clob.get_token_balance(token_id).await.unwrap_or(0.0);
-
-            if balance > 0.0 {
-                let outcome = market.outcomes.get(i).cloned().unwrap_or_else(|| {
-                    if i == 0 {
-                        "Yes".into()
-                    } else {
-                        "No".into()
-                    }
-                });
-
-                let current_price = market.prices.get(&outcome).copied().unwrap_or(0.0);
+        };
 
-                positions.push(Position {
-                    market_id: market_id.to_string(),
-                    outcome,
-                    size: balance,
-                    average_price: 0.0,
-                    current_price,
-                });
-            }
-        }
+        let positions = self
+            .fetch_data_positions(&[condition_id.to_string()])
+            .await
+            .map_err(|e| DrmError::Exchange(e.into()))?;
 
-        Ok(positions)
+        Ok(positions.iter().map(|p| p.to_position(market_id)).collect())
     }
 
     async fn fetch_balance(&self) -> Result<HashMap<String, f64>, DrmError> {
@@ -1293,3 +1453,39 @@ impl Exchange for Polymarket {
         }
     }
 }
+
+#[async_trait]
+impl OrderbookSource for Polymarket {
+    async fn fetch_book(&self, token_id: &str) -> Result<Orderbook, DrmError> {
+        self.get_orderbook(token_id)
+            .await
+            .map_err(|e| DrmError::Exchange(e.into()))
+    }
+}
+
+#[async_trait]
+impl CompleteSetConverter for Polymarket {
+    async fn split(&self, market_id: &str, amount: f64) -> Result<String, DrmError> {
+        let ctf = self.ctf().map_err(|e| DrmError::Exchange(e.into()))?;
+        let (condition_id, neg_risk) = self.fetch_condition(market_id).await?;
+
+        let receipt = ctf
+            .split(condition_id, to_base_units(amount), neg_risk)
+            .await
+            .map_err(|e| DrmError::Exchange(e.into()))?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+
+    async fn merge(&self, market_id: &str, amount: f64) -> Result<String, DrmError> {
+        let ctf = self.ctf().map_err(|e| DrmError::Exchange(e.into()))?;
+        let (condition_id, neg_risk) = self.fetch_condition(market_id).await?;
+
+        let receipt = ctf
+            .merge(condition_id, to_base_units(amount), neg_risk)
+            .await
+            .map_err(|e| DrmError::Exchange(e.into()))?;
+
+        Ok(format!("{:?}", receipt.transaction_hash))
+    }
+}
@@ -1,6 +1,8 @@
 mod client;
 mod clob;
 mod config;
+mod ctf;
+mod data;
 mod error;
 mod exchange;
 mod websocket;
@@ -8,6 +10,8 @@ mod websocket;
 pub use client::*;
 pub use clob::*;
 pub use config::*;
+pub use ctf::*;
+pub use data::*;
 pub use error::*;
 pub use exchange::*;
 pub use websocket::*;
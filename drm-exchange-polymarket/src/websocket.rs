@@ -7,7 +7,8 @@ use tokio::time::{interval, Duration};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 use drm_core::{
-    OrderBookWebSocket, Orderbook, OrderbookStream, PriceLevel, WebSocketError, WebSocketState,
+    BookSide, L2Book, OrderBookWebSocket, Orderbook, OrderbookManager, OrderbookStream, PriceLevel,
+    WebSocketError, WebSocketState,
 };
 
 const WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
@@ -44,6 +45,9 @@ struct WsPriceLevel {
 #[derive(Debug, Clone, serde::Deserialize)]
 struct WsPriceChange {
     asset_id: String,
+    price: Option<String>,
+    size: Option<String>,
+    side: Option<String>,
     best_bid: Option<String>,
     best_ask: Option<String>,
 }
@@ -54,7 +58,8 @@ pub struct PolymarketWebSocket {
     state: Arc<RwLock<WebSocketState>>,
     subscriptions: Arc<RwLock<HashMap<String, Vec<String>>>>,
     orderbook_senders: Arc<RwLock<HashMap<String, OrderbookSender>>>,
-    orderbooks: Arc<RwLock<HashMap<String, Orderbook>>>,
+    orderbooks: Arc<RwLock<HashMap<String, L2Book>>>,
+    orderbook_manager: OrderbookManager,
     write_tx: Arc<Mutex<Option<futures::channel::mpsc::UnboundedSender<Message>>>>,
     shutdown_tx: Arc<Mutex<Option<tokio::sync::oneshot::Sender<()>>>>,
     auto_reconnect: bool,
@@ -72,6 +77,7 @@ impl PolymarketWebSocket {
             subscriptions: Arc::new(RwLock::new(HashMap::new())),
             orderbook_senders: Arc::new(RwLock::new(HashMap::new())),
             orderbooks: Arc::new(RwLock::new(HashMap::new())),
+            orderbook_manager: OrderbookManager::new(),
             write_tx: Arc::new(Mutex::new(None)),
             shutdown_tx: Arc::new(Mutex::new(None)),
             auto_reconnect,
@@ -79,6 +85,17 @@ impl PolymarketWebSocket {
         }
     }
 
+    /// Publishes every book into `manager` instead of a private one, so
+    /// several clients can share a single store.
+    pub fn with_orderbook_manager(mut self, manager: OrderbookManager) -> Self {
+        self.orderbook_manager = manager;
+        self
+    }
+
+    pub fn orderbook_manager(&self) -> OrderbookManager {
+        self.orderbook_manager.clone()
+    }
+
     async fn reset_reconnect_attempts(&self) {
         let mut attempts = self.reconnect_attempts.lock().await;
         *attempts = 0;
@@ -132,54 +149,16 @@ impl PolymarketWebSocket {
 
         let market_id = msg.market.clone().unwrap_or_default();
 
-        let bids: Vec<PriceLevel> = msg
-            .bids
-            .as_ref()
-            .map(|b| {
-                b.iter()
-                    .filter_map(|l| {
-                        let price = l.price.parse::<f64>().ok()?;
-                        let size = l.size.parse::<f64>().ok()?;
-                        if price > 0.0 && size > 0.0 {
-                            Some(PriceLevel::new(price, size))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        let asks: Vec<PriceLevel> = msg
-            .asks
-            .as_ref()
-            .map(|a| {
-                a.iter()
-                    .filter_map(|l| {
-                        let price = l.price.parse::<f64>().ok()?;
-                        let size = l.size.parse::<f64>().ok()?;
-                        if price > 0.0 && size > 0.0 {
-                            Some(PriceLevel::new(price, size))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        let orderbook = Orderbook {
-            market_id: market_id.clone(),
-            asset_id: asset_id.clone(),
-            bids,
-            asks,
-            last_update_id: None,
-            timestamp: Some(chrono::Utc::now()),
-        };
+        let mut book = L2Book::new(asset_id.clone()).with_market_id(market_id);
+        book.apply_snapshot(
+            Self::parse_levels(msg.bids.as_deref()),
+            Self::parse_levels(msg.asks.as_deref()),
+        );
+        let orderbook = book.to_orderbook();
 
         {
             let mut obs = self.orderbooks.write().await;
-            obs.insert(asset_id.clone(), orderbook.clone());
+            obs.insert(asset_id.clone(), book);
         }
 
         self.broadcast_orderbook(&asset_id, orderbook).await;
@@ -195,39 +174,63 @@ impl PolymarketWebSocket {
             let asset_id = &change.asset_id;
 
             let mut obs = self.orderbooks.write().await;
-            if let Some(ob) = obs.get_mut(asset_id) {
-                if let Some(ref bid_str) = change.best_bid {
-                    if let Ok(price) = bid_str.parse::<f64>() {
-                        if price > 0.0 {
-                            if ob.bids.is_empty() {
-                                ob.bids.push(PriceLevel::new(price, 1.0));
-                            } else {
-                                ob.bids[0].price = price;
-                            }
-                        }
+            if let Some(book) = obs.get_mut(asset_id) {
+                let side = match change.side.as_deref() {
+                    Some("BUY") | Some("buy") => Some(BookSide::Bid),
+                    Some("SELL") | Some("sell") => Some(BookSide::Ask),
+                    _ => None,
+                };
+                let price = change.price.as_ref().and_then(|p| p.parse::<f64>().ok());
+                let size = change.size.as_ref().and_then(|s| s.parse::<f64>().ok());
+
+                if let (Some(side), Some(price), Some(size)) = (side, price, size) {
+                    book.apply_delta(side, price, size);
+                }
+
+                // The venue's reported top of book wins over stale local levels.
+                if let Some(bid) = change.best_bid.as_ref().and_then(|p| p.parse::<f64>().ok()) {
+                    if bid > 0.0 {
+                        book.remove_better_than(BookSide::Bid, bid);
                     }
                 }
-                if let Some(ref ask_str) = change.best_ask {
-                    if let Ok(price) = ask_str.parse::<f64>() {
-                        if price > 0.0 {
-                            if ob.asks.is_empty() {
-                                ob.asks.push(PriceLevel::new(price, 1.0));
-                            } else {
-                                ob.asks[0].price = price;
-                            }
-                        }
+                if let Some(ask) = change.best_ask.as_ref().and_then(|p| p.parse::<f64>().ok()) {
+                    if ask > 0.0 {
+                        book.remove_better_than(BookSide::Ask, ask);
                     }
                 }
-                ob.timestamp = Some(chrono::Utc::now());
 
-                let orderbook = ob.clone();
+                book.timestamp = Some(chrono::Utc::now());
+
+                if book.is_crossed() {
+                    tracing::warn!("crossed orderbook for asset {asset_id}");
+                }
+
+                let orderbook = book.to_orderbook();
                 drop(obs);
                 self.broadcast_orderbook(asset_id, orderbook).await;
             }
         }
     }
 
+    fn parse_levels(levels: Option<&[WsPriceLevel]>) -> Vec<PriceLevel> {
+        levels
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|l| {
+                let price = l.price.parse::<f64>().ok()?;
+                let size = l.size.parse::<f64>().ok()?;
+                if price > 0.0 && size > 0.0 {
+                    Some(PriceLevel::new(price, size))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
     async fn broadcast_orderbook(&self, asset_id: &str, orderbook: Orderbook) {
+        self.orderbook_manager.update(asset_id, orderbook.clone());
+
         let senders = self.orderbook_senders.read().await;
         if let Some(sender) = senders.get(asset_id) {
             let _ = sender.send(Ok(orderbook));
@@ -297,6 +300,7 @@ impl OrderBookWebSocket for PolymarketWebSocket {
             subscriptions: subscriptions.clone(),
             orderbook_senders: orderbook_senders.clone(),
             orderbooks: orderbooks.clone(),
+            orderbook_manager: self.orderbook_manager.clone(),
             write_tx: write_tx_clone.clone(),
             shutdown_tx: Arc::new(Mutex::new(None)),
             auto_reconnect: self.auto_reconnect,
@@ -463,7 +467,12 @@ impl OrderBookWebSocket for PolymarketWebSocket {
 
         {
             let mut subs = self.subscriptions.write().await;
-            subs.insert(market_id.to_string(), asset_ids.clone());
+            if subs
+                .insert(market_id.to_string(), asset_ids.clone())
+                .is_none()
+            {
+                self.orderbook_manager.acquire(market_id);
+            }
         }
 
         {
@@ -490,10 +499,10 @@ impl OrderBookWebSocket for PolymarketWebSocket {
     }
 
     async fn unsubscribe(&mut self, market_id: &str) -> Result<(), WebSocketError> {
-        {
+        let was_subscribed = {
             let mut subs = self.subscriptions.write().await;
-            subs.remove(market_id);
-        }
+            subs.remove(market_id).is_some()
+        };
         {
             let mut senders = self.orderbook_senders.write().await;
             senders.remove(market_id);
@@ -502,6 +511,9 @@ impl OrderBookWebSocket for PolymarketWebSocket {
             let mut obs = self.orderbooks.write().await;
             obs.remove(market_id);
         }
+        if was_subscribed {
+            self.orderbook_manager.release(market_id);
+        }
         Ok(())
     }
 
@@ -528,8 +540,7 @@ impl OrderBookWebSocket for PolymarketWebSocket {
 }
 
 pub fn get_orderbook_snapshot(ws: &PolymarketWebSocket, asset_id: &str) -> Option<Orderbook> {
-    futures::executor::block_on(async {
-        let obs = ws.orderbooks.read().await;
-        obs.get(asset_id).cloned()
-    })
+    ws.orderbook_manager
+        .get(asset_id)
+        .map(|ob| Orderbook::clone(&ob))
 }
@@ -1,5 +1,10 @@
+use drm_core::{DrmError, ExchangeError, OrderSide};
 use drm_core::{Exchange, FetchMarketsParams};
-use drm_exchange_polymarket::{Polymarket, PolymarketConfig};
+use drm_exchange_polymarket::{
+    ApiCredentials, ClobClient, ClobOrderSide, ClobOrderType, OrderArgs, Polymarket,
+    PolymarketConfig, SignatureType,
+};
+use std::collections::HashMap;
 use wiremock::matchers::{method, path, query_param};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -172,6 +177,41 @@ async fn test_fetch_market_by_id() {
     assert_eq!(*market.prices.get("No").unwrap(), 0.20);
 }
 
+#[tokio::test]
+async fn test_fetch_market_detects_neg_risk() {
+    // given
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/markets/neg-1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "neg-1",
+            "question": "Who will win the election?",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "outcomePrices": "[\"0.30\", \"0.70\"]",
+            "negRisk": true
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/markets/789"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sample_single_market_response()))
+        .mount(&mock_server)
+        .await;
+
+    let config = PolymarketConfig::new()
+        .with_gamma_url(mock_server.uri())
+        .with_verbose(false);
+    let exchange = Polymarket::new(config).unwrap();
+
+    // when
+    let neg_risk = exchange.fetch_market("neg-1").await.unwrap();
+    let standard = exchange.fetch_market("789").await.unwrap();
+
+    // then
+    assert!(Polymarket::is_neg_risk(&neg_risk));
+    assert!(!Polymarket::is_neg_risk(&standard));
+}
+
 #[tokio::test]
 async fn test_fetch_markets_by_slug() {
     // given
@@ -224,3 +264,392 @@ async fn test_exchange_id_and_name() {
     assert_eq!(exchange.id(), "polymarket");
     assert_eq!(exchange.name(), "Polymarket");
 }
+
+#[test]
+fn test_new_validates_signature_type() {
+    // given
+    let key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    let safe_without_funder = PolymarketConfig::new().with_private_key(key);
+    let eoa = PolymarketConfig::new()
+        .with_private_key(key)
+        .with_signature_type(SignatureType::Eoa);
+
+    // when
+    let safe_result = Polymarket::new(safe_without_funder);
+    let eoa_result = Polymarket::new(eoa);
+
+    // then
+    assert!(safe_result.is_err());
+    assert!(eoa_result.is_ok());
+}
+
+const TEST_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const TOKEN_ID: &str =
+    "71321045679252212594626385532706912750332728571942532289631379312455583992563";
+
+fn eoa_exchange() -> Polymarket {
+    let config = PolymarketConfig::new()
+        .with_private_key(TEST_KEY)
+        .with_signature_type(SignatureType::Eoa)
+        .with_verbose(false);
+    Polymarket::new(config).unwrap()
+}
+
+#[tokio::test]
+async fn test_create_order_rejects_gtd_without_expiration() {
+    // given
+    let exchange = eoa_exchange();
+    let params = HashMap::from([
+        ("token_id".to_string(), TOKEN_ID.to_string()),
+        ("order_type".to_string(), "GTD".to_string()),
+        ("neg_risk".to_string(), "false".to_string()),
+    ]);
+
+    // when
+    let result = exchange
+        .create_order("123", "Yes", OrderSide::Buy, 0.5, 10.0, params)
+        .await;
+
+    // then
+    assert!(matches!(
+        result,
+        Err(DrmError::Exchange(ExchangeError::InvalidOrder(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_create_order_rejects_expiration_on_gtc() {
+    // given
+    let exchange = eoa_exchange();
+    let params = HashMap::from([
+        ("token_id".to_string(), TOKEN_ID.to_string()),
+        ("expiration".to_string(), "1900000000".to_string()),
+        ("neg_risk".to_string(), "false".to_string()),
+    ]);
+
+    // when
+    let result = exchange
+        .create_order("123", "Yes", OrderSide::Buy, 0.5, 10.0, params)
+        .await;
+
+    // then
+    assert!(matches!(
+        result,
+        Err(DrmError::Exchange(ExchangeError::InvalidOrder(_)))
+    ));
+}
+
+#[tokio::test]
+async fn test_create_order_fails_when_neg_risk_unknown() {
+    // given
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/markets/123"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+    let config = PolymarketConfig::new()
+        .with_private_key(TEST_KEY)
+        .with_signature_type(SignatureType::Eoa)
+        .with_gamma_url(mock_server.uri())
+        .with_clob_url(mock_server.uri())
+        .with_verbose(false);
+    let exchange = Polymarket::new(config).unwrap();
+    let params = HashMap::from([("token_id".to_string(), TOKEN_ID.to_string())]);
+
+    // when
+    let result = exchange
+        .create_order("123", "Yes", OrderSide::Buy, 0.5, 10.0, params)
+        .await;
+
+    // then
+    match result {
+        Err(DrmError::Exchange(ExchangeError::InvalidOrder(msg))) => {
+            assert!(msg.contains("negRisk"))
+        }
+        other => panic!("expected InvalidOrder, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_create_order_signs_market_fee_rate() {
+    // given
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/fee-rate"))
+        .and(query_param("token_id", TOKEN_ID))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "base_fee": 200
+        })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = ClobClient::new(TEST_KEY, None, SignatureType::Eoa)
+        .unwrap()
+        .with_base_url(mock_server.uri());
+    let args = || OrderArgs {
+        token_id: TOKEN_ID.into(),
+        price: 0.4,
+        size: 25.0,
+        side: ClobOrderSide::Buy,
+        neg_risk: false,
+        expiration: 0,
+        nonce: 3,
+        fee_rate_bps: None,
+    };
+
+    // when
+    let first = client.create_order(args()).await.unwrap();
+    let second = client.create_order(args()).await.unwrap();
+
+    // then
+    assert_eq!(first.fee_rate_bps, "200");
+    assert_eq!(second.fee_rate_bps, "200");
+    assert_eq!(first.nonce, "3");
+    assert_ne!(first.salt, second.salt);
+}
+
+#[tokio::test]
+async fn test_fee_rate_rejection_refetches_fee_rate() {
+    // given
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/fee-rate"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "base_fee": 0
+        })))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/order"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": "invalid fee rate (0), current market's taker fee: 200"
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let mut client = ClobClient::new(TEST_KEY, None, SignatureType::Eoa)
+        .unwrap()
+        .with_base_url(mock_server.uri());
+    client.set_api_credentials(ApiCredentials {
+        api_key: "key".into(),
+        secret: "c2VjcmV0".into(),
+        passphrase: "pass".into(),
+    });
+    let args = || OrderArgs {
+        token_id: TOKEN_ID.into(),
+        price: 0.4,
+        size: 25.0,
+        side: ClobOrderSide::Buy,
+        neg_risk: false,
+        expiration: 0,
+        nonce: 0,
+        fee_rate_bps: None,
+    };
+
+    // when
+    let order = client.create_order(args()).await.unwrap();
+    let rejected = client.post_order(order, ClobOrderType::Gtc).await;
+    client.create_order(args()).await.unwrap();
+
+    // then
+    assert!(rejected.is_err());
+}
+
+fn sample_positions_response() -> serde_json::Value {
+    serde_json::json!([
+        {
+            "proxyWallet": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+            "asset": "111",
+            "conditionId": "0xcond1",
+            "size": 120.0,
+            "avgPrice": 0.42,
+            "initialValue": 50.4,
+            "currentValue": 66.0,
+            "cashPnl": 15.6,
+            "realizedPnl": 3.25,
+            "curPrice": 0.55,
+            "redeemable": false,
+            "title": "Will it rain tomorrow?",
+            "outcome": "Yes",
+            "outcomeIndex": 0,
+            "negativeRisk": false
+        },
+        {
+            "proxyWallet": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+            "asset": "222",
+            "conditionId": "0xcond2",
+            "size": 40.0,
+            "avgPrice": 0.9,
+            "currentValue": 40.0,
+            "realizedPnl": 0.0,
+            "curPrice": 1.0,
+            "redeemable": true,
+            "outcome": "No",
+            "outcomeIndex": 1
+        },
+        {
+            "asset": "333",
+            "conditionId": "0xcond3",
+            "size": 0.0,
+            "outcome": "Yes"
+        }
+    ])
+}
+
+#[tokio::test]
+async fn test_fetch_data_positions_lists_all_markets() {
+    // given
+    let funder = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/positions"))
+        .and(query_param("user", funder))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sample_positions_response()))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/markets"))
+        .and(query_param("condition_ids", "0xcond1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sample_gamma_markets()))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let config = PolymarketConfig::new()
+        .with_funder(funder)
+        .with_gamma_url(mock_server.uri())
+        .with_data_url(mock_server.uri())
+        .with_verbose(false);
+    let exchange = Polymarket::new(config).unwrap();
+
+    // when
+    let detailed = exchange.fetch_data_positions(&[]).await.unwrap();
+    let positions = exchange.fetch_positions(None).await.unwrap();
+
+    // then
+    assert_eq!(detailed.len(), 2);
+    assert_eq!(detailed[0].realized_pnl, 3.25);
+    assert_eq!(detailed[0].current_value, 66.0);
+    assert!(detailed[1].redeemable);
+    assert_eq!(positions.len(), 2);
+    assert_eq!(positions[1].market_id, "456");
+    assert_eq!(positions[1].average_price, 0.9);
+}
+
+fn sample_gamma_markets() -> serde_json::Value {
+    serde_json::json!([
+        {
+            "id": "123",
+            "question": "Will it rain tomorrow?",
+            "conditionId": "0xcond1",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "outcomePrices": "[\"0.55\", \"0.45\"]"
+        },
+        {
+            "id": "456",
+            "question": "Will it snow tomorrow?",
+            "conditionId": "0xcond2",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "outcomePrices": "[\"0.0\", \"1.0\"]"
+        }
+    ])
+}
+
+#[tokio::test]
+async fn test_fetch_positions_uses_same_market_id_on_both_paths() {
+    // given
+    let funder = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/markets/123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sample_gamma_markets()[0].clone()))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/markets"))
+        .and(query_param("condition_ids", "0xcond1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sample_gamma_markets()))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/positions"))
+        .and(query_param("market", "0xcond1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!([sample_positions_response()[0]])),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/positions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(sample_positions_response()))
+        .mount(&mock_server)
+        .await;
+
+    let config = PolymarketConfig::new()
+        .with_funder(funder)
+        .with_gamma_url(mock_server.uri())
+        .with_data_url(mock_server.uri())
+        .with_verbose(false);
+    let exchange = Polymarket::new(config).unwrap();
+
+    // when
+    let all = exchange.fetch_positions(None).await.unwrap();
+    let single = exchange.fetch_positions(Some("123")).await.unwrap();
+
+    // then
+    let from_all = all.iter().find(|p| p.outcome == "Yes").unwrap();
+    assert_eq!(single.len(), 1);
+    assert_eq!(from_all.market_id, "123");
+    assert_eq!(single[0].market_id, from_all.market_id);
+}
+
+#[tokio::test]
+async fn test_fetch_positions_for_market_uses_condition_id() {
+    // given
+    let funder = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/markets/123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": "123",
+            "question": "Will it rain tomorrow?",
+            "conditionId": "0xcond1",
+            "outcomes": "[\"Yes\", \"No\"]",
+            "outcomePrices": "[\"0.55\", \"0.45\"]"
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/positions"))
+        .and(query_param("market", "0xcond1"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!([sample_positions_response()[0]])),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let config = PolymarketConfig::new()
+        .with_private_key(TEST_KEY)
+        .with_funder(funder)
+        .with_gamma_url(mock_server.uri())
+        .with_data_url(mock_server.uri())
+        .with_verbose(false);
+    let exchange = Polymarket::new(config).unwrap();
+
+    // when
+    let positions = exchange.fetch_positions(Some("123")).await.unwrap();
+
+    // then
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].market_id, "123");
+    assert_eq!(positions[0].outcome, "Yes");
+    assert_eq!(positions[0].size, 120.0);
+    assert_eq!(positions[0].average_price, 0.42);
+    assert_eq!(positions[0].current_price, 0.55);
+}
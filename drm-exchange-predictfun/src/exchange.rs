@@ -7,7 +7,7 @@ use tokio::sync::Mutex;
 
 use drm_core::{
     DrmError, Exchange, ExchangeInfo, FetchMarketsParams, FetchOrdersParams, Market, Order,
-    OrderSide, OrderStatus, Position, RateLimiter,
+    OrderSide, OrderStatus, Orderbook, OrderbookSource, Position, RateLimiter,
 };
 
 use crate::config::{PredictFunConfig, PROTOCOL_NAME, PROTOCOL_VERSION};
@@ -1298,3 +1298,12 @@ impl Exchange for PredictFun {
         }
     }
 }
+
+#[async_trait]
+impl OrderbookSource for PredictFun {
+    async fn fetch_book(&self, market_id: &str) -> Result<Orderbook, DrmError> {
+        self.get_orderbook(market_id)
+            .await
+            .map_err(|e| DrmError::Exchange(e.into()))
+    }
+}